@@ -578,6 +578,10 @@ impl Item for AgentDiffPane {
         ToolbarItemLocation::PrimaryLeft
     }
 
+    fn has_breadcrumbs(&self, _: &App) -> bool {
+        true
+    }
+
     fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
         self.editor.breadcrumbs(theme, cx)
     }
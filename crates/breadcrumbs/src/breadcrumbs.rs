@@ -1,36 +1,607 @@
-use editor::Editor;
+mod breadcrumbs_panel;
+mod breadcrumbs_settings;
+
+pub use breadcrumbs_panel::BreadcrumbsPanel;
+pub use breadcrumbs_settings::{
+    BreadcrumbsContent, BreadcrumbsPanelSettings, BreadcrumbsSegmentOrder, BreadcrumbsSettings,
+    BreadcrumbsStripPrefix,
+};
+use editor::{BreadcrumbSymbolKind, DiffBase, Editor, EditorEvent};
+use file_icons::FileIcons;
 use gpui::{
-    Context, Element, EventEmitter, Focusable, FontWeight, IntoElement, ParentElement, Render,
-    StyledText, Subscription, Window,
+    Animation, AnimationExt, AnyElement, App, ClipboardItem, Context, Element, Entity,
+    EventEmitter, FocusHandle, Focusable, FontWeight, IntoElement, ParentElement, Render,
+    SharedString, StyledText, Subscription, Task, Window, actions, ease_out_quint,
 };
 use itertools::Itertools;
-use settings::Settings;
-use std::cmp;
-use theme::ActiveTheme;
+use settings::{Settings, SettingsStore};
+use std::{
+    cmp,
+    hash::{DefaultHasher, Hash, Hasher},
+    ops::Range,
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+use theme::{ActiveTheme, Theme};
 use ui::{ButtonLike, ButtonStyle, Label, Tooltip, prelude::*};
 use workspace::{
-    TabBarSettings, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView,
+    TabBarSettings, ToggleFileFinder, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView,
+    Workspace,
     item::{BreadcrumbText, ItemEvent, ItemHandle},
+    pane::render_item_indicator,
 };
 
+actions!(
+    breadcrumbs,
+    [
+        ActivateFocused,
+        CopyAllPaths,
+        CopyInnermostSegment,
+        CopyPath,
+        FocusNext,
+        FocusPrev,
+        GoToInnermostSymbolTest,
+        OpenInnermostSymbolInSplit,
+        ToggleBreadcrumbs
+    ]
+);
+
+const MAX_TYPE_HINT_LEN: usize = 40;
+const BLAME_AUTHOR_DEBOUNCE: Duration = Duration::from_millis(150);
+
+pub fn init(cx: &mut App) {
+    BreadcrumbsSettings::register(cx);
+    breadcrumbs_panel::init(cx);
+
+    cx.observe_new(|editor: &mut Editor, _, cx| register_editor(editor, cx))
+        .detach();
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(copy_all_paths);
+        workspace.register_action(copy_innermost_segment);
+        workspace.register_action(copy_path);
+        workspace.register_action(focus_next_segment);
+        workspace.register_action(focus_prev_segment);
+        workspace.register_action(go_to_innermost_symbol_test);
+        workspace.register_action(open_innermost_symbol_in_split);
+        workspace.register_action(toggle_breadcrumbs);
+    })
+    .detach();
+}
+
+/// The settings that change which symbols an editor reports in its breadcrumbs, which are
+/// applied to every editor, so that they also hold for editors that aren't active, such as
+/// those whose breadcrumbs are copied by [`CopyAllPaths`].
+#[derive(Clone, PartialEq)]
+struct EditorSymbolOptions {
+    max_depth: Option<usize>,
+    ellipsis: String,
+    leaf_kinds: Option<Vec<BreadcrumbSymbolKind>>,
+    show_type_hint: bool,
+}
+
+impl EditorSymbolOptions {
+    fn from_settings(cx: &App) -> Self {
+        let settings = BreadcrumbsSettings::get_global(cx);
+        Self {
+            max_depth: settings.max_depth,
+            ellipsis: settings.ellipsis.clone(),
+            leaf_kinds: settings.leaf_kind.clone(),
+            show_type_hint: settings.show_type_hint,
+        }
+    }
+
+    fn apply(&self, editor: &mut Editor) {
+        editor.set_breadcrumb_max_depth(self.max_depth);
+        editor.set_breadcrumb_ellipsis(self.ellipsis.clone());
+        editor.set_breadcrumb_leaf_kinds(self.leaf_kinds.clone());
+        editor.set_breadcrumb_type_hint(self.show_type_hint);
+    }
+}
+
+/// Applies the breadcrumbs settings that change which symbols the editor reports, now and
+/// whenever they change.
+fn register_editor(editor: &mut Editor, cx: &mut Context<Editor>) {
+    let mut symbol_options = EditorSymbolOptions::from_settings(cx);
+    symbol_options.apply(editor);
+    cx.observe_global::<SettingsStore>(move |editor, cx| {
+        let new_symbol_options = EditorSymbolOptions::from_settings(cx);
+        if new_symbol_options != symbol_options {
+            symbol_options = new_symbol_options;
+            symbol_options.apply(editor);
+            cx.emit(EditorEvent::BreadcrumbsChanged);
+        }
+    })
+    .detach();
+}
+
+/// Copies the breadcrumbs of every open editor, one per line.
+fn copy_all_paths(
+    workspace: &mut Workspace,
+    _: &CopyAllPaths,
+    _: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let theme = cx.theme().clone();
+    let separator = BreadcrumbsSettings::get_global(cx).separator.clone();
+    let paths = workspace
+        .items_of_type::<Editor>(cx)
+        .unique_by(|editor| editor.entity_id())
+        .filter_map(|editor| editor.breadcrumbs(&theme, cx))
+        .map(|segments| join_segments(&segments, &separator))
+        .join("\n");
+
+    if !paths.is_empty() {
+        cx.write_to_clipboard(ClipboardItem::new_string(paths));
+    }
+}
+
+/// Copies every breadcrumb of the active pane's item.
+fn copy_path(workspace: &mut Workspace, _: &CopyPath, _: &mut Window, cx: &mut Context<Workspace>) {
+    if let Some(breadcrumbs) = active_pane_breadcrumbs(workspace, cx) {
+        breadcrumbs.read(cx).copy_path(cx);
+    }
+}
+
+/// Copies the last breadcrumb of the active pane's item.
+fn copy_innermost_segment(
+    workspace: &mut Workspace,
+    _: &CopyInnermostSegment,
+    _: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if let Some(breadcrumbs) = active_pane_breadcrumbs(workspace, cx) {
+        breadcrumbs.read(cx).copy_innermost_segment(cx);
+    }
+}
+
+/// Focuses the next breadcrumb segment of the active pane, so that it can be navigated to
+/// from the keyboard.
+fn focus_next_segment(
+    workspace: &mut Workspace,
+    _: &FocusNext,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if let Some(breadcrumbs) = active_pane_breadcrumbs(workspace, cx) {
+        breadcrumbs.update(cx, |breadcrumbs, cx| {
+            breadcrumbs.focus_next_segment(window, cx)
+        });
+    }
+}
+
+/// Focuses the previous breadcrumb segment of the active pane.
+fn focus_prev_segment(
+    workspace: &mut Workspace,
+    _: &FocusPrev,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if let Some(breadcrumbs) = active_pane_breadcrumbs(workspace, cx) {
+        breadcrumbs.update(cx, |breadcrumbs, cx| {
+            breadcrumbs.focus_prev_segment(window, cx)
+        });
+    }
+}
+
+/// Opens the test of the innermost breadcrumb symbol of the active editor, letting the user
+/// pick one when several tests match the symbol's name.
+fn go_to_innermost_symbol_test(
+    workspace: &mut Workspace,
+    _: &GoToInnermostSymbolTest,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if let Some(editor) = workspace.active_item_as::<Editor>(cx) {
+        editor.update(cx, |editor, cx| {
+            editor
+                .go_to_innermost_symbol_test(window, cx)
+                .detach_and_log_err(cx);
+        });
+    }
+}
+
+/// Opens the definition of the innermost breadcrumb symbol of the active editor in a split.
+fn open_innermost_symbol_in_split(
+    workspace: &mut Workspace,
+    _: &OpenInnermostSymbolInSplit,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if let Some(editor) = workspace.active_item_as::<Editor>(cx) {
+        editor.update(cx, |editor, cx| {
+            editor
+                .go_to_innermost_symbol_definition_split(window, cx)
+                .detach_and_log_err(cx);
+        });
+    }
+}
+
+/// Shows or hides the breadcrumbs of the active pane only.
+fn toggle_breadcrumbs(
+    workspace: &mut Workspace,
+    _: &ToggleBreadcrumbs,
+    _: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    if let Some(breadcrumbs) = active_pane_breadcrumbs(workspace, cx) {
+        breadcrumbs.update(cx, |breadcrumbs, cx| breadcrumbs.toggle_visibility(cx));
+    }
+}
+
+fn active_pane_breadcrumbs(workspace: &Workspace, cx: &App) -> Option<Entity<Breadcrumbs>> {
+    workspace
+        .active_pane()
+        .read(cx)
+        .toolbar()
+        .read(cx)
+        .item_of_type::<Breadcrumbs>()
+}
+
+/// Returns the index that the segment at `index` has after [`elide_segments`] left
+/// `visible_count` of `segment_count` segments, replacing `hidden_segments` with an ellipsis,
+/// or `None` if the segment was elided.
+fn elided_segment_index(
+    index: usize,
+    segment_count: usize,
+    visible_count: usize,
+    hidden_segments: Option<Range<usize>>,
+) -> Option<usize> {
+    if visible_count == 1 && segment_count > 1 {
+        return (index + 1 == segment_count).then_some(0);
+    }
+    match hidden_segments {
+        Some(hidden_segments) if hidden_segments.contains(&index) => None,
+        Some(hidden_segments) if index >= hidden_segments.end => {
+            Some(index + 1 - hidden_segments.len())
+        }
+        _ => Some(index),
+    }
+}
+
+/// Replaces the middle segments with an ellipsis, keeping half of `max_segments` on either
+/// side of it, and returns the range of the segments it replaced. The ellipsis is at the start
+/// of this range. Limits below 2 keep only the last segment, without an ellipsis.
+///
+/// The first `pinned_prefix` segments are always kept, taking their place from the segments
+/// after the ellipsis when there are more of them than half of `max_segments`. The last
+/// segment is kept regardless.
+fn elide_segments(
+    segments: &mut Vec<BreadcrumbText>,
+    max_segments: usize,
+    pinned_prefix: usize,
+    ellipsis: &str,
+) -> Option<Range<usize>> {
+    if max_segments < 2 && pinned_prefix == 0 {
+        let last_ix = segments.len().saturating_sub(1);
+        segments.drain(..last_ix);
+        return None;
+    }
+
+    let prefix_len = cmp::max(max_segments / 2, pinned_prefix);
+    let suffix_len = cmp::min(max_segments / 2, max_segments.saturating_sub(prefix_len)).max(1);
+    let prefix_end_ix = cmp::min(segments.len(), prefix_len);
+    let suffix_start_ix = cmp::max(prefix_end_ix, segments.len().saturating_sub(suffix_len));
+
+    if suffix_start_ix > prefix_end_ix {
+        segments.splice(
+            prefix_end_ix..suffix_start_ix,
+            Some(BreadcrumbText {
+                text: ellipsis.into(),
+                highlights: None,
+                font: None,
+                type_hint: None,
+                source_range: None,
+            }),
+        );
+        Some(prefix_end_ix..suffix_start_ix)
+    } else {
+        None
+    }
+}
+
+/// Returns the author of the editor's cursor line, with a link to their profile when the
+/// Git hosting provider can derive their username from their email.
+fn blame_author_at_cursor(editor: &Entity<Editor>, cx: &mut App) -> Option<BlameAuthor> {
+    let (blame_entry, details) =
+        editor.update(cx, |editor, cx| editor.blame_entry_at_cursor(cx))?;
+    let profile_url = details
+        .and_then(|details| details.remote)
+        .zip(blame_entry.author_mail.as_deref())
+        .and_then(|(remote, author_email)| remote.author_url(author_email))
+        .map(|url| url.to_string());
+    Some(BlameAuthor {
+        name: blame_entry.author?.into(),
+        profile_url,
+    })
+}
+
+fn join_segments(segments: &[BreadcrumbText], separator: &str) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.text.as_str())
+        .join(&format!(" {separator} "))
+}
+
 pub struct Breadcrumbs {
     pane_focused: bool,
+    focus_handle: FocusHandle,
     active_item: Option<Box<dyn ItemHandle>>,
+    /// Whether the breadcrumbs are shown in this pane regardless of the item's settings,
+    /// as toggled by [`ToggleBreadcrumbs`].
+    visibility_override: Option<bool>,
+    /// Where the breadcrumbs are shown in the toolbar. Hidden breadcrumbs are not rendered,
+    /// so updates to the active item's breadcrumbs are ignored until they are shown again.
+    location: ToolbarItemLocation,
     subscription: Option<Subscription>,
+    /// The author of the active editor's cursor line, shown when `show_blame_author` is
+    /// enabled.
+    blame_author: Option<BlameAuthor>,
+    blame_author_task: Task<()>,
+    editor_subscription: Option<Subscription>,
+    /// Whether the segments elided beyond `max_segments` are shown, after clicking the
+    /// ellipsis that replaces them. Reset when the active item changes.
+    expanded: bool,
+    /// The index of the segment that is focused from the keyboard, among the segments that
+    /// can be navigated to. Reset when the active item changes.
+    focused_segment: Option<usize>,
+    /// The active item's breadcrumbs as of the last time they were rendered, so that they are
+    /// only computed again once the item reports that they changed.
+    cached_segments: Option<CachedSegments>,
 }
 
-impl Default for Breadcrumbs {
-    fn default() -> Self {
-        Self::new()
-    }
+/// The breadcrumbs of an item, along with the theme that they were highlighted with.
+struct CachedSegments {
+    theme: Arc<Theme>,
+    segments: Option<Vec<BreadcrumbText>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct BlameAuthor {
+    name: SharedString,
+    profile_url: Option<String>,
 }
 
 impl Breadcrumbs {
-    pub fn new() -> Self {
+    pub fn new(cx: &mut Context<Self>) -> Self {
         Self {
             pane_focused: false,
+            focus_handle: cx.focus_handle(),
             active_item: Default::default(),
+            visibility_override: None,
+            location: ToolbarItemLocation::Hidden,
             subscription: Default::default(),
+            blame_author: None,
+            blame_author_task: Task::ready(()),
+            editor_subscription: None,
+            expanded: false,
+            focused_segment: None,
+            cached_segments: None,
+        }
+    }
+
+    /// Shows or hides the breadcrumbs in this pane for the rest of the session, overriding
+    /// the default that comes from the settings.
+    pub fn toggle_visibility(&mut self, cx: &mut Context<Self>) {
+        let visible = match self.active_item {
+            Some(_) => self.location != ToolbarItemLocation::Hidden,
+            None => self.visibility_override.unwrap_or(true),
+        };
+        self.visibility_override = Some(!visible);
+        self.update_location(cx);
+        cx.notify();
+    }
+
+    /// Copies the active item's breadcrumbs, including any segments that are elided when
+    /// they are rendered.
+    pub fn copy_path(&self, cx: &mut App) {
+        if let Some(segments) = self.active_segments(cx) {
+            let separator = &BreadcrumbsSettings::get_global(cx).separator;
+            cx.write_to_clipboard(ClipboardItem::new_string(join_segments(
+                &segments, separator,
+            )));
+        }
+    }
+
+    /// Copies the last of the active item's breadcrumbs, which is its innermost symbol when
+    /// it has any.
+    pub fn copy_innermost_segment(&self, cx: &mut App) {
+        if let Some(segment) = self
+            .active_segments(cx)
+            .and_then(|mut segments| segments.pop())
+        {
+            cx.write_to_clipboard(ClipboardItem::new_string(segment.text));
+        }
+    }
+
+    /// Refreshes the blame author of the active editor's cursor line once the editor has
+    /// stopped changing for a moment, so that moving the cursor doesn't cause a lookup on
+    /// every step.
+    fn schedule_blame_author_update(&mut self, cx: &mut Context<Self>) {
+        if !BreadcrumbsSettings::get_global(cx).show_blame_author {
+            self.blame_author = None;
+            self.blame_author_task = Task::ready(());
+            return;
+        }
+
+        self.blame_author_task = cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(BLAME_AUTHOR_DEBOUNCE).await;
+            this.update(cx, |this, cx| {
+                let blame_author = this
+                    .active_item
+                    .as_ref()
+                    .and_then(|item| item.downcast::<Editor>())
+                    .and_then(|editor| blame_author_at_cursor(&editor, cx));
+                if blame_author != this.blame_author {
+                    this.blame_author = blame_author;
+                    cx.notify();
+                }
+            })
+            .ok();
+        });
+    }
+
+    /// Returns the active item's segments that can be navigated to, in the order they are
+    /// shown.
+    fn navigable_segments(&mut self, cx: &App) -> Vec<BreadcrumbText> {
+        let settings = BreadcrumbsSettings::get_global(cx);
+        if settings.content == BreadcrumbsContent::PathOnly {
+            return Vec::new();
+        }
+        let mut segments = self
+            .cached_active_segments(cx)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|segment| segment.source_range.is_some())
+            .collect::<Vec<_>>();
+        if settings.segment_order == BreadcrumbsSegmentOrder::SymbolsFirst {
+            segments.reverse();
+        }
+        segments
+    }
+
+    /// Focuses the breadcrumbs and moves the keyboard focus to the next segment that can be
+    /// navigated to, wrapping around after the last one.
+    pub fn focus_next_segment(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_segment_focus(true, window, cx);
+    }
+
+    /// Focuses the breadcrumbs and moves the keyboard focus to the previous segment that can
+    /// be navigated to, wrapping around before the first one.
+    pub fn focus_prev_segment(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.move_segment_focus(false, window, cx);
+    }
+
+    fn move_segment_focus(&mut self, forward: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let count = self.navigable_segments(cx).len();
+        if count == 0 {
+            self.focused_segment = None;
+            return;
+        }
+
+        self.focused_segment = Some(match self.focused_segment.filter(|ix| *ix < count) {
+            Some(ix) if forward => (ix + 1) % count,
+            Some(ix) => (ix + count - 1) % count,
+            None if forward => 0,
+            None => count - 1,
+        });
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    /// Navigates to the focused segment, as clicking it does, and returns the focus to the
+    /// editor.
+    pub fn activate_focused_segment(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(ix) = self.focused_segment.take() else {
+            return;
+        };
+        cx.notify();
+        let Some(editor) = self
+            .active_item
+            .as_ref()
+            .and_then(|item| item.downcast::<Editor>())
+        else {
+            return;
+        };
+        let Some(range) = self
+            .navigable_segments(cx)
+            .into_iter()
+            .nth(ix)
+            .and_then(|segment| segment.source_range)
+        else {
+            return;
+        };
+
+        editor.update(cx, |editor, cx| {
+            editor.go_to_breadcrumb_range(range, window, cx);
+        });
+        window.focus(&editor.focus_handle(cx));
+    }
+
+    /// Stops navigating the segments from the keyboard, returning the focus to the active item.
+    fn cancel_segment_focus(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.focused_segment = None;
+        if let Some(item) = self.active_item.as_ref() {
+            window.focus(&item.item_focus_handle(cx));
+        }
+        cx.notify();
+    }
+
+    /// Shows the segments that are hidden behind the ellipsis until the active item changes.
+    fn expand_hidden_segments(&mut self, cx: &mut Context<Self>) {
+        if !self.expanded {
+            self.expanded = true;
+            cx.notify();
+        }
+    }
+
+    /// Elides the middle segments as [`elide_segments`] does, unless they have been expanded.
+    fn elide_unexpanded_segments(
+        &self,
+        segments: &mut Vec<BreadcrumbText>,
+        max_segments: usize,
+        pinned_prefix: usize,
+        ellipsis: &str,
+    ) -> Option<Range<usize>> {
+        if self.expanded {
+            return None;
+        }
+        elide_segments(segments, max_segments, pinned_prefix, ellipsis)
+    }
+
+    /// Returns the active item's full breadcrumb trail, as the item reports it, without
+    /// stripping prefixes or eliding segments as they are when rendered.
+    pub fn active_segments(&self, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        match &self.cached_segments {
+            Some(cached) if Arc::ptr_eq(&cached.theme, cx.theme()) => cached.segments.clone(),
+            _ => self.active_item.as_ref()?.breadcrumbs(cx.theme(), cx),
+        }
+    }
+
+    /// Like [`Self::active_segments`], but remembers the segments until the active item
+    /// reports that they changed, another item becomes active, or the theme changes.
+    fn cached_active_segments(&mut self, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        let theme = cx.theme();
+        if self
+            .cached_segments
+            .as_ref()
+            .is_none_or(|cached| !Arc::ptr_eq(&cached.theme, theme))
+        {
+            self.cached_segments = Some(CachedSegments {
+                theme: theme.clone(),
+                segments: self.active_item.as_ref()?.breadcrumbs(theme, cx),
+            });
+        }
+        self.cached_segments.as_ref()?.segments.clone()
+    }
+
+    /// Returns where the breadcrumbs are shown in the toolbar, as last returned to it.
+    pub fn location(&self) -> ToolbarItemLocation {
+        self.location
+    }
+
+    /// Recomputes where the active item's breadcrumbs are shown, notifying the toolbar when
+    /// that changes.
+    fn update_location(&mut self, cx: &mut Context<Self>) {
+        let location = self
+            .active_item
+            .as_ref()
+            .map_or(ToolbarItemLocation::Hidden, |item| {
+                self.location_for(item.as_ref(), cx)
+            });
+        if location != self.location {
+            self.location = location;
+            cx.emit(ToolbarItemEvent::ChangeLocation(location));
+        }
+    }
+
+    fn location_for(&self, item: &dyn ItemHandle, cx: &App) -> ToolbarItemLocation {
+        match self.visibility_override {
+            None => item.breadcrumb_location(cx),
+            Some(false) => ToolbarItemLocation::Hidden,
+            Some(true) if item.has_breadcrumbs(cx) => ToolbarItemLocation::PrimaryLeft,
+            Some(true) => ToolbarItemLocation::Hidden,
         }
     }
 }
@@ -39,39 +610,150 @@ impl EventEmitter<ToolbarItemEvent> for Breadcrumbs {}
 
 impl Render for Breadcrumbs {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        const MAX_SEGMENTS: usize = 12;
-
         let element = h_flex()
             .id("breadcrumb-container")
+            .key_context("Breadcrumbs")
+            .track_focus(&self.focus_handle)
+            .on_action(
+                cx.listener(|this, _: &FocusNext, window, cx| this.focus_next_segment(window, cx)),
+            )
+            .on_action(
+                cx.listener(|this, _: &FocusPrev, window, cx| this.focus_prev_segment(window, cx)),
+            )
+            .on_action(cx.listener(|this, _: &ActivateFocused, window, cx| {
+                this.activate_focused_segment(window, cx)
+            }))
+            .on_action(cx.listener(|this, _: &menu::Cancel, window, cx| {
+                this.cancel_segment_focus(window, cx)
+            }))
             .flex_grow()
             .overflow_x_scroll()
             .text_ui(cx);
 
+        if self.location == ToolbarItemLocation::Hidden {
+            return element;
+        }
+        let Some(mut segments) = self.cached_active_segments(cx) else {
+            return element;
+        };
         let Some(active_item) = self.active_item.as_ref() else {
             return element;
         };
 
-        let Some(mut segments) = active_item.breadcrumbs(cx.theme(), cx) else {
-            return element;
+        // The first segment is the item's path, and the rest are its symbols.
+        let settings = BreadcrumbsSettings::get_global(cx);
+        let mut path = (!segments.is_empty()).then(|| segments.remove(0));
+        let mut symbols = segments;
+        match settings.content {
+            BreadcrumbsContent::PathAndSymbols => {}
+            BreadcrumbsContent::PathOnly => symbols.clear(),
+            BreadcrumbsContent::SymbolsOnly => path = None,
+        }
+        // The macro context isn't a symbol that can be navigated to, so it is shown after the
+        // segments rather than as one of them.
+        let macro_context = active_item
+            .downcast::<Editor>()
+            .filter(|_| {
+                settings.show_macro_context && settings.content != BreadcrumbsContent::PathOnly
+            })
+            .and_then(|editor| editor.read(cx).macro_context_at_cursor(cx));
+        if let Some(path) = path.as_mut() {
+            strip_path_prefix(
+                path,
+                settings.strip_prefix.prefixes(),
+                settings.show_stripped_prefix_indicator,
+            );
+        }
+        let path_segments = match (path, settings.file_path_segments) {
+            (Some(path), Some(directory_count)) if active_item.project_path(cx).is_some() => {
+                split_path_segment(path, directory_count)
+            }
+            (path, _) => path.into_iter().collect(),
         };
 
-        let prefix_end_ix = cmp::min(segments.len(), MAX_SEGMENTS / 2);
-        let suffix_start_ix = cmp::max(
-            prefix_end_ix,
-            segments.len().saturating_sub(MAX_SEGMENTS / 2),
-        );
+        let path_segment_count = path_segments.len();
+        let mut has_symbols = !symbols.is_empty();
+        let symbols_first = settings.segment_order == BreadcrumbsSegmentOrder::SymbolsFirst;
+        let mut segments = if symbols_first {
+            symbols.reverse();
+            symbols.extend(path_segments);
+            symbols
+        } else {
+            path_segments.into_iter().chain(symbols).collect::<Vec<_>>()
+        };
+        if segments.is_empty() {
+            return element;
+        }
 
-        if suffix_start_ix > prefix_end_ix {
-            segments.splice(
-                prefix_end_ix..suffix_start_ix,
-                Some(BreadcrumbText {
-                    text: "⋯".into(),
-                    highlights: None,
-                    font: None,
-                }),
-            );
+        let segment_count = segments.len();
+        // The file name is the last of the path segments, which come first or last.
+        let file_name_ix = (path_segment_count > 0).then(|| {
+            if symbols_first {
+                segment_count - 1
+            } else {
+                path_segment_count - 1
+            }
+        });
+        let full_texts = segments
+            .iter()
+            .map(|segment| segment.text.clone())
+            .collect::<Vec<_>>();
+        // Elided segments can't be seen to be focused, so they are all shown while the segments
+        // are navigated from the keyboard.
+        let is_navigating = self.focused_segment.is_some() && self.focus_handle.is_focused(window);
+        let hidden_segments = if is_navigating {
+            None
+        } else {
+            self.elide_unexpanded_segments(
+                &mut segments,
+                settings.max_segments,
+                settings.pinned_prefix,
+                &settings.ellipsis,
+            )
+        };
+        let separator = SharedString::from(settings.separator.clone());
+        if segments.len() == 1 && segment_count > 1 {
+            // Only the last segment is left, which is the path when the symbols come first.
+            has_symbols &= !symbols_first;
         }
 
+        // Replay the transition whenever the segments change, unless the user has asked the
+        // platform to reduce motion.
+        let animate = BreadcrumbsSettings::get_global(cx).animate && !cx.should_reduce_motion();
+        let transition_id = animate.then(|| {
+            let mut hasher = DefaultHasher::new();
+            for segment in &segments {
+                segment.text.hash(&mut hasher);
+            }
+            hasher.finish()
+        });
+
+        let path_ix = file_name_ix.and_then(|ix| {
+            elided_segment_index(ix, segment_count, segments.len(), hidden_segments.clone())
+        });
+        let leaf_ix = has_symbols.then(|| if symbols_first { 0 } else { segments.len() - 1 });
+        let show_type_hint = settings.show_type_hint;
+        let focused_range = self
+            .focused_segment
+            .filter(|_| is_navigating)
+            .and_then(|ix| self.navigable_segments(cx).into_iter().nth(ix))
+            .and_then(|segment| segment.source_range);
+        let focus_border_color = cx.theme().colors().border_focused;
+        let source_ranges = segments
+            .iter()
+            .map(|segment| segment.source_range.clone())
+            .collect::<Vec<_>>();
+        let tooltip_texts = segments
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| {
+                let type_hint = segment
+                    .type_hint
+                    .as_deref()
+                    .filter(|_| show_type_hint && Some(index) == leaf_ix);
+                segment_tooltip_text(&segment.text, type_hint)
+            })
+            .collect::<Vec<_>>();
         let highlighted_segments = segments.into_iter().enumerate().map(|(index, segment)| {
             let mut text_style = window.text_style();
             if let Some(ref font) = segment.font {
@@ -82,62 +764,312 @@ impl Render for Breadcrumbs {
             }
             text_style.color = Color::Muted.color(cx);
 
-            if index == 0 && !TabBarSettings::get_global(cx).show && active_item.is_dirty(cx) {
+            if Some(index) == path_ix
+                && !TabBarSettings::get_global(cx).show
+                && active_item.is_dirty(cx)
+            {
                 if let Some(styled_element) = apply_dirty_filename_style(&segment, &text_style, cx)
                 {
                     return styled_element;
                 }
             }
 
-            StyledText::new(segment.text.replace('\n', "⏎"))
-                .with_default_highlights(&text_style, segment.highlights.unwrap_or_default())
+            let mut text = segment_label_text(&segment.text);
+            let mut highlights = segment.highlights.unwrap_or_default();
+            let type_hint = segment
+                .type_hint
+                .filter(|_| show_type_hint && Some(index) == leaf_ix);
+            if let Some(type_hint) = type_hint {
+                let start = text.len();
+                text.push_str(": ");
+                text.push_str(&util::truncate_and_trailoff(&type_hint, MAX_TYPE_HINT_LEN));
+                highlights.push((
+                    start..text.len(),
+                    gpui::HighlightStyle {
+                        color: Some(Color::Placeholder.color(cx)),
+                        ..Default::default()
+                    },
+                ));
+            }
+
+            StyledText::new(text)
+                .with_default_highlights(&text_style, highlights)
                 .into_any()
         });
+        let file_finder_query = settings
+            .filename_opens_switcher
+            .then(|| active_item.project_path(cx))
+            .flatten()
+            .map(|project_path| {
+                let directory = project_path.path.parent().unwrap_or(Path::new(""));
+                if directory.as_os_str().is_empty() {
+                    String::new()
+                } else {
+                    format!("{}/", directory.to_string_lossy())
+                }
+            });
+        let segment_editor = active_item
+            .downcast::<Editor>()
+            .map(|editor| editor.downgrade());
+        let this = cx.entity().downgrade();
+        let path_icon = path_ix.and_then(|_| file_icon(active_item.as_ref(), cx));
+        // Clicking the file name opens the file finder, clicking a symbol jumps to it, rather
+        // than opening the outline below them, and clicking the ellipsis shows what it hides.
+        let highlighted_segments = highlighted_segments.enumerate().map(|(index, element)| {
+            let element = match path_icon.clone().filter(|_| Some(index) == path_ix) {
+                Some(icon) => h_flex()
+                    .gap_1()
+                    .child(
+                        Icon::from_path(icon)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(element)
+                    .into_any_element(),
+                None => element,
+            };
+            if let Some(hidden_segments) = hidden_segments
+                .clone()
+                .filter(|hidden_segments| hidden_segments.start == index)
+            {
+                let this = this.clone();
+                let title = format!("Show {} Hidden Segments", hidden_segments.len());
+                let hidden_texts = full_texts[hidden_segments].join("\n");
+                return ButtonLike::new("breadcrumb-ellipsis")
+                    .child(element)
+                    .style(ButtonStyle::Transparent)
+                    .tooltip(move |window, cx| {
+                        Tooltip::with_meta(title.clone(), None, hidden_texts.clone(), window, cx)
+                    })
+                    .on_click(move |_, _, cx| {
+                        cx.stop_propagation();
+                        this.update(cx, |this, cx| this.expand_hidden_segments(cx))
+                            .ok();
+                    })
+                    .into_any_element();
+            }
+
+            if let Some(query) = file_finder_query.clone().filter(|_| Some(index) == path_ix) {
+                return div()
+                    .id("breadcrumb-file-name")
+                    .cursor_pointer()
+                    .child(element)
+                    .on_click(move |_, window, cx| {
+                        cx.stop_propagation();
+                        let action = ToggleFileFinder {
+                            query: Some(query.clone()),
+                            ..Default::default()
+                        };
+                        window.dispatch_action(Box::new(action), cx);
+                    })
+                    .tooltip(|window, cx| {
+                        Tooltip::for_action("Open File", &ToggleFileFinder::default(), window, cx)
+                    })
+                    .into_any_element();
+            }
+
+            let Some((range, editor)) = source_ranges[index].clone().zip(segment_editor.clone())
+            else {
+                return element;
+            };
+            let focused = focused_range.as_ref() == Some(&range);
+            let segment = ButtonLike::new(("breadcrumb-segment", index))
+                .child(element)
+                .style(ButtonStyle::Transparent)
+                .tooltip(Tooltip::text(tooltip_texts[index].clone()))
+                .on_click(move |_, window, cx| {
+                    cx.stop_propagation();
+                    if let Some(editor) = editor.upgrade() {
+                        editor.update(cx, |editor, cx| {
+                            editor.go_to_breadcrumb_range(range.clone(), window, cx);
+                        });
+                    }
+                });
+            let segment = if focused {
+                div()
+                    .rounded_sm()
+                    .border_1()
+                    .border_color(focus_border_color)
+                    .child(segment)
+                    .into_any_element()
+            } else {
+                segment.into_any_element()
+            };
+            if Some(index) != leaf_ix {
+                return segment;
+            }
+
+            h_flex()
+                .group("breadcrumb-leaf")
+                .child(segment)
+                .child(
+                    IconButton::new("breadcrumb-go-to-test", IconName::Microscope)
+                        .icon_size(IconSize::XSmall)
+                        .icon_color(Color::Muted)
+                        .visible_on_hover("breadcrumb-leaf")
+                        .tooltip(|window, cx| {
+                            Tooltip::for_action("Go to Test", &GoToInnermostSymbolTest, window, cx)
+                        })
+                        .on_click(|_, window, cx| {
+                            cx.stop_propagation();
+                            window.dispatch_action(Box::new(GoToInnermostSymbolTest), cx);
+                        }),
+                )
+                .into_any_element()
+        });
         let breadcrumbs = Itertools::intersperse_with(highlighted_segments, || {
-            Label::new("›").color(Color::Placeholder).into_any_element()
+            Label::new(separator.clone())
+                .color(Color::Placeholder)
+                .into_any_element()
         });
 
-        let breadcrumbs_stack = h_flex().gap_1().children(breadcrumbs);
+        let settings = BreadcrumbsSettings::get_global(cx);
+        let dirty_indicator = settings
+            .show_dirty_indicator
+            .then(|| render_item_indicator(active_item.boxed_clone(), cx))
+            .flatten();
+        let file_metadata = settings
+            .show_file_metadata
+            .then(|| active_item.breadcrumb_metadata(cx))
+            .flatten()
+            .map(|metadata| {
+                Label::new(metadata)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+            });
 
-        match active_item
+        // The diff base only matters while the editor shows a diff.
+        let diff_base_indicator = active_item
             .downcast::<Editor>()
-            .map(|editor| editor.downgrade())
-        {
-            Some(editor) => element.child(
-                ButtonLike::new("toggle outline view")
-                    .child(breadcrumbs_stack)
-                    .style(ButtonStyle::Transparent)
-                    .on_click({
-                        let editor = editor.clone();
-                        move |_, window, cx| {
-                            if let Some((editor, callback)) = editor
-                                .upgrade()
-                                .zip(zed_actions::outline::TOGGLE_OUTLINE.get())
-                            {
-                                callback(editor.to_any(), window, cx);
+            .filter(|editor| editor.read(cx).is_showing_diff(cx))
+            .and_then(|editor| match editor.read(cx).diff_base() {
+                DiffBase::Head => None,
+                DiffBase::Index => Some("Unstaged"),
+                DiffBase::MergeBase => Some("Since Merge-Base"),
+            })
+            .map(|label| {
+                Label::new(label)
+                    .size(LabelSize::Small)
+                    .color(Color::Accent)
+            });
+
+        let macro_context = macro_context.map(|text| {
+            h_flex()
+                .gap_1()
+                .child(Label::new(separator.clone()).color(Color::Placeholder))
+                .child(Label::new(text).buffer_font(cx).color(Color::Muted))
+        });
+
+        let blame_author = self
+            .blame_author
+            .clone()
+            .filter(|_| settings.show_blame_author)
+            .map(|author| {
+                let label = Label::new(author.name).color(Color::Muted);
+                let author = match author.profile_url {
+                    Some(profile_url) => ButtonLike::new("breadcrumb-blame-author")
+                        .child(label)
+                        .style(ButtonStyle::Transparent)
+                        .tooltip(Tooltip::text("Open Author Profile"))
+                        .on_click(move |_, _, cx| {
+                            cx.stop_propagation();
+                            cx.open_url(&profile_url);
+                        })
+                        .into_any_element(),
+                    None => label.into_any_element(),
+                };
+                h_flex()
+                    .gap_1()
+                    .child(Label::new(separator.clone()).color(Color::Placeholder))
+                    .child(author)
+            });
+
+        let breadcrumbs_stack = h_flex()
+            .gap_1()
+            .children(breadcrumbs)
+            .children(macro_context)
+            .children(blame_author)
+            .children(dirty_indicator)
+            .children(file_metadata)
+            .children(diff_base_indicator);
+        // Only the opacity is animated, so that the layout and click targets stay put.
+        let breadcrumbs_stack = match transition_id {
+            Some(transition_id) => breadcrumbs_stack
+                .with_animation(
+                    ("breadcrumbs-transition", transition_id),
+                    Animation::new(AnimationDuration::Fast.into()).with_easing(ease_out_quint()),
+                    |this, delta| this.opacity(0.4 + 0.6 * delta),
+                )
+                .into_any_element(),
+            None => breadcrumbs_stack.into_any_element(),
+        };
+
+        let scope_diagnostics = active_item
+            .downcast::<Editor>()
+            .filter(|_| BreadcrumbsSettings::get_global(cx).show_scope_diagnostics)
+            .and_then(|editor| render_scope_diagnostics(editor, cx));
+
+        let outline_editor = active_item.downcast::<Editor>().map(|editor| {
+            let has_outline = editor.read(cx).has_outline(cx);
+            (editor.downgrade(), has_outline)
+        });
+        match outline_editor {
+            // Without an outline, the segments can still be clicked, but not the rest of the
+            // breadcrumbs, so that they don't open an empty outline.
+            Some((_, false)) => element
+                .child(
+                    ButtonLike::new("toggle outline view")
+                        .child(breadcrumbs_stack)
+                        .style(ButtonStyle::Transparent)
+                        .disabled(true)
+                        .tooltip(Tooltip::text("No Symbol Outline for This File")),
+                )
+                .children(scope_diagnostics),
+            Some((editor, true)) => element
+                .child(
+                    ButtonLike::new("toggle outline view")
+                        .child(breadcrumbs_stack)
+                        .style(ButtonStyle::Transparent)
+                        .on_click({
+                            let editor = editor.clone();
+                            move |event, window, cx| {
+                                if event.modifiers().secondary() {
+                                    if let Some(editor) = editor.upgrade() {
+                                        editor.update(cx, |editor, cx| {
+                                            editor
+                                                .go_to_innermost_symbol_definition_split(window, cx)
+                                                .detach_and_log_err(cx);
+                                        });
+                                    }
+                                } else if let Some((editor, callback)) = editor
+                                    .upgrade()
+                                    .zip(zed_actions::outline::TOGGLE_OUTLINE.get())
+                                {
+                                    callback(editor.to_any(), window, cx);
+                                }
                             }
-                        }
-                    })
-                    .tooltip(move |window, cx| {
-                        if let Some(editor) = editor.upgrade() {
-                            let focus_handle = editor.read(cx).focus_handle(cx);
-                            Tooltip::for_action_in(
-                                "Show Symbol Outline",
-                                &zed_actions::outline::ToggleOutline,
-                                &focus_handle,
-                                window,
-                                cx,
-                            )
-                        } else {
-                            Tooltip::for_action(
-                                "Show Symbol Outline",
-                                &zed_actions::outline::ToggleOutline,
-                                window,
-                                cx,
-                            )
-                        }
-                    }),
-            ),
+                        })
+                        .tooltip(move |window, cx| {
+                            if let Some(editor) = editor.upgrade() {
+                                let focus_handle = editor.read(cx).focus_handle(cx);
+                                Tooltip::for_action_in(
+                                    "Show Symbol Outline",
+                                    &zed_actions::outline::ToggleOutline,
+                                    &focus_handle,
+                                    window,
+                                    cx,
+                                )
+                            } else {
+                                Tooltip::for_action(
+                                    "Show Symbol Outline",
+                                    &zed_actions::outline::ToggleOutline,
+                                    window,
+                                    cx,
+                                )
+                            }
+                        }),
+                )
+                .children(scope_diagnostics),
             None => element
                 // Match the height and padding of the `ButtonLike` in the other arm.
                 .h(rems_from_px(22.))
@@ -147,6 +1079,50 @@ impl Render for Breadcrumbs {
     }
 }
 
+/// Renders the number of errors and warnings within the innermost symbol of the editor,
+/// which moves to the first of them when clicked.
+fn render_scope_diagnostics(editor: Entity<Editor>, cx: &App) -> Option<AnyElement> {
+    let summary = editor.read(cx).innermost_symbol_diagnostic_summary(cx);
+    if summary.is_empty() {
+        return None;
+    }
+
+    let editor = editor.downgrade();
+    Some(
+        ButtonLike::new("scope-diagnostics")
+            .style(ButtonStyle::Subtle)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .when(summary.error_count > 0, |this| {
+                        this.child(
+                            Icon::new(IconName::XCircle)
+                                .size(IconSize::Small)
+                                .color(Color::Error),
+                        )
+                        .child(Label::new(summary.error_count.to_string()).size(LabelSize::Small))
+                    })
+                    .when(summary.warning_count > 0, |this| {
+                        this.child(
+                            Icon::new(IconName::Warning)
+                                .size(IconSize::Small)
+                                .color(Color::Warning),
+                        )
+                        .child(Label::new(summary.warning_count.to_string()).size(LabelSize::Small))
+                    }),
+            )
+            .tooltip(Tooltip::text("Go to First Diagnostic in Scope"))
+            .on_click(move |_, window, cx| {
+                editor
+                    .update(cx, |editor, cx| {
+                        editor.go_to_first_innermost_symbol_diagnostic(window, cx)
+                    })
+                    .ok();
+            })
+            .into_any_element(),
+    )
+}
+
 impl ToolbarItemView for Breadcrumbs {
     fn set_active_pane_item(
         &mut self,
@@ -156,31 +1132,68 @@ impl ToolbarItemView for Breadcrumbs {
     ) -> ToolbarItemLocation {
         cx.notify();
         self.active_item = None;
+        self.expanded = false;
+        self.focused_segment = None;
+        self.blame_author = None;
+        self.blame_author_task = Task::ready(());
+        self.editor_subscription = None;
+        self.cached_segments = None;
 
         let Some(item) = active_pane_item else {
-            return ToolbarItemLocation::Hidden;
+            self.location = ToolbarItemLocation::Hidden;
+            return self.location;
         };
 
         let this = cx.entity().downgrade();
         self.subscription = Some(item.subscribe_to_item_events(
             window,
             cx,
-            Box::new(move |event, _, cx| {
-                if let ItemEvent::UpdateBreadcrumbs = event {
+            Box::new(move |event, _, cx| match event {
+                ItemEvent::UpdateBreadcrumbs => {
                     this.update(cx, |this, cx| {
-                        cx.notify();
-                        if let Some(active_item) = this.active_item.as_ref() {
-                            cx.emit(ToolbarItemEvent::ChangeLocation(
-                                active_item.breadcrumb_location(cx),
-                            ))
+                        this.cached_segments = None;
+                        this.update_location(cx);
+                        if this.location != ToolbarItemLocation::Hidden {
+                            cx.notify();
                         }
                     })
                     .ok();
                 }
+                ItemEvent::UpdateTab => {
+                    this.update(cx, |this, cx| {
+                        if this.location != ToolbarItemLocation::Hidden
+                            && BreadcrumbsSettings::get_global(cx).show_dirty_indicator
+                        {
+                            cx.notify();
+                        }
+                    })
+                    .ok();
+                }
+                _ => {}
             }),
         ));
+        if let Some(editor) = item.downcast::<Editor>() {
+            self.editor_subscription = Some(cx.subscribe(&editor, |this, _, event, cx| {
+                match event {
+                    // Only local selection changes are reported as breadcrumb updates, but the
+                    // symbols containing the cursor also change when a followed collaborator
+                    // moves it.
+                    EditorEvent::SelectionsChanged { .. } => {
+                        this.cached_segments = None;
+                        if this.location != ToolbarItemLocation::Hidden {
+                            cx.notify();
+                        }
+                        this.schedule_blame_author_update(cx);
+                    }
+                    EditorEvent::BlameUpdated => this.schedule_blame_author_update(cx),
+                    _ => {}
+                }
+            }));
+        }
         self.active_item = Some(item.boxed_clone());
-        item.breadcrumb_location(cx)
+        self.schedule_blame_author_update(cx);
+        self.location = self.location_for(item, cx);
+        self.location
     }
 
     fn pane_focus_update(
@@ -193,6 +1206,84 @@ impl ToolbarItemView for Breadcrumbs {
     }
 }
 
+/// Removes the longest of the given prefixes that the path segment starts with,
+/// matching whole path components only.
+fn strip_path_prefix(segment: &mut BreadcrumbText, prefixes: &[String], show_indicator: bool) {
+    let path = Path::new(segment.text.as_str());
+    let Some(rest) = prefixes
+        .iter()
+        .map(|prefix| prefix.trim_end_matches('/'))
+        .filter(|prefix| !prefix.is_empty())
+        .filter_map(|prefix| path.strip_prefix(prefix).ok())
+        .filter(|rest| !rest.as_os_str().is_empty())
+        .min_by_key(|rest| rest.as_os_str().len())
+    else {
+        return;
+    };
+
+    let rest = rest.to_string_lossy().to_string();
+    let indicator = if show_indicator { "…/" } else { "" };
+    let offset = segment.text.len() - rest.len();
+    if let Some(highlights) = segment.highlights.as_mut() {
+        highlights.retain_mut(|(range, _)| {
+            if range.end <= offset {
+                return false;
+            }
+            range.start = range.start.saturating_sub(offset) + indicator.len();
+            range.end = range.end - offset + indicator.len();
+            true
+        });
+    }
+    segment.text = format!("{indicator}{rest}");
+}
+
+/// Splits a file path segment into the file name preceded by up to `directory_count` of its
+/// parent directories, each as a separate segment.
+fn split_path_segment(segment: BreadcrumbText, directory_count: usize) -> Vec<BreadcrumbText> {
+    let components = Path::new(&segment.text)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>();
+    if components.len() < 2 {
+        return vec![segment];
+    }
+
+    let start_ix = components
+        .len()
+        .saturating_sub(directory_count.saturating_add(1));
+    components[start_ix..]
+        .iter()
+        .map(|component| BreadcrumbText {
+            text: component.clone(),
+            highlights: None,
+            font: segment.font.clone(),
+            type_hint: None,
+            source_range: None,
+        })
+        .collect()
+}
+
+/// Returns the text a segment is labeled with, which fits on a single line.
+fn segment_label_text(text: &str) -> String {
+    text.replace('\n', "⏎")
+}
+
+/// Returns the full text of a segment shown in its tooltip, with its line breaks and its
+/// whole type hint.
+fn segment_tooltip_text(text: &str, type_hint: Option<&str>) -> String {
+    match type_hint {
+        Some(type_hint) => format!("{text}: {type_hint}"),
+        None => text.to_string(),
+    }
+}
+
+/// Returns the icon of the active item's file type from the icon theme, if the item is backed
+/// by a file.
+fn file_icon(item: &dyn ItemHandle, cx: &App) -> Option<SharedString> {
+    let project_path = item.project_path(cx)?;
+    FileIcons::get_icon(&project_path.path, cx)
+}
+
 fn apply_dirty_filename_style(
     segment: &BreadcrumbText,
     text_style: &gpui::TextStyle,
@@ -235,3 +1326,603 @@ fn apply_dirty_filename_style(
             .into_any(),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::breadcrumbs_settings::{DEFAULT_ELLIPSIS, DEFAULT_SEPARATOR};
+    use editor::test::editor_lsp_test_context::EditorLspTestContext;
+    use gpui::TestAppContext;
+    use std::{cell::Cell, rc::Rc};
+    use workspace::item::{
+        Item,
+        test::{TestItem, TestProjectItem},
+    };
+
+    struct BreadcrumbsTestItem {
+        segments: Vec<&'static str>,
+        focus_handle: gpui::FocusHandle,
+        breadcrumbs_calls: Cell<usize>,
+    }
+
+    impl EventEmitter<ItemEvent> for BreadcrumbsTestItem {}
+
+    impl Focusable for BreadcrumbsTestItem {
+        fn focus_handle(&self, _: &App) -> gpui::FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl Render for BreadcrumbsTestItem {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            gpui::div().track_focus(&self.focus_handle)
+        }
+    }
+
+    impl Item for BreadcrumbsTestItem {
+        type Event = ItemEvent;
+
+        fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+            f(*event)
+        }
+
+        fn tab_content_text(&self, _: usize, _: &App) -> SharedString {
+            "Test".into()
+        }
+
+        fn has_breadcrumbs(&self, _: &App) -> bool {
+            true
+        }
+
+        fn breadcrumbs(&self, _: &theme::Theme, _: &App) -> Option<Vec<BreadcrumbText>> {
+            self.breadcrumbs_calls.set(self.breadcrumbs_calls.get() + 1);
+            Some(
+                self.segments
+                    .iter()
+                    .map(|segment| BreadcrumbText {
+                        text: segment.to_string(),
+                        highlights: None,
+                        font: None,
+                        type_hint: None,
+                        source_range: None,
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    #[gpui::test]
+    fn test_hidden_breadcrumbs_ignore_item_updates(cx: &mut TestAppContext) {
+        init_test(cx);
+        let cx = cx.add_empty_window();
+        let breadcrumbs = cx.new(Breadcrumbs::new);
+        let item = cx.new(TestItem::new);
+
+        let location = breadcrumbs.update_in(cx, |breadcrumbs, window, cx| {
+            breadcrumbs.set_active_pane_item(Some(&item as &dyn ItemHandle), window, cx)
+        });
+        assert_eq!(location, ToolbarItemLocation::Hidden);
+
+        let notify_count = Rc::new(Cell::new(0));
+        cx.update(|_, cx| {
+            let notify_count = notify_count.clone();
+            cx.observe(&breadcrumbs, move |_, _| {
+                notify_count.set(notify_count.get() + 1);
+            })
+            .detach();
+        });
+
+        item.update(cx, |_, cx| cx.emit(ItemEvent::UpdateBreadcrumbs));
+        item.update(cx, |_, cx| cx.emit(ItemEvent::UpdateTab));
+        cx.run_until_parked();
+        assert_eq!(notify_count.get(), 0);
+    }
+
+    /// Opens a Rust editor whose outline contains modules and functions.
+    async fn init_editor_test(cx: &mut TestAppContext) -> EditorLspTestContext {
+        let language = language::Language::new(
+            language::LanguageConfig {
+                name: "Rust".into(),
+                matcher: language::LanguageMatcher {
+                    path_suffixes: vec!["rs".to_string()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_outline_query(
+            r#"
+            (mod_item "mod" @context name: (_) @name) @item
+            (function_item "fn" @context name: (_) @name) @item
+            "#,
+        )
+        .unwrap();
+        let mut cx = EditorLspTestContext::new(language, Default::default(), cx).await;
+        cx.update(|_, cx| BreadcrumbsSettings::register(cx));
+        cx
+    }
+
+    #[gpui::test]
+    async fn test_cached_segments_follow_cursor(cx: &mut TestAppContext) {
+        let mut cx = init_editor_test(cx).await;
+        cx.set_state("fn first() {\n    ˇ1\n}\n\nfn second() {\n    2\n}\n");
+        cx.run_until_parked();
+
+        let breadcrumbs = cx.new(Breadcrumbs::new);
+        let editor = cx.editor.clone();
+        cx.update(|window, cx| {
+            breadcrumbs.update(cx, |breadcrumbs, cx| {
+                breadcrumbs.set_active_pane_item(Some(&editor as &dyn ItemHandle), window, cx)
+            })
+        });
+        let innermost_segment = |cx: &mut gpui::VisualTestContext| {
+            breadcrumbs.update(cx, |breadcrumbs, cx| {
+                breadcrumbs
+                    .cached_active_segments(cx)
+                    .and_then(|mut segments| segments.pop())
+                    .map(|segment| segment.text)
+            })
+        };
+        assert_eq!(innermost_segment(&mut cx), Some("fn first".to_string()));
+
+        cx.set_selections_state("fn first() {\n    1\n}\n\nfn second() {\n    ˇ2\n}\n");
+        cx.run_until_parked();
+        assert_eq!(innermost_segment(&mut cx), Some("fn second".to_string()));
+
+        cx.set_selections_state("fn first() {\n    ˇ1\n}\n\nfn second() {\n    2\n}\n");
+        cx.run_until_parked();
+        assert_eq!(innermost_segment(&mut cx), Some("fn first".to_string()));
+    }
+
+    #[gpui::test]
+    async fn test_symbol_settings_apply_to_editors(cx: &mut TestAppContext) {
+        let mut cx = init_editor_test(cx).await;
+        cx.update_editor(|editor, _, cx| register_editor(editor, cx));
+        cx.set_state("mod outer {\n    fn inner() {\n        ˇ1\n    }\n}\n");
+        cx.run_until_parked();
+
+        let breadcrumbs = cx.new(Breadcrumbs::new);
+        let editor = cx.editor.clone();
+        cx.update(|window, cx| {
+            breadcrumbs.update(cx, |breadcrumbs, cx| {
+                breadcrumbs.set_active_pane_item(Some(&editor as &dyn ItemHandle), window, cx)
+            })
+        });
+        let innermost_segment = |cx: &mut gpui::VisualTestContext| {
+            breadcrumbs.update(cx, |breadcrumbs, cx| {
+                breadcrumbs
+                    .cached_active_segments(cx)
+                    .and_then(|mut segments| segments.pop())
+                    .map(|segment| segment.text)
+            })
+        };
+        let set_max_depth = |max_depth: Option<usize>, cx: &mut gpui::VisualTestContext| {
+            cx.update(|_, cx| {
+                SettingsStore::update_global(cx, |store, cx| {
+                    store.update_user_settings::<BreadcrumbsSettings>(cx, |settings| {
+                        settings.max_depth = max_depth.map(Some);
+                    });
+                });
+            });
+            cx.run_until_parked();
+        };
+        assert_eq!(innermost_segment(&mut cx), Some("fn inner".to_string()));
+
+        // The cached segments are recomputed once the settings change, without switching
+        // items.
+        set_max_depth(Some(1), &mut cx);
+        assert_eq!(innermost_segment(&mut cx), Some("mod outer ⋯".to_string()));
+        set_max_depth(None, &mut cx);
+        assert_eq!(innermost_segment(&mut cx), Some("fn inner".to_string()));
+    }
+
+    #[gpui::test]
+    async fn test_keyboard_segment_navigation(cx: &mut TestAppContext) {
+        let mut cx = init_editor_test(cx).await;
+        cx.set_state("mod outer {\n    fn inner() {\n        ˇ1\n    }\n}\n");
+        cx.run_until_parked();
+
+        let breadcrumbs = cx.new(Breadcrumbs::new);
+        let editor = cx.editor.clone();
+        let set_active_item = |cx: &mut gpui::VisualTestContext| {
+            cx.update(|window, cx| {
+                breadcrumbs.update(cx, |breadcrumbs, cx| {
+                    breadcrumbs.set_active_pane_item(Some(&editor as &dyn ItemHandle), window, cx)
+                })
+            });
+        };
+        let focused_segment = |cx: &mut gpui::VisualTestContext| {
+            breadcrumbs.update(cx, |breadcrumbs, cx| {
+                let segments = breadcrumbs.navigable_segments(cx);
+                breadcrumbs
+                    .focused_segment
+                    .map(|ix| segments[ix].text.clone())
+            })
+        };
+        set_active_item(&mut cx);
+        assert_eq!(focused_segment(&mut cx), None);
+
+        let focus_next = |cx: &mut gpui::VisualTestContext| {
+            cx.update(|window, cx| {
+                breadcrumbs.update(cx, |breadcrumbs, cx| {
+                    breadcrumbs.focus_next_segment(window, cx)
+                })
+            });
+        };
+        focus_next(&mut cx);
+        assert_eq!(focused_segment(&mut cx), Some("mod outer".to_string()));
+        cx.update(|window, cx| {
+            assert!(breadcrumbs.read(cx).focus_handle.is_focused(window));
+        });
+        focus_next(&mut cx);
+        assert_eq!(focused_segment(&mut cx), Some("fn inner".to_string()));
+        focus_next(&mut cx);
+        assert_eq!(focused_segment(&mut cx), Some("mod outer".to_string()));
+        cx.update(|window, cx| {
+            breadcrumbs.update(cx, |breadcrumbs, cx| {
+                breadcrumbs.focus_prev_segment(window, cx)
+            })
+        });
+        assert_eq!(focused_segment(&mut cx), Some("fn inner".to_string()));
+
+        // Activating a segment navigates to it, as clicking it does.
+        cx.update(|window, cx| {
+            breadcrumbs.update(cx, |breadcrumbs, cx| {
+                breadcrumbs.activate_focused_segment(window, cx)
+            })
+        });
+        cx.assert_editor_state("mod outer {\n    ˇfn inner() {\n        1\n    }\n}\n");
+        assert_eq!(focused_segment(&mut cx), None);
+        cx.update(|window, cx| {
+            assert!(editor.focus_handle(cx).is_focused(window));
+        });
+
+        // Swapping the active item resets the focused segment.
+        focus_next(&mut cx);
+        assert!(focused_segment(&mut cx).is_some());
+        set_active_item(&mut cx);
+        assert_eq!(focused_segment(&mut cx), None);
+    }
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            BreadcrumbsSettings::register(cx);
+        });
+    }
+
+    #[gpui::test]
+    fn test_copy_path(cx: &mut TestAppContext) {
+        init_test(cx);
+        let cx = cx.add_empty_window();
+        let breadcrumbs = cx.new(Breadcrumbs::new);
+        let item = cx.new(|cx| BreadcrumbsTestItem {
+            segments: vec!["a", "b", "c"],
+            focus_handle: cx.focus_handle(),
+            breadcrumbs_calls: Cell::default(),
+        });
+        breadcrumbs.update_in(cx, |breadcrumbs, window, cx| {
+            breadcrumbs.set_active_pane_item(Some(&item as &dyn ItemHandle), window, cx)
+        });
+
+        cx.update(|_, cx| breadcrumbs.read(cx).copy_path(cx));
+        assert_eq!(
+            cx.read_from_clipboard().and_then(|item| item.text()),
+            Some("a › b › c".to_string())
+        );
+
+        cx.update(|_, cx| breadcrumbs.read(cx).copy_innermost_segment(cx));
+        assert_eq!(
+            cx.read_from_clipboard().and_then(|item| item.text()),
+            Some("c".to_string())
+        );
+    }
+
+    #[gpui::test]
+    fn test_custom_separator_and_ellipsis(cx: &mut TestAppContext) {
+        init_test(cx);
+        let cx = cx.add_empty_window();
+        let breadcrumbs = cx.new(Breadcrumbs::new);
+        let item = cx.new(|cx| BreadcrumbsTestItem {
+            segments: vec!["a", "b", "c", "d", "e", "f"],
+            focus_handle: cx.focus_handle(),
+            breadcrumbs_calls: Cell::default(),
+        });
+        breadcrumbs.update_in(cx, |breadcrumbs, window, cx| {
+            breadcrumbs.set_active_pane_item(Some(&item as &dyn ItemHandle), window, cx)
+        });
+
+        let set_separators = |separator: &str, ellipsis: &str, cx: &mut gpui::VisualTestContext| {
+            let (separator, ellipsis) = (separator.to_string(), ellipsis.to_string());
+            cx.update(|_, cx| {
+                SettingsStore::update_global(cx, |store, cx| {
+                    store.update_user_settings::<BreadcrumbsSettings>(cx, |settings| {
+                        settings.separator = Some(separator);
+                        settings.ellipsis = Some(ellipsis);
+                    });
+                });
+            });
+        };
+        let visible_segments = |cx: &mut gpui::VisualTestContext| {
+            breadcrumbs.read_with(cx, |breadcrumbs, cx| {
+                let settings = BreadcrumbsSettings::get_global(cx);
+                let mut segments = breadcrumbs.active_segments(cx).unwrap();
+                breadcrumbs.elide_unexpanded_segments(&mut segments, 4, 0, &settings.ellipsis);
+                join_segments(&segments, &settings.separator)
+            })
+        };
+
+        set_separators("/", "...", cx);
+        assert_eq!(visible_segments(cx), "a / b / ... / e / f");
+        cx.update(|_, cx| breadcrumbs.read(cx).copy_path(cx));
+        assert_eq!(
+            cx.read_from_clipboard().and_then(|item| item.text()),
+            Some("a / b / c / d / e / f".to_string())
+        );
+
+        // Empty strings fall back to the defaults.
+        set_separators("", "", cx);
+        assert_eq!(visible_segments(cx), "a › b › ⋯ › e › f");
+    }
+
+    #[gpui::test]
+    fn test_active_segments_and_location(cx: &mut TestAppContext) {
+        init_test(cx);
+        let cx = cx.add_empty_window();
+        let breadcrumbs = cx.new(Breadcrumbs::new);
+        breadcrumbs.read_with(cx, |breadcrumbs, cx| {
+            assert!(breadcrumbs.active_segments(cx).is_none());
+            assert_eq!(breadcrumbs.location(), ToolbarItemLocation::Hidden);
+        });
+
+        cx.update(|_, cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<BreadcrumbsSettings>(cx, |settings| {
+                    settings.max_segments = Some(4);
+                });
+            });
+        });
+        let item = cx.new(|cx| BreadcrumbsTestItem {
+            segments: vec!["a", "b", "c", "d", "e", "f"],
+            focus_handle: cx.focus_handle(),
+            breadcrumbs_calls: Cell::default(),
+        });
+        breadcrumbs.update_in(cx, |breadcrumbs, window, cx| {
+            breadcrumbs.set_active_pane_item(Some(&item as &dyn ItemHandle), window, cx);
+            breadcrumbs.toggle_visibility(cx);
+        });
+        // Showing the breadcrumbs doesn't require computing them.
+        assert_eq!(
+            item.read_with(cx, |item, _| item.breadcrumbs_calls.get()),
+            0
+        );
+
+        // The trail is returned in full, even though it is elided when rendered.
+        breadcrumbs.read_with(cx, |breadcrumbs, cx| {
+            let segments = breadcrumbs.active_segments(cx).unwrap();
+            assert_eq!(join_segments(&segments, "/"), "a / b / c / d / e / f");
+            assert_eq!(breadcrumbs.location(), ToolbarItemLocation::PrimaryLeft);
+        });
+    }
+
+    #[gpui::test]
+    fn test_file_icon(cx: &mut TestAppContext) {
+        init_test(cx);
+        let file_item = cx.new(|cx| {
+            TestItem::new(cx)
+                .with_singleton(true)
+                .with_project_items(&[TestProjectItem::new(1, "src/main.rs", cx)])
+        });
+        let non_file_item = cx.new(|cx| BreadcrumbsTestItem {
+            segments: vec!["Diagnostics"],
+            focus_handle: cx.focus_handle(),
+            breadcrumbs_calls: Cell::default(),
+        });
+
+        cx.update(|cx| {
+            assert!(file_icon(&file_item, cx).is_some());
+            assert_eq!(file_icon(&non_file_item, cx), None);
+        });
+    }
+
+    #[test]
+    fn test_segment_tooltip_text() {
+        let text = "fn parse(\n    input: &str,\n)";
+        assert_eq!(segment_label_text(text), "fn parse(⏎    input: &str,⏎)");
+        assert_eq!(segment_tooltip_text(text, None), text);
+        assert_ne!(segment_tooltip_text(text, None), segment_label_text(text));
+
+        let type_hint = "Result<HashMap<String, Vec<usize>>, ParseError>";
+        assert_eq!(
+            segment_tooltip_text("fn parse()", Some(type_hint)),
+            "fn parse(): Result<HashMap<String, Vec<usize>>, ParseError>"
+        );
+    }
+
+    #[test]
+    fn test_split_path_segment() {
+        let split = |path: &str, directory_count: usize| {
+            let segment = BreadcrumbText {
+                text: path.to_string(),
+                highlights: None,
+                font: None,
+                type_hint: None,
+                source_range: None,
+            };
+            split_path_segment(segment, directory_count)
+                .into_iter()
+                .map(|segment| segment.text)
+                .collect::<Vec<_>>()
+        };
+
+        let path = "crates/editor/src/display_map/mod.rs";
+        assert_eq!(split(path, 0), ["mod.rs"]);
+        assert_eq!(split(path, 1), ["display_map", "mod.rs"]);
+        assert_eq!(split(path, 3), ["editor", "src", "display_map", "mod.rs"]);
+        assert_eq!(split("src/mod.rs", 3), ["src", "mod.rs"]);
+        assert_eq!(split("mod.rs", 3), ["mod.rs"]);
+    }
+
+    #[test]
+    fn test_elided_segment_index() {
+        // Three path segments followed by three symbols, of which the middle two are elided.
+        let mut segments = (0..6)
+            .map(|ix| BreadcrumbText {
+                text: ix.to_string(),
+                highlights: None,
+                font: None,
+                type_hint: None,
+                source_range: None,
+            })
+            .collect::<Vec<_>>();
+        let hidden_segments = elide_segments(&mut segments, 4, 0, DEFAULT_ELLIPSIS);
+        assert_eq!(hidden_segments, Some(2..4));
+        let index = |ix| elided_segment_index(ix, 6, segments.len(), hidden_segments.clone());
+        assert_eq!(index(1), Some(1));
+        assert_eq!(index(2), None);
+        assert_eq!(index(4), Some(3));
+
+        assert_eq!(elided_segment_index(5, 6, 1, None), Some(0));
+        assert_eq!(elided_segment_index(2, 6, 1, None), None);
+    }
+
+    #[test]
+    fn test_elide_segments() {
+        let elided = |count: usize, max_segments: usize, pinned_prefix: usize| {
+            let mut segments = (0..count)
+                .map(|ix| BreadcrumbText {
+                    text: ix.to_string(),
+                    highlights: None,
+                    font: None,
+                    type_hint: None,
+                    source_range: None,
+                })
+                .collect::<Vec<_>>();
+            let hidden_segments =
+                elide_segments(&mut segments, max_segments, pinned_prefix, DEFAULT_ELLIPSIS);
+            if let Some(hidden_segments) = hidden_segments {
+                assert_eq!(segments[hidden_segments.start].text, DEFAULT_ELLIPSIS);
+                assert_eq!(segments.len() + hidden_segments.len() - 1, count);
+            }
+            segments
+                .into_iter()
+                .map(|segment| segment.text)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(elided(3, 12, 0), ["0", "1", "2"]);
+        assert_eq!(
+            elided(14, 12, 0),
+            [
+                "0", "1", "2", "3", "4", "5", "⋯", "8", "9", "10", "11", "12", "13"
+            ]
+        );
+        assert_eq!(elided(6, 4, 0), ["0", "1", "⋯", "4", "5"]);
+        assert_eq!(elided(4, 4, 0), ["0", "1", "2", "3"]);
+        assert_eq!(elided(6, 1, 0), ["5"]);
+        assert_eq!(elided(6, 0, 0), ["5"]);
+        assert_eq!(elided(0, 0, 0), Vec::<String>::new());
+
+        // Pinned segments are kept ahead of the ellipsis, shortening the segments after it.
+        assert_eq!(elided(14, 12, 2), elided(14, 12, 0));
+        assert_eq!(elided(20, 6, 4), ["0", "1", "2", "3", "⋯", "18", "19"]);
+        assert_eq!(elided(20, 4, 5), ["0", "1", "2", "3", "4", "⋯", "19"]);
+        assert_eq!(elided(6, 1, 2), ["0", "1", "⋯", "5"]);
+        assert_eq!(elided(5, 4, 8), ["0", "1", "2", "3", "4"]);
+    }
+
+    #[gpui::test]
+    fn test_expand_hidden_segments(cx: &mut TestAppContext) {
+        init_test(cx);
+        let cx = cx.add_empty_window();
+        let breadcrumbs = cx.new(Breadcrumbs::new);
+        let item = cx.new(|cx| BreadcrumbsTestItem {
+            segments: vec!["a", "b", "c", "d", "e", "f"],
+            focus_handle: cx.focus_handle(),
+            breadcrumbs_calls: Cell::default(),
+        });
+        let other_item = cx.new(|cx| BreadcrumbsTestItem {
+            segments: vec!["a"],
+            focus_handle: cx.focus_handle(),
+            breadcrumbs_calls: Cell::default(),
+        });
+        breadcrumbs.update_in(cx, |breadcrumbs, window, cx| {
+            breadcrumbs.set_active_pane_item(Some(&item as &dyn ItemHandle), window, cx);
+        });
+
+        let visible_segments = |cx: &mut gpui::VisualTestContext| {
+            breadcrumbs.read_with(cx, |breadcrumbs, cx| {
+                let mut segments = breadcrumbs.active_segments(cx).unwrap();
+                let hidden_segments =
+                    breadcrumbs.elide_unexpanded_segments(&mut segments, 4, 0, DEFAULT_ELLIPSIS);
+                (
+                    join_segments(&segments, DEFAULT_SEPARATOR),
+                    hidden_segments.map(|range| range.len()),
+                )
+            })
+        };
+        assert_eq!(
+            visible_segments(cx),
+            ("a › b › ⋯ › e › f".to_string(), Some(2))
+        );
+
+        breadcrumbs.update(cx, |breadcrumbs, cx| breadcrumbs.expand_hidden_segments(cx));
+        assert_eq!(
+            visible_segments(cx),
+            ("a › b › c › d › e › f".to_string(), None)
+        );
+
+        // Switching items collapses the segments again.
+        breadcrumbs.update_in(cx, |breadcrumbs, window, cx| {
+            breadcrumbs.set_active_pane_item(Some(&other_item as &dyn ItemHandle), window, cx);
+            breadcrumbs.set_active_pane_item(Some(&item as &dyn ItemHandle), window, cx);
+        });
+        assert_eq!(visible_segments(cx).1, Some(2));
+    }
+
+    #[gpui::test]
+    fn test_segments_are_cached_until_breadcrumbs_update(cx: &mut TestAppContext) {
+        init_test(cx);
+        let cx = cx.add_empty_window();
+        let breadcrumbs = cx.new(Breadcrumbs::new);
+        let item = cx.new(|cx| BreadcrumbsTestItem {
+            segments: vec!["a", "b", "c"],
+            focus_handle: cx.focus_handle(),
+            breadcrumbs_calls: Cell::default(),
+        });
+        breadcrumbs.update_in(cx, |breadcrumbs, window, cx| {
+            breadcrumbs.set_active_pane_item(Some(&item as &dyn ItemHandle), window, cx);
+        });
+
+        let breadcrumbs_calls = |cx: &mut gpui::VisualTestContext| {
+            item.read_with(cx, |item, _| item.breadcrumbs_calls.get())
+        };
+        let fetch_segments = |cx: &mut gpui::VisualTestContext| {
+            breadcrumbs.update(cx, |breadcrumbs, cx| {
+                let segments = breadcrumbs.cached_active_segments(cx).unwrap();
+                join_segments(&segments, "/")
+            })
+        };
+
+        let calls_before = breadcrumbs_calls(cx);
+        for _ in 0..3 {
+            assert_eq!(fetch_segments(cx), "a / b / c");
+        }
+        assert_eq!(breadcrumbs_calls(cx), calls_before + 1);
+
+        item.update(cx, |item, cx| {
+            item.segments = vec!["a", "b"];
+            cx.emit(ItemEvent::UpdateBreadcrumbs);
+        });
+        cx.run_until_parked();
+
+        let calls_before = breadcrumbs_calls(cx);
+        for _ in 0..3 {
+            assert_eq!(fetch_segments(cx), "a / b");
+        }
+        assert_eq!(breadcrumbs_calls(cx), calls_before + 1);
+    }
+}
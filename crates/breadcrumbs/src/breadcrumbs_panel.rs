@@ -0,0 +1,240 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use editor::Editor;
+use fs::Fs;
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable, Pixels,
+    Render, StyledText, Subscription, Task, WeakEntity, Window, actions, px,
+};
+use settings::Settings;
+use theme::ActiveTheme;
+use ui::{ListItem, prelude::*};
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+    item::{ItemEvent, ItemHandle},
+};
+
+use crate::{BreadcrumbsSettings, strip_path_prefix};
+
+actions!(breadcrumbs_panel, [ToggleFocus]);
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<BreadcrumbsPanel>(window, cx);
+        });
+    })
+    .detach();
+}
+
+/// Lists the breadcrumbs of the active item vertically, with each symbol indented below
+/// the one containing it.
+pub struct BreadcrumbsPanel {
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    active_item: Option<Box<dyn ItemHandle>>,
+    item_subscription: Option<Subscription>,
+    _workspace_subscription: Subscription,
+}
+
+impl BreadcrumbsPanel {
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            workspace.update_in(cx, |workspace, window, cx| Self::new(workspace, window, cx))
+        })
+    }
+
+    pub fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let active_item = workspace.active_item(cx);
+        let workspace_handle = cx.entity();
+
+        cx.new(|cx| {
+            let workspace_subscription = cx.subscribe_in(
+                &workspace_handle,
+                window,
+                |this, workspace, event: &workspace::Event, window, cx| {
+                    if let workspace::Event::ActiveItemChanged = event {
+                        let active_item = workspace.read(cx).active_item(cx);
+                        this.set_active_item(active_item, window, cx);
+                    }
+                },
+            );
+
+            let mut this = Self {
+                fs,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                active_item: None,
+                item_subscription: None,
+                _workspace_subscription: workspace_subscription,
+            };
+            this.set_active_item(active_item, window, cx);
+            this
+        })
+    }
+
+    fn set_active_item(
+        &mut self,
+        item: Option<Box<dyn ItemHandle>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let this = cx.entity().downgrade();
+        self.item_subscription = item.as_ref().map(|item| {
+            item.subscribe_to_item_events(
+                window,
+                cx,
+                Box::new(move |event, _, cx| {
+                    if let ItemEvent::UpdateBreadcrumbs = event {
+                        this.update(cx, |_, cx| cx.notify()).ok();
+                    }
+                }),
+            )
+        });
+        self.active_item = item;
+        cx.notify();
+    }
+}
+
+impl Render for BreadcrumbsPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let panel = v_flex()
+            .id("breadcrumbs-panel")
+            .key_context("BreadcrumbsPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .p_1()
+            .overflow_y_scroll()
+            .bg(cx.theme().colors().panel_background);
+
+        let Some(mut segments) = self
+            .active_item
+            .as_ref()
+            .and_then(|item| item.breadcrumbs(cx.theme(), cx))
+        else {
+            return panel.child(
+                Label::new("No breadcrumbs for the active item")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            );
+        };
+
+        let settings = BreadcrumbsSettings::get_global(cx);
+        if let Some(path) = segments.first_mut() {
+            strip_path_prefix(
+                path,
+                settings.strip_prefix.prefixes(),
+                settings.show_stripped_prefix_indicator,
+            );
+        }
+
+        let editor = self
+            .active_item
+            .as_ref()
+            .and_then(|item| item.downcast::<Editor>())
+            .map(|editor| editor.downgrade());
+        panel.children(segments.into_iter().enumerate().map(|(ix, segment)| {
+            let mut text_style = window.text_style();
+            if let Some(font) = segment.font {
+                text_style.font_family = font.family;
+                text_style.font_features = font.features;
+                text_style.font_style = font.style;
+                text_style.font_weight = font.weight;
+            }
+            let label = StyledText::new(segment.text.replace('\n', "⏎"))
+                .with_default_highlights(&text_style, segment.highlights.unwrap_or_default());
+
+            let editor = editor.clone();
+            ListItem::new(ix)
+                .indent_level(ix)
+                .indent_step_size(px(12.))
+                .child(label)
+                .on_click(move |_, window, cx| {
+                    let Some(editor) = editor.as_ref().and_then(|editor| editor.upgrade()) else {
+                        return;
+                    };
+                    // The first segment is the item's path, and the rest are its symbols.
+                    if ix == 0 {
+                        if let Some(callback) = zed_actions::outline::TOGGLE_OUTLINE.get() {
+                            callback(editor.to_any(), window, cx);
+                        }
+                    } else {
+                        editor.update(cx, |editor, cx| {
+                            editor.go_to_containing_symbol(ix - 1, window, cx);
+                            editor.focus_handle(cx).focus(window);
+                        });
+                    }
+                })
+        }))
+    }
+}
+
+impl Focusable for BreadcrumbsPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for BreadcrumbsPanel {}
+
+impl Panel for BreadcrumbsPanel {
+    fn persistent_name() -> &'static str {
+        "BreadcrumbsPanel"
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        BreadcrumbsSettings::get_global(cx).panel.dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file::<BreadcrumbsSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| settings.panel.get_or_insert_default().dock = Some(position),
+        );
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| BreadcrumbsSettings::get_global(cx).panel.default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        BreadcrumbsSettings::get_global(cx)
+            .panel
+            .button
+            .then_some(IconName::ListCollapse)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Breadcrumbs Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        10
+    }
+}
@@ -0,0 +1,222 @@
+use anyhow::Result;
+use editor::BreadcrumbSymbolKind;
+use gpui::{App, Pixels};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use workspace::dock::DockPosition;
+
+/// The separator shown between segments when none is configured.
+pub(crate) const DEFAULT_SEPARATOR: &str = "›";
+/// The ellipsis that replaces elided segments when none is configured.
+pub(crate) const DEFAULT_ELLIPSIS: &str = "⋯";
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BreadcrumbsSettings {
+    pub content: BreadcrumbsContent,
+    pub show_dirty_indicator: bool,
+    pub show_file_metadata: bool,
+    pub segment_order: BreadcrumbsSegmentOrder,
+    pub show_scope_diagnostics: bool,
+    pub strip_prefix: BreadcrumbsStripPrefix,
+    pub show_stripped_prefix_indicator: bool,
+    pub animate: bool,
+    pub show_type_hint: bool,
+    pub show_macro_context: bool,
+    pub filename_opens_switcher: bool,
+    pub max_segments: usize,
+    pub pinned_prefix: usize,
+    pub max_depth: Option<usize>,
+    pub leaf_kind: Option<Vec<BreadcrumbSymbolKind>>,
+    pub file_path_segments: Option<usize>,
+    pub separator: String,
+    pub ellipsis: String,
+    pub show_blame_author: bool,
+    pub panel: BreadcrumbsPanelSettings,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BreadcrumbsPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+/// Which kinds of segments are shown in the breadcrumbs.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BreadcrumbsContent {
+    /// Show the file path and the symbols containing the cursor.
+    #[default]
+    PathAndSymbols,
+    /// Show only the symbols containing the cursor, leaving the breadcrumbs
+    /// empty outside of any symbol.
+    SymbolsOnly,
+    /// Show only the file path.
+    PathOnly,
+}
+
+/// The order in which the file path and the symbols are shown in the breadcrumbs.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BreadcrumbsSegmentOrder {
+    /// Show the file path, followed by the symbols from outermost to innermost.
+    #[default]
+    PathFirst,
+    /// Show the symbols from innermost to outermost, followed by the file path.
+    SymbolsFirst,
+}
+
+/// One or more path prefixes to remove from the start of the breadcrumbs' file path.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(untagged)]
+pub enum BreadcrumbsStripPrefix {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl BreadcrumbsStripPrefix {
+    pub fn prefixes(&self) -> &[String] {
+        match self {
+            Self::One(prefix) => std::slice::from_ref(prefix),
+            Self::Many(prefixes) => prefixes,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct BreadcrumbsSettingsContent {
+    /// Which kinds of segments to show.
+    ///
+    /// Default: path_and_symbols
+    pub content: Option<BreadcrumbsContent>,
+    /// Whether to show an indicator next to the file name when the item has unsaved changes.
+    ///
+    /// Default: false
+    pub show_dirty_indicator: Option<bool>,
+    /// Whether to show a trailing segment describing non-text files, such as
+    /// the dimensions and size of an image.
+    ///
+    /// Default: false
+    pub show_file_metadata: Option<bool>,
+    /// The order in which the file path and the symbols are shown.
+    ///
+    /// Default: path_first
+    pub segment_order: Option<BreadcrumbsSegmentOrder>,
+    /// Whether to show the number of errors and warnings within the innermost
+    /// symbol containing the cursor.
+    ///
+    /// Default: false
+    pub show_scope_diagnostics: Option<bool>,
+    /// A path prefix, or a list of them, to remove from the start of the file path
+    /// when the path begins with it. The longest matching prefix is removed.
+    ///
+    /// Default: []
+    pub strip_prefix: Option<BreadcrumbsStripPrefix>,
+    /// Whether to show an ellipsis in place of a removed path prefix.
+    ///
+    /// Default: true
+    pub show_stripped_prefix_indicator: Option<bool>,
+    /// Whether to fade the breadcrumbs in when their segments change.
+    ///
+    /// The animation is skipped when the system is set to reduce motion.
+    ///
+    /// Default: false
+    pub animate: Option<bool>,
+    /// Whether to show the type of the innermost symbol, such as a function's
+    /// return type, dimmed after its name.
+    ///
+    /// Default: false
+    pub show_type_hint: Option<bool>,
+    /// Whether to show the macro invocation or attribute containing the cursor,
+    /// such as `vec!` or `#[derive]`, after the breadcrumbs. Only supported in Rust.
+    ///
+    /// Default: false
+    pub show_macro_context: Option<bool>,
+    /// Whether clicking the file name opens the file finder, prefilled with the file's
+    /// directory, instead of the symbol outline.
+    ///
+    /// Default: false
+    pub filename_opens_switcher: Option<bool>,
+    /// The number of segments to show before the middle ones are replaced with an
+    /// ellipsis. Values below 2 show only the last segment.
+    ///
+    /// Default: 12
+    pub max_segments: Option<usize>,
+    /// The number of leading segments, such as the module or namespace a symbol is in, that
+    /// are never replaced with the ellipsis. They are kept even beyond `max_segments`, leaving
+    /// fewer of the trailing segments; the last segment is always shown.
+    ///
+    /// Default: 0
+    pub pinned_prefix: Option<usize>,
+    /// The number of the outermost symbols containing the cursor to compute, which bounds
+    /// the work done in deeply nested code. Unlike `max_segments`, deeper symbols are not
+    /// computed at all. `null` computes every symbol.
+    ///
+    /// Default: null
+    pub max_depth: Option<Option<usize>>,
+    /// The kinds of symbols that the breadcrumbs end at, leaving out deeper symbols such as
+    /// closures: the innermost symbol of one of these kinds is the last one shown. Can contain
+    /// "function", "type" and "module". `null` shows symbols of every kind.
+    ///
+    /// Default: null
+    pub leaf_kind: Option<Option<Vec<BreadcrumbSymbolKind>>>,
+    /// The number of the file's parent directories to show before its name, each as a
+    /// separate segment, instead of the whole path as one segment. Like the other segments,
+    /// they are elided beyond `max_segments`. `null` shows the whole path.
+    ///
+    /// Default: null
+    pub file_path_segments: Option<Option<usize>>,
+    /// The text shown between segments. Empty text shows the default separator.
+    ///
+    /// Default: "›"
+    pub separator: Option<String>,
+    /// The text that replaces the middle segments beyond `max_segments`. Empty text shows
+    /// the default ellipsis.
+    ///
+    /// Default: "⋯"
+    pub ellipsis: Option<String>,
+    /// Whether to show the author of the cursor line, from the editor's Git blame, after
+    /// the other segments. Clicking the author opens their profile on the Git hosting
+    /// provider when it is known.
+    ///
+    /// Default: false
+    pub show_blame_author: Option<bool>,
+    /// Settings for the panel that lists the breadcrumbs vertically.
+    pub panel: Option<BreadcrumbsPanelSettingsContent>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct BreadcrumbsPanelSettingsContent {
+    /// Whether to show the breadcrumbs panel button in the status bar.
+    ///
+    /// Default: false
+    pub button: Option<bool>,
+    /// Where to dock the panel.
+    ///
+    /// Default: right
+    pub dock: Option<DockPosition>,
+    /// Default width of the panel in pixels.
+    ///
+    /// Default: 240
+    pub default_width: Option<f32>,
+}
+
+impl Settings for BreadcrumbsSettings {
+    const KEY: Option<&'static str> = Some("breadcrumbs");
+
+    type FileContent = BreadcrumbsSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut App) -> Result<Self> {
+        let mut settings: Self = sources.json_merge()?;
+        if settings.separator.is_empty() {
+            settings.separator = DEFAULT_SEPARATOR.to_string();
+        }
+        if settings.ellipsis.is_empty() {
+            settings.ellipsis = DEFAULT_ELLIPSIS.to_string();
+        }
+        Ok(settings)
+    }
+
+    fn import_from_vscode(_: &settings::VsCodeSettings, _: &mut Self::FileContent) {}
+}
@@ -273,6 +273,35 @@ impl BufferDiffSnapshot {
         &self.inner.base_text
     }
 
+    /// Returns the row of the base text that corresponds to the given buffer row. Rows within
+    /// a hunk map to the first row of the hunk's base text.
+    pub fn base_text_row_for_buffer_row(&self, row: u32, buffer: &text::BufferSnapshot) -> u32 {
+        let base_text = &self.inner.base_text;
+        let mut delta = 0i64;
+        for hunk in self.hunks_intersecting_range(Anchor::MIN..Anchor::MAX, buffer) {
+            if hunk.range.start.row > row {
+                break;
+            }
+            let base_range = base_text.offset_to_point(hunk.diff_base_byte_range.start)
+                ..base_text.offset_to_point(hunk.diff_base_byte_range.end);
+            if row < hunk.range.end.row {
+                return base_range.start.row;
+            }
+            delta = base_range.end.row as i64 - hunk.range.end.row as i64;
+        }
+        (row as i64 + delta).clamp(0, base_text.max_point().row as i64) as u32
+    }
+
+    /// Returns the row of the base text that the given buffer row is unchanged from, or `None`
+    /// if the row was added or modified.
+    pub fn unchanged_base_text_row(&self, row: u32, buffer: &text::BufferSnapshot) -> Option<u32> {
+        let changed = self
+            .hunks_intersecting_range(Anchor::MIN..Anchor::MAX, buffer)
+            .take_while(|hunk| hunk.range.start.row <= row)
+            .any(|hunk| row < hunk.range.end.row);
+        (!changed).then(|| self.base_text_row_for_buffer_row(row, buffer))
+    }
+
     pub fn base_texts_eq(&self, other: &Self) -> bool {
         if self.inner.base_text_exists != other.inner.base_text_exists {
             return false;
@@ -1399,6 +1428,43 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_base_text_row_for_buffer_row(cx: &mut TestAppContext) {
+        let diff_base = "
+            one
+            two
+            three
+            four
+            five
+        "
+        .unindent();
+
+        let buffer_text = "
+            zero
+            one
+            TWO
+            three
+            five
+            six
+        "
+        .unindent();
+
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), buffer_text);
+        let diff = BufferDiffSnapshot::new_sync(buffer.clone(), diff_base.clone(), cx);
+        let base_rows = (0..=6)
+            .map(|row| diff.base_text_row_for_buffer_row(row, &buffer))
+            .collect::<Vec<_>>();
+        assert_eq!(base_rows, [0, 0, 1, 2, 4, 5, 5]);
+
+        let unchanged_rows = (0..=6)
+            .map(|row| diff.unchanged_base_text_row(row, &buffer))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            unchanged_rows,
+            [None, Some(0), None, Some(2), Some(4), None, Some(5)]
+        );
+    }
+
     #[gpui::test]
     async fn test_buffer_diff_with_secondary(cx: &mut gpui::TestAppContext) {
         let head_text = "
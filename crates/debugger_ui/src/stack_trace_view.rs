@@ -436,6 +436,10 @@ impl Item for StackTraceView {
         ToolbarItemLocation::PrimaryLeft
     }
 
+    fn has_breadcrumbs(&self, _: &App) -> bool {
+        true
+    }
+
     fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
         self.editor.breadcrumbs(theme, cx)
     }
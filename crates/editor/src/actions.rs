@@ -261,6 +261,7 @@ actions!(
         CancelFlycheck,
         CancelLanguageServerWork,
         ClearFlycheck,
+        CollapseAllDiffHunks,
         ConfirmRename,
         ConfirmCompletionInsert,
         ConfirmCompletionReplace,
@@ -284,12 +285,15 @@ actions!(
         CopyHighlightJson,
         CopyFileName,
         CopyFileNameWithoutExtension,
+        CopyDiffStats,
         CopyPermalinkToLine,
         Cut,
         CutToEndOfLine,
+        CycleDiffBase,
         Delete,
         DeleteLine,
         DeleteToEndOfLine,
+        DiffQuickActions,
         DeleteToNextSubwordEnd,
         DeleteToPreviousSubwordStart,
         DisplayCursorNames,
@@ -359,6 +363,7 @@ actions!(
         NewlineBelow,
         NextEditPrediction,
         NextScreen,
+        OpenBlameToLine,
         OpenContextMenu,
         OpenExcerpts,
         OpenExcerptsSplit,
@@ -367,6 +372,7 @@ actions!(
         OpenPermalinkToLine,
         OpenSelectionsInMultibuffer,
         OpenUrl,
+        OpenReferencedIssueOrPullRequest,
         OrganizeImports,
         Outdent,
         AutoIndent,
@@ -413,12 +419,14 @@ actions!(
         SelectToStartOfParagraph,
         SelectUp,
         ShowCharacterPalette,
+        ShowCodeOwners,
         ShowEditPrediction,
         ShowSignatureHelp,
         ShowWordCompletions,
         ShuffleLines,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
+        SplitDiffIntoTabs,
         SplitSelectionIntoLines,
         StopLanguageServer,
         SwitchSourceHeader,
@@ -433,6 +441,8 @@ actions!(
         ToggleGitBlameInline,
         OpenGitBlameCommit,
         ToggleDiagnostics,
+        ToggleDiffBase,
+        ToggleDiffTabsScrollSync,
         ToggleIndentGuides,
         ToggleInlayHints,
         ToggleInlineValues,
@@ -64,8 +64,8 @@ use dap::TelemetrySpawnLocation;
 use display_map::*;
 pub use display_map::{ChunkRenderer, ChunkRendererContext, DisplayPoint, FoldPlaceholder};
 pub use editor_settings::{
-    CurrentLineHighlight, DocumentColorsRenderMode, EditorSettings, HideMouseMode,
-    ScrollBeyondLastLine, ScrollbarAxes, SearchSettings, ShowScrollbar,
+    BreadcrumbSymbolKind, CurrentLineHighlight, DocumentColorsRenderMode, EditorSettings,
+    HideMouseMode, ScrollBeyondLastLine, ScrollbarAxes, SearchSettings, ShowScrollbar,
 };
 use editor_settings::{GoToDefinitionFallback, Minimap as MinimapSettings};
 pub use editor_settings_controls::*;
@@ -163,12 +163,12 @@ use multi_buffer::{
 };
 use parking_lot::Mutex;
 use project::{
-    CodeAction, Completion, CompletionIntent, CompletionSource, DocumentHighlight, InlayHint,
-    Location, LocationLink, PrepareRenameResponse, Project, ProjectItem, ProjectTransaction,
-    TaskSourceKind,
+    CodeAction, Completion, CompletionIntent, CompletionSource, DiagnosticSummary,
+    DocumentHighlight, InlayHint, Location, LocationLink, PrepareRenameResponse, Project,
+    ProjectItem, ProjectTransaction, TaskSourceKind,
     debugger::breakpoint_store::Breakpoint,
     lsp_store::{CompletionDocumentation, FormatTrigger, LspFormatTarget, OpenLspBufferHandle},
-    project_settings::{GitGutterSetting, ProjectSettings},
+    project_settings::{GitGutterSetting, GitHunkNumbering, ProjectSettings},
 };
 use rand::prelude::*;
 use rpc::{ErrorExt, proto::*};
@@ -184,7 +184,7 @@ use std::sync::Arc;
 use std::{
     any::TypeId,
     borrow::Cow,
-    cell::RefCell,
+    cell::{Cell, RefCell},
     cmp::{self, Ordering, Reverse},
     mem,
     num::NonZeroU32,
@@ -234,6 +234,7 @@ pub(crate) const CURSORS_VISIBLE_FOR: Duration = Duration::from_millis(2000);
 #[doc(hidden)]
 pub const CODE_ACTIONS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
 const SELECTION_HIGHLIGHT_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(100);
+const MERGE_BASE_DIFF_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
 
 pub(crate) const CODE_ACTION_TIMEOUT: Duration = Duration::from_secs(5);
 pub(crate) const FORMAT_TIMEOUT: Duration = Duration::from_secs(5);
@@ -299,6 +300,7 @@ enum DocumentHighlightWrite {}
 enum InputComposition {}
 pub enum PendingInput {}
 enum SelectedTextHighlight {}
+enum DiffHunkFold {}
 
 pub enum ConflictsOuter {}
 pub enum ConflictsOurs {}
@@ -1040,6 +1042,9 @@ pub struct Editor {
     linked_editing_range_task: Option<Task<Option<()>>>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
     pending_rename: Option<RenameState>,
+    optimistic_rename: Option<OptimisticRename>,
+    /// The numbers shown in the controls of diff hunks, keyed by the start of each hunk.
+    hunk_numbers: HashMap<Anchor, usize>,
     searchable: bool,
     cursor_shape: CursorShape,
     current_line_highlight: Option<CurrentLineHighlight>,
@@ -1049,6 +1054,7 @@ pub struct Editor {
     input_enabled: bool,
     use_modal_editing: bool,
     read_only: bool,
+    allow_staging_while_read_only: bool,
     leader_id: Option<CollaboratorId>,
     remote_id: Option<ViewId>,
     pub hover_state: HoverState,
@@ -1114,6 +1120,10 @@ pub struct Editor {
     in_project_search: bool,
     previous_search_ranges: Option<Arc<[Range<Anchor>]>>,
     breadcrumb_header: Option<String>,
+    breadcrumb_max_depth: Option<usize>,
+    breadcrumb_ellipsis: Option<String>,
+    breadcrumb_leaf_kinds: Option<Vec<BreadcrumbSymbolKind>>,
+    breadcrumb_type_hint: bool,
     focused_block: Option<FocusedBlock>,
     next_scroll_position: NextScrollCursorCenterTopBottom,
     addons: HashMap<TypeId, Box<dyn Addon>>,
@@ -1121,6 +1131,9 @@ pub struct Editor {
     load_diff_task: Option<Shared<Task<()>>>,
     /// Whether we are temporarily displaying a diff other than git's
     temporary_diff_override: bool,
+    diff_base: DiffBase,
+    refresh_merge_base_diff_task: Task<()>,
+    diff_scroll_sync: Option<DiffScrollSync>,
     selection_mark_mode: bool,
     toggle_fold_multiple_buffers: Task<()>,
     _scroll_cursor_center_top_bottom_task: Task<()>,
@@ -1137,6 +1150,27 @@ pub struct Editor {
     colors: Option<LspColorData>,
 }
 
+/// The git revision that an editor's diff hunks are computed against.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
+pub enum DiffBase {
+    /// Show all uncommitted changes, compared to HEAD.
+    #[default]
+    Head,
+    /// Show unstaged changes, compared to the index.
+    Index,
+    /// Show all changes made on the current branch, compared to its merge-base with the
+    /// branch's upstream.
+    MergeBase,
+}
+
+/// Links the editor of a buffer with the editor of its diff base opened by
+/// [`Editor::split_diff_into_tabs`], so that the latter follows the former's scroll position.
+struct DiffScrollSync {
+    enabled: Rc<Cell<bool>>,
+    /// Only set on the editor of the diff base, which scrolls along with the buffer's editor.
+    _leader_subscription: Option<Subscription>,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Default)]
 enum NextScrollCursorCenterTopBottom {
     #[default]
@@ -1456,6 +1490,18 @@ pub struct RenameState {
     block_id: CustomBlockId,
 }
 
+/// A rename whose new name is shown in the breadcrumbs before the buffer is reparsed.
+struct OptimisticRename {
+    /// The name being replaced, which is shared with the task performing the
+    /// rename, so that it can tell whether a later rename has replaced this one.
+    old_name: Arc<str>,
+    new_name: String,
+    buffer_id: BufferId,
+    /// Whether the rename has been applied, in which case the next reparse
+    /// of the buffer will reflect the new name.
+    applied: bool,
+}
+
 struct InvalidationStack<T>(Vec<T>);
 
 struct RegisteredInlineCompletionProvider {
@@ -1690,6 +1736,7 @@ impl Editor {
         clone.scroll_manager.clone_state(&self.scroll_manager);
         clone.searchable = self.searchable;
         clone.read_only = self.read_only;
+        clone.allow_staging_while_read_only = self.allow_staging_while_read_only;
         clone
     }
 
@@ -1873,9 +1920,10 @@ impl Editor {
                             _,
                         ) => {
                             this.load_diff_task = Some(
-                                update_uncommitted_diff_for_buffer(
+                                update_diff_for_buffers(
                                     cx.entity(),
                                     &project,
+                                    this.diff_base,
                                     this.buffer.read(cx).all_buffers(),
                                     this.buffer.clone(),
                                     cx,
@@ -1883,6 +1931,13 @@ impl Editor {
                                 .shared(),
                             );
                         }
+                        // The merge-base moves along with HEAD and the upstream.
+                        GitStoreEvent::RepositoryUpdated(_, RepositoryEvent::Updated { .. }, _)
+                            if this.diff_base == DiffBase::MergeBase =>
+                        {
+                            let buffers = this.buffer.read(cx).all_buffers();
+                            this.refresh_merge_base_diff(buffers, cx);
+                        }
                         _ => {}
                     }
                 }));
@@ -1920,9 +1975,10 @@ impl Editor {
         let mut load_uncommitted_diff = None;
         if let Some(project) = project.clone() {
             load_uncommitted_diff = Some(
-                update_uncommitted_diff_for_buffer(
+                update_diff_for_buffers(
                     cx.entity(),
                     &project,
+                    DiffBase::default(),
                     buffer.read(cx).all_buffers(),
                     buffer.clone(),
                     cx,
@@ -2008,6 +2064,8 @@ impl Editor {
             document_highlights_task: None,
             linked_editing_range_task: None,
             pending_rename: None,
+            optimistic_rename: None,
+            hunk_numbers: HashMap::default(),
             searchable: true,
             cursor_shape: EditorSettings::get_global(cx)
                 .cursor_shape
@@ -2019,6 +2077,7 @@ impl Editor {
             input_enabled: true,
             use_modal_editing: mode.is_full(),
             read_only: mode.is_minimap(),
+            allow_staging_while_read_only: false,
             use_autoclose: true,
             use_auto_surround: true,
             auto_replace_emoji_shortcode: false,
@@ -2102,6 +2161,10 @@ impl Editor {
             in_project_search: false,
             previous_search_ranges: None,
             breadcrumb_header: None,
+            breadcrumb_max_depth: None,
+            breadcrumb_ellipsis: None,
+            breadcrumb_leaf_kinds: None,
+            breadcrumb_type_hint: false,
             focused_block: None,
             next_scroll_position: NextScrollCursorCenterTopBottom::default(),
             addons: HashMap::default(),
@@ -2114,6 +2177,9 @@ impl Editor {
             text_style_refinement: None,
             load_diff_task: load_uncommitted_diff,
             temporary_diff_override: false,
+            diff_base: DiffBase::default(),
+            refresh_merge_base_diff_task: Task::ready(()),
+            diff_scroll_sync: None,
             mouse_cursor_hidden: false,
             minimap: None,
             hide_mouse_mode: EditorSettings::get_global(cx)
@@ -2746,6 +2812,16 @@ impl Editor {
         self.read_only = read_only;
     }
 
+    /// Keeps the diff hunk controls available while the editor is read-only, so that hunks can
+    /// still be staged and unstaged. Restoring hunks remains disabled.
+    pub fn set_allow_staging_while_read_only(&mut self, allow: bool) {
+        self.allow_staging_while_read_only = allow;
+    }
+
+    pub fn allows_staging_while_read_only(&self) -> bool {
+        self.allow_staging_while_read_only
+    }
+
     pub fn set_use_autoclose(&mut self, autoclose: bool) {
         self.use_autoclose = autoclose;
     }
@@ -10105,6 +10181,10 @@ impl Editor {
         window: &mut Window,
         cx: &mut Context<Editor>,
     ) {
+        if self.read_only(cx) {
+            return;
+        }
+
         let mut revert_changes = HashMap::default();
         let chunk_by = self
             .snapshot(window, cx)
@@ -14667,6 +14747,71 @@ impl Editor {
         self.refresh_inline_completion(false, true, window, cx);
     }
 
+    /// Returns the diagnostics within the innermost symbol containing the cursor.
+    fn innermost_symbol_diagnostics(&self, cx: &App) -> Vec<DiagnosticEntry<usize>> {
+        let multi_buffer = self.buffer.read(cx);
+        let cursor = self.selections.newest_anchor().head();
+        let Some(symbol) = multi_buffer
+            .symbols_containing(cursor, None, cx)
+            .and_then(|(_, mut symbols)| symbols.pop())
+        else {
+            return Vec::new();
+        };
+
+        let snapshot = multi_buffer.snapshot(cx);
+        snapshot
+            .diagnostics_in_range::<usize>(symbol.range.to_offset(&snapshot))
+            .collect()
+    }
+
+    /// Returns whether the editor edits a single buffer whose language has a symbol outline,
+    /// without computing the outline itself.
+    pub fn has_outline(&self, cx: &App) -> bool {
+        self.buffer
+            .read(cx)
+            .as_singleton()
+            .and_then(|buffer| buffer.read(cx).language().cloned())
+            .and_then(|language| language.grammar().cloned())
+            .is_some_and(|grammar| grammar.outline_config.is_some())
+    }
+
+    /// Counts the errors and warnings within the innermost symbol containing the cursor.
+    pub fn innermost_symbol_diagnostic_summary(&self, cx: &App) -> DiagnosticSummary {
+        DiagnosticSummary::new(&self.innermost_symbol_diagnostics(cx))
+    }
+
+    /// Moves the cursor to the first error or warning within the innermost symbol
+    /// containing the cursor.
+    pub fn go_to_first_innermost_symbol_diagnostic(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(diagnostic) = self
+            .innermost_symbol_diagnostics(cx)
+            .into_iter()
+            .filter(|entry| {
+                entry.diagnostic.is_primary
+                    && matches!(
+                        entry.diagnostic.severity,
+                        lsp::DiagnosticSeverity::ERROR | lsp::DiagnosticSeverity::WARNING
+                    )
+            })
+            .min_by_key(|entry| entry.range.start)
+        else {
+            return;
+        };
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let Some(buffer_id) = buffer.anchor_after(diagnostic.range.start).buffer_id else {
+            return;
+        };
+        self.change_selections(Some(Autoscroll::fit()), window, cx, |s| {
+            s.select_ranges(vec![diagnostic.range.start..diagnostic.range.start])
+        });
+        self.activate_diagnostics(buffer_id, diagnostic, window, cx);
+    }
+
     pub fn go_to_next_hunk(&mut self, _: &GoToHunk, window: &mut Window, cx: &mut Context<Self>) {
         self.hide_mouse_cursor(HideMouseCursorOrigin::MovementAction, cx);
         let snapshot = self.snapshot(window, cx);
@@ -14913,6 +15058,302 @@ impl Editor {
         self.go_to_definition_of_kind(GotoDefinitionKind::Type, true, window, cx)
     }
 
+    /// Opens the definition of the innermost symbol containing the cursor in a split.
+    pub fn go_to_innermost_symbol_definition_split(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Navigated>> {
+        let Some(provider) = self.semantics_provider.clone() else {
+            return Task::ready(Ok(Navigated::No));
+        };
+        let Some((buffer, position)) = self.innermost_symbol_name_position(cx) else {
+            return Task::ready(Ok(Navigated::No));
+        };
+        let kind = GotoDefinitionKind::Symbol;
+        let Some(definitions) = provider.definitions(&buffer, position, kind, cx) else {
+            return Task::ready(Ok(Navigated::No));
+        };
+
+        cx.spawn_in(window, async move |editor, cx| {
+            let definitions = definitions.await?;
+            let navigated = editor
+                .update_in(cx, |editor, window, cx| {
+                    editor.navigate_to_hover_links(
+                        Some(kind),
+                        definitions.into_iter().map(HoverLink::Text).collect(),
+                        true,
+                        window,
+                        cx,
+                    )
+                })?
+                .await?;
+            anyhow::Ok(navigated)
+        })
+    }
+
+    /// Opens the test of the innermost symbol containing the cursor, found among the
+    /// project's symbols by the names that tests of the symbol conventionally have. When
+    /// several tests match, they are offered in a menu at the cursor.
+    pub fn go_to_innermost_symbol_test(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Navigated>> {
+        let Some(project) = self.project.clone() else {
+            return Task::ready(Ok(Navigated::No));
+        };
+        let Some(name) = self.innermost_symbol_name(cx) else {
+            return Task::ready(Ok(Navigated::No));
+        };
+        let symbols = project.update(cx, |project, cx| project.symbols(&name, cx));
+
+        cx.spawn_in(window, async move |editor, cx| {
+            let mut tests = symbols
+                .await?
+                .into_iter()
+                .filter(|symbol| {
+                    matches!(
+                        symbol.kind,
+                        lsp::SymbolKind::FUNCTION | lsp::SymbolKind::METHOD
+                    ) && is_test_name_for_symbol(&symbol.name, &name)
+                })
+                .collect::<Vec<_>>();
+            tests.dedup_by(|a, b| a.path == b.path && a.range == b.range);
+
+            match tests.len() {
+                0 => Ok(Navigated::No),
+                1 => {
+                    editor
+                        .update_in(cx, |editor, window, cx| {
+                            editor.navigate_to_project_symbol(tests.remove(0), window, cx)
+                        })?
+                        .await
+                }
+                _ => {
+                    editor.update_in(cx, |editor, window, cx| {
+                        editor.deploy_project_symbols_menu("Tests", tests, window, cx);
+                    })?;
+                    Ok(Navigated::Yes)
+                }
+            }
+        })
+    }
+
+    /// Opens a menu at the cursor that navigates to the chosen symbol.
+    fn deploy_project_symbols_menu(
+        &mut self,
+        title: &'static str,
+        symbols: Vec<project::Symbol>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let editor = cx.weak_entity();
+        let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
+            symbols
+                .into_iter()
+                .fold(menu.header(title), |menu, symbol| {
+                    let label = format!("{} ({})", symbol.name, symbol.path.path.display());
+                    let editor = editor.clone();
+                    menu.entry(label, None, move |window, cx| {
+                        editor
+                            .update(cx, |editor, cx| {
+                                editor
+                                    .navigate_to_project_symbol(symbol.clone(), window, cx)
+                                    .detach_and_log_err(cx);
+                            })
+                            .ok();
+                    })
+                })
+        });
+
+        let character_size = self.character_size(window);
+        let menu_position = crate::mouse_context_menu::MenuPosition::PinnedToEditor {
+            source: self.selections.newest_anchor().head(),
+            offset: gpui::point(character_size.width, character_size.height),
+        };
+        self.mouse_context_menu = Some(MouseContextMenu::new(
+            self,
+            menu_position,
+            context_menu,
+            window,
+            cx,
+        ));
+        cx.notify();
+    }
+
+    /// Opens the buffer of a symbol found among the project's symbols and moves the cursor
+    /// to the start of the symbol.
+    fn navigate_to_project_symbol(
+        &mut self,
+        symbol: project::Symbol,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Navigated>> {
+        let Some(project) = self.project.clone() else {
+            return Task::ready(Ok(Navigated::No));
+        };
+        let buffer = project.update(cx, |project, cx| {
+            project.open_buffer_for_symbol(&symbol, cx)
+        });
+
+        cx.spawn_in(window, async move |editor, cx| {
+            let buffer = buffer.await?;
+            let start = buffer.read_with(cx, |buffer, _| {
+                let start = buffer.clip_point_utf16(symbol.range.start, Bias::Left);
+                buffer.anchor_after(start)
+            })?;
+            let link = HoverLink::Text(LocationLink {
+                origin: None,
+                target: Location {
+                    buffer,
+                    range: start..start,
+                },
+            });
+            editor
+                .update_in(cx, |editor, window, cx| {
+                    editor.navigate_to_hover_links(None, vec![link], false, window, cx)
+                })?
+                .await
+        })
+    }
+
+    fn innermost_symbol_name(&self, cx: &App) -> Option<String> {
+        let (_, symbols) = self.buffer.read(cx).symbols_containing(
+            self.selections.newest_anchor().head(),
+            None,
+            cx,
+        )?;
+        let symbol = symbols.last()?;
+        Some(
+            symbol
+                .text
+                .get(symbol.name_ranges.first()?.clone())?
+                .to_string(),
+        )
+    }
+
+    /// Returns the position of the name of the innermost symbol containing the cursor.
+    fn innermost_symbol_name_position(&self, cx: &App) -> Option<(Entity<Buffer>, text::Anchor)> {
+        let multi_buffer = self.buffer.read(cx);
+        let cursor = self.selections.newest_anchor().head();
+        let (_, symbols) = multi_buffer.symbols_containing(cursor, None, cx)?;
+        let name_range = symbols.last()?.name_range.clone()?;
+        multi_buffer.text_anchor_for_position(name_range.start, cx)
+    }
+
+    /// Moves the cursor to the start of one of the symbols containing it, where a depth of
+    /// zero is the outermost symbol, matching the order of the breadcrumbs.
+    pub fn go_to_containing_symbol(
+        &mut self,
+        depth: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let cursor = self.selections.newest_anchor().head();
+        let Some((_, symbols)) = self.buffer.read(cx).symbols_containing(cursor, None, cx) else {
+            return;
+        };
+        let Some(symbol) = symbols.get(depth) else {
+            return;
+        };
+        let start = symbol.range.start;
+        self.change_selections(Some(Autoscroll::center()), window, cx, |s| {
+            s.select_anchor_ranges([start..start]);
+        });
+    }
+
+    /// Moves the cursor to the start of a range of the buffer containing it, such as the
+    /// source range of one of the editor's breadcrumbs.
+    pub fn go_to_breadcrumb_range(
+        &mut self,
+        range: Range<text::Anchor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let excerpt_id = self.selections.newest_anchor().head().excerpt_id;
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let Some(start) = snapshot.anchor_in_excerpt(excerpt_id, range.start) else {
+            return;
+        };
+        self.change_selections(Some(Autoscroll::center()), window, cx, |s| {
+            s.select_anchor_ranges([start..start]);
+        });
+    }
+
+    /// Returns a label for the innermost macro invocation, attribute, or attributed item
+    /// containing the cursor, such as `vec!` or `#[derive]`, in languages where that context
+    /// is known.
+    pub fn macro_context_at_cursor(&self, cx: &App) -> Option<String> {
+        let cursor = self.selections.newest_anchor().head();
+        let (buffer, offset) = self.buffer.read(cx).point_to_buffer_offset(cursor, cx)?;
+        let buffer = buffer.read(cx).snapshot();
+
+        // Macro arguments are often parsed in layers injected into the invocation, so the
+        // innermost context may come from any of the layers containing the cursor.
+        let mut context: Option<(usize, String)> = None;
+        for layer in buffer.syntax_layers() {
+            let root = layer.node();
+            if offset < root.start_byte() || offset > root.end_byte() {
+                continue;
+            }
+            let mut node = root.descendant_for_byte_range(offset, offset);
+            while let Some(current) = node {
+                if let Some(label) = macro_context_label(&layer.language.name(), current, &buffer) {
+                    let len = current.byte_range().len();
+                    if context
+                        .as_ref()
+                        .is_none_or(|(context_len, _)| len < *context_len)
+                    {
+                        context = Some((len, label));
+                    }
+                    break;
+                }
+                node = current.parent();
+            }
+        }
+        context.map(|(_, label)| label)
+    }
+
+    /// Returns the text of the line comment containing the cursor, together with the comment
+    /// lines directly above and below it, with their comment prefixes removed.
+    pub fn comment_at_cursor(&self, cx: &App) -> Option<String> {
+        let cursor = self.selections.newest_anchor().head();
+        let (buffer, offset) = self.buffer.read(cx).point_to_buffer_offset(cursor, cx)?;
+        let buffer = buffer.read(cx).snapshot();
+        let prefixes = buffer
+            .language_scope_at(offset)?
+            .line_comment_prefixes()
+            .to_vec();
+
+        let comment_text = |row: u32| {
+            let line = buffer
+                .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+                .collect::<String>();
+            let line = line.trim_start();
+            // Prefer the longest prefix, so that doc comments lose all of their slashes.
+            prefixes
+                .iter()
+                .filter_map(|prefix| line.strip_prefix(prefix.trim_end()))
+                .min_by_key(|text| text.len())
+                .map(|text| text.trim().to_string())
+        };
+
+        let row = buffer.offset_to_point(offset).row;
+        comment_text(row)?;
+        let start_row = (0..row)
+            .rev()
+            .take_while(|row| comment_text(*row).is_some())
+            .last()
+            .unwrap_or(row);
+        let end_row = (row + 1..=buffer.max_point().row)
+            .take_while(|row| comment_text(*row).is_some())
+            .last()
+            .unwrap_or(row);
+        let comment = (start_row..=end_row).filter_map(comment_text).join("\n");
+        Some(comment.trim().to_string()).filter(|comment| !comment.is_empty())
+    }
+
     fn go_to_definition_of_kind(
         &mut self,
         kind: GotoDefinitionKind,
@@ -14997,6 +15438,37 @@ impl Editor {
         url_finder.detach();
     }
 
+    /// Opens the issue or pull request that the URL under the cursor links to, on its own page
+    /// rather than on the subpage the URL may point at. URLs that no Git hosting provider
+    /// recognizes are opened as they are.
+    pub fn open_referenced_issue_or_pull_request(
+        &mut self,
+        _: &OpenReferencedIssueOrPullRequest,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let head = self.selections.newest_anchor().head();
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(head, cx)
+        else {
+            return;
+        };
+
+        cx.spawn_in(window, async move |editor, cx| {
+            let Some((_, url)) = find_url(&buffer, position, cx.clone()) else {
+                return Ok(());
+            };
+            editor.update(cx, |_, cx| {
+                let reference = ::git::GitHostingProviderRegistry::try_global(cx)
+                    .and_then(|registry| ::git::parse_issue_or_pull_request_url(registry, &url));
+                match reference {
+                    Some((_, reference)) => cx.open_url(reference.url.as_str()),
+                    None => cx.open_url(&url),
+                }
+            })
+        })
+        .detach();
+    }
+
     pub fn open_selected_filename(
         &mut self,
         _: &OpenSelectedFilename,
@@ -15675,21 +16147,46 @@ impl Editor {
             cx,
         )?;
 
+        self.optimistic_rename = Some(OptimisticRename {
+            old_name: old_name.clone(),
+            new_name: new_name.clone(),
+            buffer_id: buffer.read(cx).remote_id(),
+            applied: false,
+        });
+        cx.emit(EditorEvent::BreadcrumbsChanged);
+
         Some(cx.spawn_in(window, async move |editor, cx| {
-            let project_transaction = rename.await?;
-            Self::open_project_transaction(
-                &editor,
-                workspace,
-                project_transaction,
-                format!("Rename: {} → {}", old_name, new_name),
-                cx,
-            )
-            .await?;
+            let result = async {
+                let project_transaction = rename.await?;
+                Self::open_project_transaction(
+                    &editor,
+                    workspace,
+                    project_transaction,
+                    format!("Rename: {} → {}", old_name, new_name),
+                    cx,
+                )
+                .await
+            }
+            .await;
 
             editor.update(cx, |editor, cx| {
+                if let Some(optimistic_rename) = editor
+                    .optimistic_rename
+                    .as_mut()
+                    .filter(|rename| Arc::ptr_eq(&rename.old_name, &old_name))
+                {
+                    if result.is_ok() && buffer.read(cx).is_parsing() {
+                        // The new name is shown once the buffer is reparsed.
+                        optimistic_rename.applied = true;
+                    } else {
+                        // Either the rename failed, or the buffer already reflects it.
+                        editor.optimistic_rename = None;
+                        cx.emit(EditorEvent::BreadcrumbsChanged);
+                    }
+                }
                 editor.refresh_document_highlights(cx);
             })?;
-            Ok(())
+            result
         }))
     }
 
@@ -16989,22 +17486,131 @@ impl Editor {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        self.remove_folds_with_type(
+            &[Anchor::min()..Anchor::max()],
+            TypeId::of::<DiffHunkFold>(),
+            false,
+            cx,
+        );
         self.buffer.update(cx, |buffer, cx| {
             buffer.expand_diff_hunks(vec![Anchor::min()..Anchor::max()], cx)
         });
     }
 
-    pub fn toggle_selected_diff_hunks(
+    /// Collapses every diff hunk and folds the lines it still shows into a one-line summary of
+    /// the hunk, until the hunks are expanded again or the diff changes.
+    ///
+    /// Hunks that only delete lines have no lines of their own once collapsed, so they are
+    /// left as they are, and their deleted lines are folded when shown. So are the deleted
+    /// lines of every hunk in views that always expand them.
+    pub fn collapse_all_diff_hunks(
         &mut self,
-        _: &ToggleSelectedDiffHunks,
-        _window: &mut Window,
+        _: &CollapseAllDiffHunks,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let ranges: Vec<_> = self.selections.disjoint.iter().map(|s| s.range()).collect();
-        self.toggle_diff_hunks_in_ranges(ranges, cx);
-    }
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let ranges = snapshot
+            .diff_hunks()
+            .filter(|hunk| !hunk.row_range.is_empty())
+            .map(|hunk| {
+                let start = Point::new(hunk.row_range.start.0, 0);
+                (start..start, hunk.excerpt_id)
+            })
+            .collect::<Vec<_>>();
+        self.buffer.update(cx, |buffer, cx| {
+            buffer.expand_or_collapse_diff_hunks_inner(ranges, false, cx)
+        });
 
-    pub fn diff_hunks_in_ranges<'a>(
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let is_deleted_row = |row: u32| {
+            snapshot
+                .row_infos(MultiBufferRow(row))
+                .next()
+                .and_then(|row_info| row_info.diff_status)
+                .is_some_and(|status| status.is_deleted())
+        };
+        let creases = snapshot
+            .diff_hunks()
+            .filter_map(|hunk| {
+                // The deleted lines, when shown, are next to the hunk's added lines.
+                let mut start_row = hunk.row_range.start.0;
+                while start_row > 0 && is_deleted_row(start_row - 1) {
+                    start_row -= 1;
+                }
+                let mut end_row = hunk.row_range.start.0;
+                while end_row <= snapshot.max_row().0 && is_deleted_row(end_row) {
+                    end_row += 1;
+                }
+                let end_row = MultiBufferRow(end_row.max(hunk.row_range.end.0).checked_sub(1)?);
+                if end_row.0 < start_row {
+                    return None;
+                }
+
+                let summary = diff_hunk_summary(&hunk, &snapshot)?;
+                let range =
+                    Point::new(start_row, 0)..Point::new(end_row.0, snapshot.line_len(end_row));
+                Some(Crease::simple(
+                    range,
+                    self.diff_hunk_fold_placeholder(summary.into(), cx),
+                ))
+            })
+            .collect::<Vec<_>>();
+        self.fold_creases(creases, false, window, cx);
+    }
+
+    fn diff_hunk_fold_placeholder(
+        &self,
+        summary: SharedString,
+        cx: &mut Context<Self>,
+    ) -> FoldPlaceholder {
+        let editor = cx.entity().downgrade();
+        FoldPlaceholder {
+            constrain_width: false,
+            render: Arc::new(move |fold_id, fold_range, cx| {
+                let editor = editor.clone();
+                div()
+                    .id(fold_id)
+                    .px_1()
+                    .bg(cx.theme().colors().ghost_element_background)
+                    .hover(|style| style.bg(cx.theme().colors().ghost_element_hover))
+                    .active(|style| style.bg(cx.theme().colors().ghost_element_active))
+                    .rounded_xs()
+                    .cursor_pointer()
+                    .text_color(cx.theme().colors().text_muted)
+                    .child(summary.clone())
+                    .on_mouse_down(MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                    .on_click(move |_, _window, cx| {
+                        editor
+                            .update(cx, |editor, cx| {
+                                editor.remove_folds_with_type(
+                                    &[fold_range.start..fold_range.end],
+                                    TypeId::of::<DiffHunkFold>(),
+                                    false,
+                                    cx,
+                                );
+                                cx.stop_propagation();
+                            })
+                            .ok();
+                    })
+                    .into_any()
+            }),
+            merge_adjacent: false,
+            type_tag: Some(TypeId::of::<DiffHunkFold>()),
+        }
+    }
+
+    pub fn toggle_selected_diff_hunks(
+        &mut self,
+        _: &ToggleSelectedDiffHunks,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let ranges: Vec<_> = self.selections.disjoint.iter().map(|s| s.range()).collect();
+        self.toggle_diff_hunks_in_ranges(ranges, cx);
+    }
+
+    pub fn diff_hunks_in_ranges<'a>(
         &'a self,
         ranges: &'a [Range<Anchor>],
         buffer: &'a MultiBufferSnapshot,
@@ -17043,6 +17649,49 @@ impl Editor {
         self.stage_or_unstage_diff_hunks(stage, ranges, cx);
     }
 
+    fn refresh_hunk_numbers(&mut self, cx: &App) {
+        self.hunk_numbers.clear();
+        let numbering = ProjectSettings::get_global(cx)
+            .git
+            .number_hunks
+            .unwrap_or_default();
+        if numbering == GitHunkNumbering::Off {
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut buffer_hunk_starts = HashMap::<BufferId, Vec<text::Anchor>>::default();
+        for (ix, hunk) in snapshot.diff_hunks().enumerate() {
+            let number = if numbering == GitHunkNumbering::All {
+                let Some(buffer) = snapshot.buffer_for_excerpt(hunk.excerpt_id) else {
+                    continue;
+                };
+                let hunk_starts = buffer_hunk_starts.entry(hunk.buffer_id).or_insert_with(|| {
+                    snapshot
+                        .diff_for_buffer_id(hunk.buffer_id)
+                        .map(|diff| {
+                            diff.hunks_intersecting_range(
+                                text::Anchor::MIN..text::Anchor::MAX,
+                                buffer,
+                            )
+                            .map(|hunk| hunk.buffer_range.start)
+                            .collect()
+                        })
+                        .unwrap_or_default()
+                });
+                hunk_starts
+                    .partition_point(|start| start.cmp(&hunk.buffer_range.start, buffer).is_lt())
+                    + 1
+            } else {
+                ix + 1
+            };
+            self.hunk_numbers.insert(
+                Anchor::in_buffer(hunk.excerpt_id, hunk.buffer_id, hunk.buffer_range.start),
+                number,
+            );
+        }
+    }
+
     pub fn set_render_diff_hunk_controls(
         &mut self,
         render_diff_hunk_controls: RenderDiffHunkControlsFn,
@@ -17976,6 +18625,50 @@ impl Editor {
         }
     }
 
+    /// Copies a summary of the changes in this editor's diffs, e.g. `+3 −1` for a single file or
+    /// git's `2 files changed, 3 insertions(+), 1 deletion(-)` for a multibuffer.
+    ///
+    /// The counts cover every hunk of each buffer's diff, not only the excerpts being shown.
+    pub fn copy_diff_stats(&mut self, _: &CopyDiffStats, _: &mut Window, cx: &mut Context<Self>) {
+        let multi_buffer = self.buffer.read(cx);
+        let mut files_changed = 0;
+        let mut insertions = 0;
+        let mut deletions = 0;
+        for buffer in multi_buffer.all_buffers() {
+            let buffer = buffer.read(cx);
+            let Some(diff) = multi_buffer.diff_for(buffer.remote_id()) else {
+                continue;
+            };
+            let diff = diff.read(cx);
+            let base_text = diff.base_text();
+            let mut has_hunks = false;
+            let hunks =
+                diff.hunks_intersecting_range(text::Anchor::MIN..text::Anchor::MAX, buffer, cx);
+            for hunk in hunks {
+                has_hunks = true;
+                insertions += line_count(hunk.range);
+                deletions += line_count(
+                    base_text.offset_to_point(hunk.diff_base_byte_range.start)
+                        ..base_text.offset_to_point(hunk.diff_base_byte_range.end),
+                );
+            }
+            if has_hunks {
+                files_changed += 1;
+            }
+        }
+
+        let summary = if multi_buffer.is_singleton() {
+            format!("+{insertions} \u{2212}{deletions}")
+        } else {
+            format_diff_stats(files_changed, insertions, deletions)
+        };
+        cx.write_to_clipboard(ClipboardItem::new_string(summary));
+
+        fn line_count(range: Range<Point>) -> u32 {
+            range.end.row - range.start.row + (range.end.column > 0) as u32
+        }
+    }
+
     pub fn toggle_git_blame(
         &mut self,
         _: &::git::Blame,
@@ -18040,6 +18733,29 @@ impl Editor {
         None
     }
 
+    /// Returns the blame entry of the line under the newest cursor, along with the details
+    /// of its commit. Returns `None` while blame is not loaded, and for lines that are
+    /// uncommitted or have changed since blame was loaded.
+    pub fn blame_entry_at_cursor(
+        &self,
+        cx: &mut App,
+    ) -> Option<(BlameEntry, Option<ParsedCommitMessage>)> {
+        let blame = self.blame.as_ref()?;
+        let cursor = self.selections.newest_anchor().head();
+        let (buffer, offset) = self.buffer.read(cx).point_to_buffer_offset(cursor, cx)?;
+        let buffer = buffer.read(cx);
+        let row_info = RowInfo {
+            buffer_id: Some(buffer.remote_id()),
+            buffer_row: Some(buffer.offset_to_point(offset).row),
+            ..Default::default()
+        };
+        blame.update(cx, |blame, cx| {
+            let blame_entry = blame.blame_for_rows(&[row_info], cx).next().flatten()?;
+            let details = blame.details_for_entry(&blame_entry);
+            Some((blame_entry, details))
+        })
+    }
+
     pub fn git_blame_inline_enabled(&self) -> bool {
         self.git_blame_inline_enabled
     }
@@ -18082,8 +18798,10 @@ impl Editor {
 
             let project = project.clone();
             let blame = cx.new(|cx| GitBlame::new(buffer, project, user_triggered, focused, cx));
-            self.blame_subscription =
-                Some(cx.observe_in(&blame, window, |_, _, _, cx| cx.notify()));
+            self.blame_subscription = Some(cx.observe_in(&blame, window, |_, _, _, cx| {
+                cx.emit(EditorEvent::BlameUpdated);
+                cx.notify();
+            }));
             self.blame = Some(blame);
         }
     }
@@ -18158,7 +18876,9 @@ impl Editor {
         snapshot.line_len(buffer_row) == 0
     }
 
-    fn get_permalink_to_line(&self, cx: &mut Context<Self>) -> Task<Result<url::Url>> {
+    /// Returns a permalink to the lines of the newest selection on the repository's hosting
+    /// provider.
+    pub fn get_permalink_to_line(&self, cx: &mut Context<Self>) -> Task<Result<url::Url>> {
         let buffer_and_selection = maybe!({
             let selection = self.selections.newest::<Point>(cx);
             let selection_range = selection.range();
@@ -18290,6 +19010,112 @@ impl Editor {
         .detach();
     }
 
+    /// Opens the hosting provider's blame of the cursor's line, as of the repository's HEAD.
+    pub fn open_blame_to_line(
+        &mut self,
+        _: &OpenBlameToLine,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let cursor = self.selections.newest_anchor().head();
+        let Some((buffer, position)) = self.buffer.read(cx).text_anchor_for_position(cursor, cx)
+        else {
+            return;
+        };
+        let line = text::ToPoint::to_point(&position, &buffer.read(cx).snapshot()).row;
+        let blame_url_task = project.update(cx, |project, cx| {
+            project.get_blame_url_to_line(&buffer, line, cx)
+        });
+        let workspace = self.workspace();
+
+        cx.spawn_in(window, async move |_, cx| match blame_url_task.await {
+            Ok(blame_url) => {
+                cx.update(|_, cx| {
+                    cx.open_url(blame_url.as_ref());
+                })
+                .ok();
+            }
+            Err(err) => {
+                let message = format!("Failed to open blame: {err}");
+
+                anyhow::Result::<()>::Err(err).log_err();
+
+                if let Some(workspace) = workspace {
+                    workspace
+                        .update(cx, |workspace, cx| {
+                            struct OpenBlameToLine;
+
+                            workspace.show_toast(
+                                Toast::new(NotificationId::unique::<OpenBlameToLine>(), message),
+                                cx,
+                            )
+                        })
+                        .ok();
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Lists the owners of the file under the cursor from its repository's CODEOWNERS file,
+    /// linking each to its page on the hosting provider when possible.
+    pub fn show_code_owners(
+        &mut self,
+        _: &ShowCodeOwners,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let cursor = self.selections.newest_anchor().head();
+        let Some((buffer, _)) = self.buffer.read(cx).text_anchor_for_position(cursor, cx) else {
+            return;
+        };
+        let code_owners = project.update(cx, |project, cx| {
+            project.code_owners_for_buffer(&buffer, cx)
+        });
+
+        cx.spawn_in(window, async move |editor, cx| {
+            let code_owners = code_owners.await?;
+            if code_owners.is_empty() {
+                return Ok(());
+            }
+
+            editor.update_in(cx, |editor, window, cx| {
+                let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
+                    code_owners.into_iter().fold(
+                        menu.header("Code Owners"),
+                        |menu, (owner, url)| match url {
+                            Some(url) => {
+                                menu.entry(owner, None, move |_, cx| cx.open_url(url.as_str()))
+                            }
+                            None => menu.label(owner),
+                        },
+                    )
+                });
+
+                let character_size = editor.character_size(window);
+                let menu_position = crate::mouse_context_menu::MenuPosition::PinnedToEditor {
+                    source: cursor,
+                    offset: gpui::point(character_size.width, character_size.height),
+                };
+                editor.mouse_context_menu = Some(MouseContextMenu::new(
+                    editor,
+                    menu_position,
+                    context_menu,
+                    window,
+                    cx,
+                ));
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn insert_uuid_v4(
         &mut self,
         _: &InsertUuidV4,
@@ -18575,6 +19401,31 @@ impl Editor {
         self.breadcrumb_header = Some(new_header);
     }
 
+    /// Limits the breadcrumbs to the given number of the outermost symbols containing the
+    /// cursor, marking the innermost one shown when deeper symbols are left out.
+    pub fn set_breadcrumb_max_depth(&mut self, max_depth: Option<usize>) {
+        self.breadcrumb_max_depth = max_depth;
+    }
+
+    /// Sets the text that marks the innermost symbol shown in the breadcrumbs when deeper
+    /// symbols are left out. Defaults to "⋯".
+    pub fn set_breadcrumb_ellipsis(&mut self, ellipsis: String) {
+        self.breadcrumb_ellipsis = Some(ellipsis);
+    }
+
+    /// Ends the breadcrumbs at the innermost symbol containing the cursor that is of one of
+    /// the given kinds, leaving out deeper symbols such as closures. All symbols are shown
+    /// when none of them is of these kinds, or when no kinds are given.
+    pub fn set_breadcrumb_leaf_kinds(&mut self, leaf_kinds: Option<Vec<BreadcrumbSymbolKind>>) {
+        self.breadcrumb_leaf_kinds = leaf_kinds;
+    }
+
+    /// Sets whether the innermost symbol in the breadcrumbs comes with the type annotated on
+    /// it, such as a function's return type.
+    pub fn set_breadcrumb_type_hint(&mut self, show_type_hint: bool) {
+        self.breadcrumb_type_hint = show_type_hint;
+    }
+
     pub fn clear_search_within_ranges(&mut self, cx: &mut Context<Self>) {
         self.clear_background_highlights::<SearchWithinRange>(cx);
     }
@@ -19195,6 +20046,9 @@ impl Editor {
 
                 if let Some(buffer) = edited_buffer {
                     self.update_lsp_data(None, Some(buffer.read(cx).remote_id()), window, cx);
+                    if self.diff_base == DiffBase::MergeBase {
+                        self.refresh_merge_base_diff(HashSet::from_iter([buffer.clone()]), cx);
+                    }
                 }
 
                 if *singleton_buffer_edited {
@@ -19246,12 +20100,14 @@ impl Editor {
                 excerpts,
             } => {
                 self.tasks_update_task = Some(self.refresh_runnables(window, cx));
+                self.refresh_hunk_numbers(cx);
                 let buffer_id = buffer.read(cx).remote_id();
                 if self.buffer.read(cx).diff_for(buffer_id).is_none() {
                     if let Some(project) = &self.project {
-                        update_uncommitted_diff_for_buffer(
+                        update_diff_for_buffers(
                             cx.entity(),
                             project,
+                            self.diff_base,
                             [buffer.clone()],
                             self.buffer.clone(),
                             cx,
@@ -19272,6 +20128,7 @@ impl Editor {
                 removed_buffer_ids,
             } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                self.refresh_hunk_numbers(cx);
                 let buffer = self.buffer.read(cx);
                 self.registered_buffers
                     .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
@@ -19288,17 +20145,26 @@ impl Editor {
                 self.display_map.update(cx, |map, cx| {
                     map.unfold_buffers(buffer_ids.iter().copied(), cx)
                 });
+                self.refresh_hunk_numbers(cx);
                 cx.emit(EditorEvent::ExcerptsEdited {
                     ids: excerpt_ids.clone(),
                 });
             }
             multi_buffer::Event::ExcerptsExpanded { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                self.refresh_hunk_numbers(cx);
                 cx.emit(EditorEvent::ExcerptsExpanded { ids: ids.clone() })
             }
             multi_buffer::Event::Reparsed(buffer_id) => {
                 self.tasks_update_task = Some(self.refresh_runnables(window, cx));
                 jsx_tag_auto_close::refresh_enabled_in_any_buffer(self, multibuffer, cx);
+                if self
+                    .optimistic_rename
+                    .as_ref()
+                    .is_some_and(|rename| rename.applied && rename.buffer_id == *buffer_id)
+                {
+                    self.optimistic_rename = None;
+                }
 
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
             }
@@ -19313,12 +20179,24 @@ impl Editor {
             }
             multi_buffer::Event::DirtyChanged => cx.emit(EditorEvent::DirtyChanged),
             multi_buffer::Event::Saved => cx.emit(EditorEvent::Saved),
-            multi_buffer::Event::FileHandleChanged
-            | multi_buffer::Event::Reloaded
-            | multi_buffer::Event::BufferDiffChanged => cx.emit(EditorEvent::TitleChanged),
+            multi_buffer::Event::BufferDiffChanged => {
+                // The hunks may have moved or changed, so their summaries would be stale.
+                self.remove_folds_with_type(
+                    &[Anchor::min()..Anchor::max()],
+                    TypeId::of::<DiffHunkFold>(),
+                    false,
+                    cx,
+                );
+                self.refresh_hunk_numbers(cx);
+                cx.emit(EditorEvent::TitleChanged);
+            }
+            multi_buffer::Event::FileHandleChanged | multi_buffer::Event::Reloaded => {
+                cx.emit(EditorEvent::TitleChanged)
+            }
             multi_buffer::Event::Closed => cx.emit(EditorEvent::Closed),
             multi_buffer::Event::DiagnosticsUpdated => {
                 self.update_diagnostics_state(window, cx);
+                cx.emit(EditorEvent::BreadcrumbsChanged);
             }
             _ => {}
         };
@@ -19331,6 +20209,353 @@ impl Editor {
         cx.notify();
     }
 
+    pub fn diff_base(&self) -> DiffBase {
+        self.diff_base
+    }
+
+    /// Returns whether the editor shows the base text of any diff hunk inline, either because
+    /// it is a diff view that expands every hunk, such as the project diff, or because some
+    /// of its hunks were expanded.
+    pub fn is_showing_diff(&self, cx: &App) -> bool {
+        let buffer = self.buffer.read(cx);
+        buffer.all_diff_hunks_expanded()
+            || buffer.has_expanded_diff_hunks_in_ranges(&[Anchor::min()..Anchor::max()], cx)
+    }
+
+    /// Switches the diff base between HEAD and the index. Only available while the editor
+    /// shows a diff, see [`Self::is_showing_diff`].
+    pub fn toggle_diff_base(
+        &mut self,
+        _: &ToggleDiffBase,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.is_showing_diff(cx) {
+            cx.propagate();
+            return;
+        }
+        let diff_base = match self.diff_base {
+            DiffBase::Head => DiffBase::Index,
+            DiffBase::Index | DiffBase::MergeBase => DiffBase::Head,
+        };
+        self.set_diff_base(diff_base, cx);
+    }
+
+    /// Advances the diff base from HEAD to the index, then to the merge-base with the
+    /// upstream, and back to HEAD. The merge-base is skipped unless the branch of every
+    /// buffer has an upstream. Only available while the editor shows a diff.
+    pub fn cycle_diff_base(
+        &mut self,
+        _: &CycleDiffBase,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.is_showing_diff(cx) {
+            cx.propagate();
+            return;
+        }
+        let diff_base = match self.diff_base {
+            DiffBase::Head => DiffBase::Index,
+            DiffBase::Index if self.can_diff_against_merge_base(cx) => DiffBase::MergeBase,
+            DiffBase::Index | DiffBase::MergeBase => DiffBase::Head,
+        };
+        self.set_diff_base(diff_base, cx);
+    }
+
+    fn can_diff_against_merge_base(&self, cx: &App) -> bool {
+        let Some(project) = self.project.as_ref() else {
+            return false;
+        };
+        let git_store = project.read(cx).git_store().read(cx);
+        let buffers = self.buffer.read(cx).all_buffers();
+        !buffers.is_empty()
+            && buffers.iter().all(|buffer| {
+                git_store
+                    .repository_and_path_for_buffer_id(buffer.read(cx).remote_id(), cx)
+                    .is_some_and(|(repo, _)| {
+                        let branch = repo.read(cx).branch.as_ref();
+                        branch.is_some_and(|branch| branch.upstream.is_some())
+                    })
+            })
+    }
+
+    fn set_diff_base(&mut self, diff_base: DiffBase, cx: &mut Context<Self>) {
+        if self.temporary_diff_override || diff_base == self.diff_base {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let buffers = self.buffer.read(cx).all_buffers();
+        let multi_buffer = self.buffer.read(cx);
+        if buffers
+            .iter()
+            .all(|buffer| multi_buffer.diff_for(buffer.read(cx).remote_id()).is_none())
+        {
+            return;
+        }
+
+        self.diff_base = diff_base;
+        self.refresh_merge_base_diff_task = Task::ready(());
+        self.load_diff_task = Some(
+            update_diff_for_buffers(
+                cx.entity(),
+                &project,
+                self.diff_base,
+                buffers,
+                self.buffer.clone(),
+                cx,
+            )
+            .shared(),
+        );
+        cx.emit(EditorEvent::DiffBaseChanged);
+        cx.notify();
+    }
+
+    /// Recomputes the diffs of the given buffers against the merge-base. Unlike the diffs
+    /// against HEAD and the index, these aren't kept up to date by the git store.
+    fn refresh_merge_base_diff(
+        &mut self,
+        buffers: HashSet<Entity<Buffer>>,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        self.refresh_merge_base_diff_task = cx.spawn(async move |editor, cx| {
+            cx.background_executor()
+                .timer(MERGE_BASE_DIFF_DEBOUNCE_TIMEOUT)
+                .await;
+            let Some(task) = editor
+                .update(cx, |editor, cx| {
+                    (editor.diff_base == DiffBase::MergeBase).then(|| {
+                        update_diff_for_buffers(
+                            cx.entity(),
+                            &project,
+                            DiffBase::MergeBase,
+                            buffers,
+                            editor.buffer.clone(),
+                            cx,
+                        )
+                    })
+                })
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            task.await;
+        });
+    }
+
+    /// Opens the diff base of this editor's buffer in a read-only tab next to this one, which
+    /// scrolls along with this editor until the sync is toggled off.
+    pub fn split_diff_into_tabs(
+        &mut self,
+        _: &SplitDiffIntoTabs,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let multi_buffer = self.buffer.read(cx);
+        let Some(buffer) = multi_buffer.as_singleton() else {
+            return;
+        };
+        let Some(base_text) = multi_buffer
+            .diff_for(buffer.read(cx).remote_id())
+            .and_then(|diff| diff.read(cx).base_text_string())
+        else {
+            return;
+        };
+        let language = buffer.read(cx).language().cloned();
+        let title = format!("{} (Base)", multi_buffer.title(cx));
+        let create_buffer = project.update(cx, |project, cx| project.create_buffer(cx));
+        let leader = cx.entity();
+
+        cx.spawn_in(window, async move |_, cx| {
+            let base_buffer = create_buffer.await?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                base_buffer.update(cx, |buffer, cx| {
+                    buffer.set_text(base_text, cx);
+                    buffer.set_language(language, cx);
+                    buffer.set_capability(Capability::ReadOnly, cx);
+                });
+                let multibuffer =
+                    cx.new(|cx| MultiBuffer::singleton(base_buffer, cx).with_title(title));
+
+                let enabled = Rc::new(Cell::new(true));
+                let base_editor = cx.new(|cx| {
+                    let mut editor =
+                        Editor::for_multibuffer(multibuffer, Some(project), window, cx);
+                    editor.set_read_only(true);
+                    let subscription = cx.subscribe_in(
+                        &leader,
+                        window,
+                        |editor, leader, event: &EditorEvent, window, cx| {
+                            if let EditorEvent::ScrollPositionChanged { .. } = event {
+                                editor.follow_diff_scroll_position(leader, window, cx);
+                            }
+                        },
+                    );
+                    editor.diff_scroll_sync = Some(DiffScrollSync {
+                        enabled: enabled.clone(),
+                        _leader_subscription: Some(subscription),
+                    });
+                    editor.follow_diff_scroll_position(&leader, window, cx);
+                    editor
+                });
+                leader.update(cx, |leader, _| {
+                    leader.diff_scroll_sync = Some(DiffScrollSync {
+                        enabled,
+                        _leader_subscription: None,
+                    });
+                });
+
+                workspace.add_item_to_active_pane(Box::new(base_editor), None, true, window, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Toggles whether the tabs opened by [`Editor::split_diff_into_tabs`] scroll together.
+    pub fn toggle_diff_tabs_scroll_sync(
+        &mut self,
+        _: &ToggleDiffTabsScrollSync,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+        if let Some(sync) = &self.diff_scroll_sync {
+            sync.enabled.set(!sync.enabled.get());
+        }
+    }
+
+    /// Scrolls this editor, which shows a diff base, to the rows corresponding to the ones at
+    /// the top of the given editor of the diffed buffer.
+    fn follow_diff_scroll_position(
+        &mut self,
+        leader: &Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self
+            .diff_scroll_sync
+            .as_ref()
+            .is_some_and(|sync| sync.enabled.get())
+        {
+            return;
+        }
+
+        let Some((base_row, row_offset)) = leader.update(cx, |leader, cx| {
+            let scroll_position = leader.scroll_position(cx);
+            let snapshot = leader.display_map.update(cx, |map, cx| map.snapshot(cx));
+            let top_row = DisplayPoint::new(DisplayRow(scroll_position.y as u32), 0)
+                .to_point(&snapshot)
+                .row;
+            let multi_buffer = leader.buffer.read(cx);
+            let buffer = multi_buffer.as_singleton()?.read(cx).text_snapshot();
+            let diff = multi_buffer.diff_for(buffer.remote_id())?;
+            let base_row = diff
+                .read(cx)
+                .snapshot(cx)
+                .base_text_row_for_buffer_row(top_row, &buffer);
+            Some((base_row, scroll_position.y.fract()))
+        }) else {
+            return;
+        };
+
+        let snapshot = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let display_row = snapshot
+            .point_to_display_point(Point::new(base_row, 0), Bias::Left)
+            .row();
+        let scroll_x = self.scroll_position(cx).x;
+        self.set_scroll_position(
+            point(scroll_x, display_row.as_f32() + row_offset),
+            window,
+            cx,
+        );
+    }
+
+    /// Opens a menu at the cursor listing the operations available on this editor's diffs.
+    pub fn deploy_diff_quick_actions(
+        &mut self,
+        _: &DiffQuickActions,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let multi_buffer = self.buffer.read(cx);
+        if multi_buffer
+            .all_buffers()
+            .iter()
+            .all(|buffer| multi_buffer.diff_for(buffer.read(cx).remote_id()).is_none())
+        {
+            return;
+        }
+
+        let focus_handle = self.focus_handle(cx);
+        let can_toggle_diff_base =
+            !self.temporary_diff_override && self.project.is_some() && self.is_showing_diff(cx);
+        let diff_base_label = match self.diff_base {
+            DiffBase::Head => "Show Unstaged Changes",
+            DiffBase::Index | DiffBase::MergeBase => "Show All Uncommitted Changes",
+        };
+        let is_read_only = self.read_only(cx);
+        let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
+            menu.context(focus_handle)
+                .action("Go to Next Hunk", Box::new(GoToHunk))
+                .action("Go to Previous Hunk", Box::new(GoToPreviousHunk))
+                .separator()
+                .action("Toggle Selected Hunks", Box::new(ToggleSelectedDiffHunks))
+                .action("Expand All Hunks", Box::new(ExpandAllDiffHunks))
+                .action("Collapse All Hunks", Box::new(CollapseAllDiffHunks))
+                .separator()
+                .action("Stage or Unstage Hunk", Box::new(::git::ToggleStaged))
+                .action("Stage and Next", Box::new(::git::StageAndNext))
+                .action("Unstage and Next", Box::new(::git::UnstageAndNext))
+                .map(|menu| {
+                    if is_read_only {
+                        menu.disabled_action("Restore Hunk", Box::new(Restore))
+                    } else {
+                        menu.action("Restore Hunk", Box::new(Restore))
+                    }
+                })
+                .separator()
+                .map(|menu| {
+                    if can_toggle_diff_base {
+                        menu.action(diff_base_label, Box::new(ToggleDiffBase))
+                    } else {
+                        menu.disabled_action(diff_base_label, Box::new(ToggleDiffBase))
+                    }
+                })
+                .map(|menu| {
+                    if can_toggle_diff_base {
+                        menu.action("Cycle Diff Base", Box::new(CycleDiffBase))
+                    } else {
+                        menu.disabled_action("Cycle Diff Base", Box::new(CycleDiffBase))
+                    }
+                })
+                .action("Copy Diff Stats", Box::new(CopyDiffStats))
+        });
+
+        let character_size = self.character_size(window);
+        let menu_position = crate::mouse_context_menu::MenuPosition::PinnedToEditor {
+            source: self.selections.newest_anchor().head(),
+            offset: gpui::point(character_size.width, character_size.height),
+        };
+        self.mouse_context_menu = Some(MouseContextMenu::new(
+            self,
+            menu_position,
+            context_menu,
+            window,
+            cx,
+        ));
+        cx.notify();
+    }
+
     pub fn start_temporary_diff_override(&mut self) {
         self.load_diff_task.take();
         self.temporary_diff_override = true;
@@ -19345,9 +20570,10 @@ impl Editor {
 
         if let Some(project) = self.project.clone() {
             self.load_diff_task = Some(
-                update_uncommitted_diff_for_buffer(
+                update_diff_for_buffers(
                     cx.entity(),
                     &project,
+                    self.diff_base,
                     self.buffer.read(cx).all_buffers(),
                     self.buffer.clone(),
                     cx,
@@ -19376,6 +20602,7 @@ impl Editor {
         };
         self.set_max_diagnostics_severity(new_severity, cx);
         self.tasks_update_task = Some(self.refresh_runnables(window, cx));
+        self.refresh_hunk_numbers(cx);
         self.update_edit_prediction_settings(cx);
         self.refresh_inline_completion(true, false, window, cx);
         self.refresh_inlay_hints(
@@ -20567,9 +21794,64 @@ fn insert_extra_newline_tree_sitter(buffer: &MultiBufferSnapshot, range: Range<u
             .all(|c| c.is_whitespace() && c != '\n')
 }
 
-fn update_uncommitted_diff_for_buffer(
+/// Labels a syntax node that gives macro context to the code within it, for the languages
+/// that have such nodes.
+fn macro_context_label(
+    language: &language::LanguageName,
+    node: language::Node,
+    buffer: &language::BufferSnapshot,
+) -> Option<String> {
+    let text = |node: language::Node| buffer.text_for_range(node.byte_range()).collect::<String>();
+    // The path of an attribute is the first child of the attribute within the item.
+    let attribute_path = |node: language::Node| Some(text(node.named_child(0)?.named_child(0)?));
+    match (language.as_ref(), node.kind()) {
+        ("Rust", "macro_invocation") => {
+            Some(format!("{}!", text(node.child_by_field_name("macro")?)))
+        }
+        ("Rust", "attribute_item") => Some(format!("#[{}]", attribute_path(node)?)),
+        ("Rust", "inner_attribute_item") => Some(format!("#![{}]", attribute_path(node)?)),
+        // Outer attributes aren't children of the item they apply to, but its previous
+        // siblings.
+        ("Rust", _) => {
+            let mut attributes = Vec::new();
+            let mut sibling = node.prev_named_sibling();
+            while let Some(attribute) = sibling.filter(|node| node.kind() == "attribute_item") {
+                attributes.push(format!("#[{}]", attribute_path(attribute)?));
+                sibling = attribute.prev_named_sibling();
+            }
+            attributes.reverse();
+            (!attributes.is_empty()).then(|| attributes.join(" "))
+        }
+        _ => None,
+    }
+}
+
+/// Returns whether `test` is named the way tests of the symbol named `symbol` conventionally
+/// are, such as `test_parse` or `parse_test` for `parse`, and `TestParse` for `Parse`. Tests
+/// of one case of the symbol, such as `test_parse_empty_input`, also match.
+fn is_test_name_for_symbol(test: &str, symbol: &str) -> bool {
+    let subject = ["test_", "Test", "test"]
+        .into_iter()
+        .find_map(|prefix| test.strip_prefix(prefix))
+        .or_else(|| {
+            ["_test", "Test"]
+                .into_iter()
+                .find_map(|suffix| test.strip_suffix(suffix))
+        });
+    let Some(subject) = subject.filter(|subject| !subject.is_empty()) else {
+        return false;
+    };
+
+    subject.get(..symbol.len()).is_some_and(|name| {
+        name.eq_ignore_ascii_case(symbol)
+            && matches!(subject[symbol.len()..].chars().next(), None | Some('_'))
+    })
+}
+
+fn update_diff_for_buffers(
     editor: Entity<Editor>,
     project: &Entity<Project>,
+    diff_base: DiffBase,
     buffers: impl IntoIterator<Item = Entity<Buffer>>,
     buffer: Entity<MultiBuffer>,
     cx: &mut App,
@@ -20578,12 +21860,17 @@ fn update_uncommitted_diff_for_buffer(
     project.update(cx, |project, cx| {
         for buffer in buffers {
             if project::File::from_dyn(buffer.read(cx).file()).is_some() {
-                tasks.push(project.open_uncommitted_diff(buffer.clone(), cx))
+                tasks.push(match diff_base {
+                    DiffBase::Head => project.open_uncommitted_diff(buffer.clone(), cx),
+                    DiffBase::Index => project.open_unstaged_diff(buffer.clone(), cx),
+                    DiffBase::MergeBase => project.open_merge_base_diff(buffer.clone(), cx),
+                })
             }
         }
     });
     cx.spawn(async move |cx| {
-        let diffs = future::join_all(tasks).await;
+        let (diffs, errors): (Vec<_>, Vec<_>) =
+            future::join_all(tasks).await.into_iter().partition_result();
         if editor
             .read_with(cx, |editor, _cx| editor.temporary_diff_override)
             .unwrap_or(false)
@@ -20591,9 +21878,43 @@ fn update_uncommitted_diff_for_buffer(
             return;
         }
 
+        if diff_base == DiffBase::MergeBase {
+            // The buffers that failed would otherwise keep showing their previous diff under
+            // the merge-base's label, so the diff base is reverted instead.
+            if let Some(error) = errors.into_iter().next() {
+                let message = format!("Failed to diff against the merge-base: {error:#}");
+                log::error!("{message}");
+                editor
+                    .update(cx, |editor, cx| {
+                        if let Some(workspace) = editor.workspace() {
+                            workspace.update(cx, |workspace, cx| {
+                                struct MergeBaseDiffError;
+
+                                workspace.show_toast(
+                                    Toast::new(
+                                        NotificationId::unique::<MergeBaseDiffError>(),
+                                        message,
+                                    ),
+                                    cx,
+                                )
+                            });
+                        }
+                        if editor.diff_base == DiffBase::MergeBase {
+                            editor.set_diff_base(DiffBase::Head, cx);
+                        }
+                    })
+                    .ok();
+                return;
+            }
+        } else {
+            for error in errors {
+                log::error!("failed to open the diff: {error:#}");
+            }
+        }
+
         buffer
             .update(cx, |buffer, cx| {
-                for diff in diffs.into_iter().flatten() {
+                for diff in diffs {
                     buffer.add_diff(diff, cx);
                 }
             })
@@ -20601,6 +21922,60 @@ fn update_uncommitted_diff_for_buffer(
     })
 }
 
+/// Formats diff statistics the way `git diff --shortstat` does, omitting empty counts.
+fn format_diff_stats(files_changed: usize, insertions: u32, deletions: u32) -> String {
+    let mut summary = format!(
+        "{files_changed} {} changed",
+        if files_changed == 1 { "file" } else { "files" }
+    );
+    if insertions > 0 || deletions == 0 {
+        let noun = if insertions == 1 {
+            "insertion"
+        } else {
+            "insertions"
+        };
+        summary.push_str(&format!(", {insertions} {noun}(+)"));
+    }
+    if deletions > 0 {
+        let noun = if deletions == 1 {
+            "deletion"
+        } else {
+            "deletions"
+        };
+        summary.push_str(&format!(", {deletions} {noun}(-)"));
+    }
+    summary
+}
+
+/// Summarizes a diff hunk with the header that unified diffs introduce it with, followed by
+/// its line counts, such as `@@ -12,2 +12,3 @@ 2 removed, 3 added`.
+fn diff_hunk_summary(hunk: &MultiBufferDiffHunk, snapshot: &MultiBufferSnapshot) -> Option<String> {
+    use text::ToPoint as _;
+
+    // As in unified diffs, the lines are numbered from one, and an empty side starts at the line
+    // before the hunk.
+    let line_range = |range: Range<Point>| {
+        let count = range.end.row - range.start.row + u32::from(range.end.column > 0);
+        let start = if count == 0 {
+            range.start.row
+        } else {
+            range.start.row + 1
+        };
+        (start, count)
+    };
+
+    let buffer = snapshot.buffer_for_excerpt(hunk.excerpt_id)?;
+    let base_text = snapshot.diff_for_buffer_id(hunk.buffer_id)?.base_text();
+    let (old_start, old_count) = line_range(hunk.diff_base_byte_range.to_point(base_text));
+    let (new_start, new_count) = line_range(
+        hunk.buffer_range.start.to_point(buffer)..hunk.buffer_range.end.to_point(buffer),
+    );
+    Some(format!(
+        "@@ -{old_start},{old_count} +{new_start},{new_count} @@ \
+        {old_count} removed, {new_count} added"
+    ))
+}
+
 fn char_len_with_expanded_tabs(offset: usize, text: &str, tab_size: NonZeroU32) -> usize {
     let tab_size = tab_size.get() as usize;
     let mut width = offset;
@@ -20898,6 +22273,20 @@ fn wrap_with_prefix(
     wrapped_text
 }
 
+#[test]
+fn test_format_diff_stats() {
+    assert_eq!(
+        format_diff_stats(3, 42, 17),
+        "3 files changed, 42 insertions(+), 17 deletions(-)"
+    );
+    assert_eq!(format_diff_stats(1, 1, 0), "1 file changed, 1 insertion(+)");
+    assert_eq!(format_diff_stats(2, 0, 1), "2 files changed, 1 deletion(-)");
+    assert_eq!(
+        format_diff_stats(0, 0, 0),
+        "0 files changed, 0 insertions(+)"
+    );
+}
+
 #[test]
 fn test_wrap_with_prefix() {
     assert_eq!(
@@ -21952,6 +23341,9 @@ pub enum EditorEvent {
     Saved,
     TitleChanged,
     DiffBaseChanged,
+    BreadcrumbsChanged,
+    /// The Git blame of the editor's buffer was loaded or updated.
+    BlameUpdated,
     SelectionsChanged {
         local: bool,
     },
@@ -22887,6 +24279,8 @@ fn render_diff_hunk_controls(
     _window: &mut Window,
     cx: &mut App,
 ) -> AnyElement {
+    let hunk_number = editor.read(cx).hunk_numbers.get(&hunk_range.start).copied();
+
     h_flex()
         .h(line_height)
         .mr_1()
@@ -22901,6 +24295,13 @@ fn render_diff_hunk_controls(
         .gap_1()
         .block_mouse_except_scroll()
         .shadow_md()
+        .when_some(hunk_number, |el, hunk_number| {
+            el.child(
+                Label::new(format!("#{hunk_number}"))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+        })
         .child(if status.has_secondary_hunk() {
             Button::new(("stage", row as u64), "Stage")
                 .alpha(if status.is_pending() { 0.66 } else { 1.0 })
@@ -22980,7 +24381,7 @@ fn render_diff_hunk_controls(
                         });
                     }
                 })
-                .disabled(is_created_file),
+                .disabled(is_created_file || editor.read(cx).read_only(cx)),
         )
         .when(
             !editor.read(cx).buffer().read(cx).all_diff_hunks_expanded(),
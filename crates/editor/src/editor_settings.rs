@@ -24,6 +24,7 @@ pub struct EditorSettings {
     pub scrollbar: Scrollbar,
     pub minimap: Minimap,
     pub gutter: Gutter,
+    pub diff_gutter: DiffGutter,
     pub scroll_beyond_last_line: ScrollBeyondLastLine,
     pub vertical_scroll_margin: f32,
     pub autoscroll_on_clicks: bool,
@@ -178,11 +179,49 @@ impl Minimap {
 pub struct Gutter {
     pub min_line_number_digits: usize,
     pub line_numbers: bool,
+    pub diff_line_numbers: DiffLineNumbers,
     pub runnables: bool,
     pub breakpoints: bool,
     pub folds: bool,
 }
 
+/// Which line numbers are shown for the rows of a buffer with changes.
+///
+/// Default: new
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffLineNumbers {
+    /// Show the numbers of the lines in the diff base, leaving added lines unnumbered.
+    Old,
+    /// Show the numbers of the lines in the buffer, leaving deleted lines unnumbered.
+    #[default]
+    New,
+    /// Show the numbers of the lines in the diff base, followed by those in the buffer.
+    Both,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct DiffGutter {
+    pub width: Option<f32>,
+    pub marker_style: DiffGutterMarkerStyle,
+}
+
+/// How changed lines are marked in the gutter.
+///
+/// Default: bar
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffGutterMarkerStyle {
+    /// A thin bar colored by the kind of change.
+    #[default]
+    Bar,
+    /// A block filling the space to the left of the line numbers.
+    Block,
+    /// A `+`, `~` or `−` sign for added, modified and deleted lines, so that
+    /// the kind of change doesn't need to be told apart by color.
+    Sign,
+}
+
 /// When to show the scrollbar in the editor.
 ///
 /// Default: auto
@@ -336,6 +375,73 @@ pub struct SearchSettings {
     pub regex: bool,
 }
 
+/// A kind of symbol shown in the breadcrumbs, as determined by the syntax node spanning it.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BreadcrumbSymbolKind {
+    /// Functions and methods.
+    Function,
+    /// Types and their implementations, such as structs, enums, classes, traits and
+    /// interfaces.
+    Type,
+    /// Modules and namespaces.
+    Module,
+}
+
+impl BreadcrumbSymbolKind {
+    /// Returns the kind of symbol a syntax node of the given kind declares, such as
+    /// `function_item` in Rust or `class_declaration` in TypeScript.
+    ///
+    /// Only declarations are recognized, so expressions such as closures and the type
+    /// annotations of other symbols are of no kind.
+    pub fn for_node_kind(node_kind: &str) -> Option<Self> {
+        match node_kind {
+            "function_item"
+            | "function_signature_item"
+            | "function_declaration"
+            | "function_definition"
+            | "generator_function_declaration"
+            | "method_declaration"
+            | "method_definition"
+            | "method_signature"
+            | "abstract_method_signature"
+            | "method"
+            | "singleton_method"
+            | "constructor_declaration" => Some(Self::Function),
+            "struct_item"
+            | "enum_item"
+            | "union_item"
+            | "trait_item"
+            | "impl_item"
+            | "type_item"
+            | "struct_specifier"
+            | "enum_specifier"
+            | "union_specifier"
+            | "class_specifier"
+            | "class"
+            | "class_declaration"
+            | "abstract_class_declaration"
+            | "class_definition"
+            | "interface_declaration"
+            | "enum_declaration"
+            | "record_declaration"
+            | "struct_declaration"
+            | "protocol_declaration"
+            | "type_alias_declaration"
+            | "type_declaration"
+            | "type_spec" => Some(Self::Type),
+            "mod_item"
+            | "module"
+            | "internal_module"
+            | "namespace_definition"
+            | "namespace_declaration"
+            | "package_declaration"
+            | "package_clause" => Some(Self::Module),
+            _ => None,
+        }
+    }
+}
+
 /// What to do when go to definition yields no results.
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -427,6 +533,8 @@ pub struct EditorSettingsContent {
     pub minimap: Option<MinimapContent>,
     /// Gutter related settings
     pub gutter: Option<GutterContent>,
+    /// Settings for the diff markers in the gutter
+    pub diff_gutter: Option<DiffGutterContent>,
     /// Whether the editor will scroll beyond the last line.
     ///
     /// Default: one_page
@@ -664,6 +772,11 @@ pub struct GutterContent {
     ///
     /// Default: true
     pub line_numbers: Option<bool>,
+    /// Which line numbers to show for the rows of a buffer with changes, when
+    /// line numbers are shown.
+    ///
+    /// Default: new
+    pub diff_line_numbers: Option<DiffLineNumbers>,
     /// Minimum number of characters to reserve space for in the gutter.
     ///
     /// Default: 4
@@ -682,6 +795,21 @@ pub struct GutterContent {
     pub folds: Option<bool>,
 }
 
+/// Settings for the diff markers in the gutter
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct DiffGutterContent {
+    /// The width of the diff markers in pixels. When null, bars are
+    /// proportional to the line height, and blocks and signs fill the space
+    /// to the left of the line numbers.
+    ///
+    /// Default: null
+    pub width: Option<f32>,
+    /// How changed lines are marked.
+    ///
+    /// Default: bar
+    pub marker_style: Option<DiffGutterMarkerStyle>,
+}
+
 impl EditorSettings {
     pub fn jupyter_enabled(cx: &App) -> bool {
         EditorSettings::get_global(cx).jupyter.enabled
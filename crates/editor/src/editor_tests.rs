@@ -7066,6 +7066,162 @@ async fn test_select_previous_with_single_selection(cx: &mut TestAppContext) {
     cx.assert_editor_state("«ˇabc»\n«ˇabc» «ˇabc»\ndef«ˇabc»\n«ˇabc»");
 }
 
+#[gpui::test]
+async fn test_macro_context_at_cursor(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::LANGUAGE.into()),
+    ));
+
+    let text = r#"
+        #[derive(Debug)]
+        struct Point { x: i32 }
+
+        fn main() {
+            let v = vec![1, 2];
+            let w = 3;
+        }
+
+        #[tokio::main]
+        #[allow(dead_code)]
+        async fn serve() {
+            let x = 1;
+        }
+    "#
+    .unindent();
+
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| build_editor(buffer, window, cx));
+
+    editor
+        .condition::<crate::EditorEvent>(cx, |editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    let mut macro_context_at = |row: u32, column: u32| {
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(None, window, cx, |s| {
+                let point = DisplayPoint::new(DisplayRow(row), column);
+                s.select_display_ranges([point..point]);
+            });
+            editor.macro_context_at_cursor(cx)
+        })
+    };
+    assert_eq!(macro_context_at(0, 10).as_deref(), Some("#[derive]"));
+    assert_eq!(macro_context_at(1, 15).as_deref(), Some("#[derive]"));
+    assert_eq!(macro_context_at(4, 17).as_deref(), Some("vec!"));
+    assert_eq!(macro_context_at(5, 8), None);
+    assert_eq!(
+        macro_context_at(11, 8).as_deref(),
+        Some("#[tokio::main] #[allow]")
+    );
+}
+
+#[gpui::test]
+async fn test_innermost_symbol_name_position(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::LANGUAGE.into()),
+        )
+        .with_outline_query(
+            r#"
+            (function_item "fn" @context name: (_) @name) @item
+            (impl_item "impl" @context trait: (_)? @context "for"? @context type: (_) @name) @item
+            "#,
+        )
+        .unwrap(),
+    );
+
+    let text = r#"
+        fn f() {
+            1
+        }
+
+        impl Foo for Foo {
+            const N: u32 = 2;
+        }
+    "#
+    .unindent();
+
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| build_editor(buffer, window, cx));
+
+    editor
+        .condition::<crate::EditorEvent>(cx, |editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    let mut name_position_at = |row: u32, column: u32| {
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(None, window, cx, |s| {
+                let point = DisplayPoint::new(DisplayRow(row), column);
+                s.select_display_ranges([point..point]);
+            });
+            let (buffer, position) = editor.innermost_symbol_name_position(cx)?;
+            Some(position.to_point(&buffer.read(cx).snapshot()))
+        })
+    };
+    // The names are found by their position in the syntax tree, rather than by searching the
+    // symbol's text, where "f" also starts `fn` and "Foo" also names the trait.
+    assert_eq!(name_position_at(1, 4), Some(Point::new(0, 3)));
+    assert_eq!(name_position_at(5, 4), Some(Point::new(4, 13)));
+}
+
+#[gpui::test]
+async fn test_comment_at_cursor(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            line_comments: vec!["// ".into(), "/// ".into()],
+            ..Default::default()
+        },
+        None,
+    ));
+
+    let text = r#"
+        /// Adds two numbers.
+        fn add(a: i32, b: i32) -> i32 {
+            // TODO: handle overflow
+            // by saturating.
+            a + b
+        }
+    "#
+    .unindent();
+
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+    let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+    let (editor, cx) = cx.add_window_view(|window, cx| build_editor(buffer, window, cx));
+
+    let mut comment_at = |row: u32, column: u32| {
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(None, window, cx, |s| {
+                let point = DisplayPoint::new(DisplayRow(row), column);
+                s.select_display_ranges([point..point]);
+            });
+            editor.comment_at_cursor(cx)
+        })
+    };
+    assert_eq!(comment_at(0, 5).as_deref(), Some("Adds two numbers."));
+    assert_eq!(comment_at(1, 5), None);
+    assert_eq!(
+        comment_at(3, 10).as_deref(),
+        Some("TODO: handle overflow\nby saturating.")
+    );
+    assert_eq!(comment_at(4, 4), None);
+}
+
 #[gpui::test]
 async fn test_select_larger_smaller_syntax_node(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -14380,6 +14536,22 @@ fn test_split_words() {
     assert_eq!(split(":do_the_thing"), &[":", "do_", "the_", "thing"]);
 }
 
+#[test]
+fn test_is_test_name_for_symbol() {
+    assert!(is_test_name_for_symbol("test_parse", "parse"));
+    assert!(is_test_name_for_symbol("parse_test", "parse"));
+    assert!(is_test_name_for_symbol("test_parse_empty_input", "parse"));
+    assert!(is_test_name_for_symbol("TestParse", "Parse"));
+    assert!(is_test_name_for_symbol("testParse", "parse"));
+    assert!(is_test_name_for_symbol("ParseTest", "Parse"));
+
+    assert!(!is_test_name_for_symbol("parse", "parse"));
+    assert!(!is_test_name_for_symbol("test_parser", "parse"));
+    assert!(!is_test_name_for_symbol("parser_test", "parse"));
+    assert!(!is_test_name_for_symbol("test_", "parse"));
+    assert!(!is_test_name_for_symbol("test_format", "parse"));
+}
+
 #[gpui::test]
 async fn test_move_to_enclosing_bracket(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -16401,6 +16573,177 @@ async fn test_mutlibuffer_in_navigation_history(cx: &mut TestAppContext) {
         .unwrap();
 }
 
+#[gpui::test]
+async fn test_is_showing_diff(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("ˇone\nTWO\nthree\n");
+    cx.set_head_text("one\ntwo\nthree\n");
+    executor.run_until_parked();
+    cx.update_editor(|editor, _, cx| assert!(!editor.is_showing_diff(cx)));
+
+    // The diff base can only be changed while a diff is shown.
+    cx.update_editor(|editor, window, cx| {
+        editor.toggle_diff_base(&ToggleDiffBase, window, cx);
+        assert_eq!(editor.diff_base(), DiffBase::Head);
+    });
+
+    cx.update_editor(|editor, window, cx| {
+        editor.expand_all_diff_hunks(&ExpandAllDiffHunks, window, cx);
+    });
+    executor.run_until_parked();
+    cx.update_editor(|editor, window, cx| {
+        assert!(editor.is_showing_diff(cx));
+        editor.toggle_diff_base(&ToggleDiffBase, window, cx);
+        assert_eq!(editor.diff_base(), DiffBase::Index);
+        editor.toggle_diff_base(&ToggleDiffBase, window, cx);
+    });
+    executor.run_until_parked();
+
+    cx.update_editor(|editor, _, cx| {
+        editor.buffer().update(cx, |buffer, cx| {
+            buffer.collapse_diff_hunks(vec![Anchor::min()..Anchor::max()], cx)
+        });
+    });
+    executor.run_until_parked();
+    cx.update_editor(|editor, _, cx| assert!(!editor.is_showing_diff(cx)));
+
+    // Diff views expand every hunk, even before any hunk exists.
+    cx.set_head_text("one\nTWO\nthree\n");
+    executor.run_until_parked();
+    cx.update_editor(|editor, _, cx| {
+        editor.set_expand_all_diff_hunks(cx);
+        assert!(editor.is_showing_diff(cx));
+    });
+}
+
+#[gpui::test]
+async fn test_collapse_all_diff_hunks(executor: BackgroundExecutor, cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("ˇone\nTWO\nTHREE\nfour\nfive\nSIX\n");
+    cx.set_head_text("one\ntwo\nthree\nfour\nfive\nsix\n");
+    executor.run_until_parked();
+
+    cx.update_editor(|editor, window, cx| {
+        editor.expand_all_diff_hunks(&ExpandAllDiffHunks, window, cx);
+    });
+    executor.run_until_parked();
+    cx.update_editor(|editor, _, cx| {
+        assert_eq!(
+            editor.display_text(cx),
+            "one\ntwo\nthree\nTWO\nTHREE\nfour\nfive\nsix\nSIX\n"
+        );
+    });
+
+    // Each hunk is folded into a single row summarizing it.
+    cx.update_editor(|editor, window, cx| {
+        editor.collapse_all_diff_hunks(&CollapseAllDiffHunks, window, cx);
+    });
+    executor.run_until_parked();
+    cx.update_editor(|editor, _, cx| {
+        assert_eq!(editor.display_text(cx), "one\n⋯\nfour\nfive\n⋯\n");
+
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let summaries = snapshot
+            .diff_hunks()
+            .filter_map(|hunk| diff_hunk_summary(&hunk, &snapshot))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summaries,
+            [
+                "@@ -2,2 +2,2 @@ 2 removed, 2 added",
+                "@@ -6,1 +6,1 @@ 1 removed, 1 added",
+            ]
+        );
+    });
+
+    cx.update_editor(|editor, window, cx| {
+        editor.expand_all_diff_hunks(&ExpandAllDiffHunks, window, cx);
+    });
+    executor.run_until_parked();
+    cx.update_editor(|editor, _, cx| {
+        assert_eq!(
+            editor.display_text(cx),
+            "one\ntwo\nthree\nTWO\nTHREE\nfour\nfive\nsix\nSIX\n"
+        );
+    });
+
+    // Folded hunks are unfolded when the diff is recomputed.
+    cx.update_editor(|editor, window, cx| {
+        editor.collapse_all_diff_hunks(&CollapseAllDiffHunks, window, cx);
+    });
+    executor.run_until_parked();
+    cx.update_editor(|editor, _, cx| {
+        assert_eq!(editor.display_text(cx), "one\n⋯\nfour\nfive\n⋯\n");
+    });
+    cx.set_head_text("one\ntwo\nthree\nfour\nfive\nSIX\n");
+    executor.run_until_parked();
+    cx.update_editor(|editor, _, cx| {
+        assert_eq!(
+            editor.display_text(cx),
+            "one\nTWO\nTHREE\nfour\nfive\nSIX\n"
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_collapse_all_diff_hunks_with_deleted_lines(
+    executor: BackgroundExecutor,
+    cx: &mut TestAppContext,
+) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("ˇone\nTWO\nfour\nsix\n");
+    cx.set_head_text("one\ntwo\nthree\nfour\nfive\nsix\n");
+    executor.run_until_parked();
+
+    // Hunks that only delete lines stay collapsed when they are.
+    cx.update_editor(|editor, window, cx| {
+        editor.collapse_all_diff_hunks(&CollapseAllDiffHunks, window, cx);
+    });
+    executor.run_until_parked();
+    cx.update_editor(|editor, _, cx| {
+        assert_eq!(editor.display_text(cx), "one\n⋯\nfour\nsix\n");
+    });
+
+    // Their deleted lines are folded when shown.
+    cx.update_editor(|editor, window, cx| {
+        editor.expand_all_diff_hunks(&ExpandAllDiffHunks, window, cx);
+    });
+    executor.run_until_parked();
+    cx.update_editor(|editor, _, cx| {
+        assert_eq!(
+            editor.display_text(cx),
+            "one\ntwo\nthree\nTWO\nfour\nfive\nsix\n"
+        );
+    });
+    cx.update_editor(|editor, window, cx| {
+        editor.collapse_all_diff_hunks(&CollapseAllDiffHunks, window, cx);
+    });
+    executor.run_until_parked();
+    cx.update_editor(|editor, _, cx| {
+        assert_eq!(editor.display_text(cx), "one\n⋯\nfour\n⋯\nsix\n");
+    });
+
+    // Views that always expand hunks fold their deleted lines as well.
+    cx.update_editor(|editor, window, cx| {
+        editor.expand_all_diff_hunks(&ExpandAllDiffHunks, window, cx);
+        editor.set_expand_all_diff_hunks(cx);
+    });
+    executor.run_until_parked();
+    cx.update_editor(|editor, window, cx| {
+        editor.collapse_all_diff_hunks(&CollapseAllDiffHunks, window, cx);
+    });
+    executor.run_until_parked();
+    cx.update_editor(|editor, _, cx| {
+        assert_eq!(editor.display_text(cx), "one\n⋯\nfour\n⋯\nsix\n");
+    });
+}
+
 #[gpui::test]
 async fn test_toggle_selected_diff_hunks(executor: BackgroundExecutor, cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -16,9 +16,9 @@ use crate::{
         HighlightedChunk, ToDisplayPoint,
     },
     editor_settings::{
-        CurrentLineHighlight, DocumentColorsRenderMode, DoubleClickInMultibuffer, Minimap,
-        MinimapThumb, MinimapThumbBorder, ScrollBeyondLastLine, ScrollbarAxes,
-        ScrollbarDiagnostics, ShowMinimap, ShowScrollbar,
+        CurrentLineHighlight, DiffGutterMarkerStyle, DiffLineNumbers, DocumentColorsRenderMode,
+        DoubleClickInMultibuffer, Minimap, MinimapThumb, MinimapThumbBorder, ScrollBeyondLastLine,
+        ScrollbarAxes, ScrollbarDiagnostics, ShowMinimap, ShowScrollbar,
     },
     git::blame::{BlameRenderer, GitBlame, GlobalBlameRenderer},
     hover_popover::{
@@ -406,6 +406,11 @@ impl EditorElement {
                 .detach_and_log_err(cx);
         });
         register_action(editor, window, Editor::open_url);
+        register_action(
+            editor,
+            window,
+            Editor::open_referenced_issue_or_pull_request,
+        );
         register_action(editor, window, Editor::open_selected_filename);
         register_action(editor, window, Editor::fold);
         register_action(editor, window, Editor::fold_at_level);
@@ -448,18 +453,27 @@ impl EditorElement {
         register_action(editor, window, Editor::copy_relative_path);
         register_action(editor, window, Editor::copy_file_name);
         register_action(editor, window, Editor::copy_file_name_without_extension);
+        register_action(editor, window, Editor::copy_diff_stats);
         register_action(editor, window, Editor::copy_highlight_json);
         register_action(editor, window, Editor::copy_permalink_to_line);
         register_action(editor, window, Editor::open_permalink_to_line);
+        register_action(editor, window, Editor::open_blame_to_line);
+        register_action(editor, window, Editor::show_code_owners);
         register_action(editor, window, Editor::copy_file_location);
         register_action(editor, window, Editor::toggle_git_blame);
         register_action(editor, window, Editor::toggle_git_blame_inline);
         register_action(editor, window, Editor::open_git_blame_commit);
         register_action(editor, window, Editor::toggle_selected_diff_hunks);
+        register_action(editor, window, Editor::toggle_diff_base);
+        register_action(editor, window, Editor::cycle_diff_base);
+        register_action(editor, window, Editor::split_diff_into_tabs);
+        register_action(editor, window, Editor::toggle_diff_tabs_scroll_sync);
+        register_action(editor, window, Editor::deploy_diff_quick_actions);
         register_action(editor, window, Editor::toggle_staged_selected_diff_hunks);
         register_action(editor, window, Editor::stage_and_next);
         register_action(editor, window, Editor::unstage_and_next);
         register_action(editor, window, Editor::expand_all_diff_hunks);
+        register_action(editor, window, Editor::collapse_all_diff_hunks);
         register_action(editor, window, Editor::go_to_previous_change);
         register_action(editor, window, Editor::go_to_next_change);
 
@@ -2053,10 +2067,16 @@ impl EditorElement {
             .git_gutter
             .unwrap_or_default();
         if let GitGutterSetting::TrackedFiles = git_gutter_setting {
+            let marker_width = self.diff_marker_width(line_height, cx);
             for (hunk, hitbox) in &mut display_hunks {
                 if matches!(hunk, DisplayDiffHunk::Unfolded { .. }) {
-                    let hunk_bounds =
-                        Self::diff_hunk_bounds(snapshot, line_height, gutter_hitbox.bounds, hunk);
+                    let hunk_bounds = Self::diff_hunk_bounds(
+                        snapshot,
+                        line_height,
+                        marker_width,
+                        gutter_hitbox.bounds,
+                        hunk,
+                    );
                     *hitbox = Some(window.insert_hitbox(hunk_bounds, HitboxBehavior::BlockMouse));
                 }
             }
@@ -3069,6 +3089,8 @@ impl EditorElement {
             None
         };
         let relative_rows = self.calculate_relative_line_numbers(snapshot, &rows, relative_to);
+        let diff_line_numbers = diff_line_numbers(snapshot, cx);
+        let column_width = diff_line_number_digits(snapshot) as usize;
         let mut line_number = String::new();
         let line_numbers = buffer_rows
             .into_iter()
@@ -3076,16 +3098,25 @@ impl EditorElement {
             .flat_map(|(ix, row_info)| {
                 let display_row = DisplayRow(rows.start.0 + ix as u32);
                 line_number.clear();
-                let non_relative_number = row_info.buffer_row? + 1;
-                let number = relative_rows
-                    .get(&display_row)
-                    .unwrap_or(&non_relative_number);
-                write!(&mut line_number, "{number}").unwrap();
-                if row_info
-                    .diff_status
-                    .is_some_and(|status| status.is_deleted())
-                {
-                    return None;
+                let (old_row, new_row) = diff_rows(snapshot, row_info, diff_line_numbers)?;
+                let old_number = old_row.map(|row| row + 1);
+                let new_number =
+                    new_row.map(|row| relative_rows.get(&display_row).copied().unwrap_or(row + 1));
+                match diff_line_numbers {
+                    DiffLineNumbers::New => write!(&mut line_number, "{}", new_number?).unwrap(),
+                    DiffLineNumbers::Old => write!(&mut line_number, "{}", old_number?).unwrap(),
+                    // Pad both columns, so that they stay aligned when one side is missing.
+                    DiffLineNumbers::Both => write!(
+                        &mut line_number,
+                        "{:>column_width$} {:>column_width$}",
+                        old_number
+                            .map(|number| number.to_string())
+                            .unwrap_or_default(),
+                        new_number
+                            .map(|number| number.to_string())
+                            .unwrap_or_default(),
+                    )
+                    .unwrap(),
                 }
 
                 let color = active_rows
@@ -5399,12 +5430,20 @@ impl EditorElement {
         }
     }
 
-    fn paint_gutter_diff_hunks(layout: &mut EditorLayout, window: &mut Window, cx: &mut App) {
+    fn paint_gutter_diff_hunks(
+        &self,
+        layout: &mut EditorLayout,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
         if layout.display_hunks.is_empty() {
             return;
         }
 
         let line_height = layout.position_map.line_height;
+        let marker_width = self.diff_marker_width(line_height, cx);
+        let paints_signs =
+            EditorSettings::get_global(cx).diff_gutter.marker_style == DiffGutterMarkerStyle::Sign;
         window.paint_layer(layout.gutter_hitbox.bounds, |window| {
             for (hunk, hitbox) in &layout.display_hunks {
                 let hunk_to_paint = match hunk {
@@ -5412,6 +5451,7 @@ impl EditorElement {
                         let hunk_bounds = Self::diff_hunk_bounds(
                             &layout.position_map.snapshot,
                             line_height,
+                            marker_width,
                             layout.gutter_hitbox.bounds,
                             &hunk,
                         );
@@ -5439,12 +5479,16 @@ impl EditorElement {
                             Corners::all(px(0.)),
                             *status,
                         ),
-                        DiffHunkStatusKind::Deleted if !display_row_range.is_empty() => (
-                            hunk_hitbox.bounds,
-                            cx.theme().colors().version_control_deleted,
-                            Corners::all(px(0.)),
-                            *status,
-                        ),
+                        DiffHunkStatusKind::Deleted
+                            if !display_row_range.is_empty() || paints_signs =>
+                        {
+                            (
+                                hunk_hitbox.bounds,
+                                cx.theme().colors().version_control_deleted,
+                                Corners::all(px(0.)),
+                                *status,
+                            )
+                        }
                         DiffHunkStatusKind::Deleted => (
                             Bounds::new(
                                 point(
@@ -5461,6 +5505,18 @@ impl EditorElement {
                 };
 
                 if let Some((hunk_bounds, background_color, corner_radii, status)) = hunk_to_paint {
+                    if paints_signs {
+                        self.paint_diff_hunk_signs(
+                            hunk_bounds,
+                            background_color,
+                            status,
+                            line_height,
+                            window,
+                            cx,
+                        );
+                        continue;
+                    }
+
                     // Flatten the background color with the editor color to prevent
                     // elements below transparent hunks from showing through
                     let flattened_background_color = cx
@@ -5499,26 +5555,70 @@ impl EditorElement {
         });
     }
 
+    /// Paints a sign for every row of the hunk, centered within the marker's bounds so that signs
+    /// line up with the line numbers of their rows.
+    fn paint_diff_hunk_signs(
+        &self,
+        hunk_bounds: Bounds<Pixels>,
+        color: Hsla,
+        status: DiffHunkStatus,
+        line_height: Pixels,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let sign = match status.kind {
+            DiffHunkStatusKind::Added => "+",
+            DiffHunkStatusKind::Modified => "~",
+            DiffHunkStatusKind::Deleted => "\u{2212}",
+        };
+        let color = if Self::diff_hunk_hollow(status, cx) {
+            color.opacity(0.5)
+        } else {
+            color
+        };
+        let shaped_sign = self.shape_line_number(sign.into(), color, window);
+        let x =
+            hunk_bounds.origin.x + ((hunk_bounds.size.width - shaped_sign.width) / 2.).max(px(0.));
+        let row_count = (hunk_bounds.size.height / line_height).round().max(1.) as usize;
+        for row in 0..row_count {
+            let origin = point(x, hunk_bounds.origin.y + line_height * row as f32);
+            shaped_sign.paint(origin, line_height, window, cx).log_err();
+        }
+    }
+
     fn gutter_strip_width(line_height: Pixels) -> Pixels {
         (0.275 * line_height).floor()
     }
 
+    /// Returns the width of the diff hunk markers in the gutter, which never extend into the
+    /// line numbers.
+    fn diff_marker_width(&self, line_height: Pixels, cx: &App) -> Pixels {
+        let diff_gutter = EditorSettings::get_global(cx).diff_gutter;
+        let available_width = self.editor.read(cx).gutter_dimensions.left_padding;
+        let width = match (diff_gutter.width, diff_gutter.marker_style) {
+            (Some(width), _) => px(width.max(1.)),
+            (None, DiffGutterMarkerStyle::Bar) => Self::gutter_strip_width(line_height),
+            (None, DiffGutterMarkerStyle::Block | DiffGutterMarkerStyle::Sign) => available_width,
+        };
+        width.min(available_width)
+    }
+
     fn diff_hunk_bounds(
         snapshot: &EditorSnapshot,
         line_height: Pixels,
+        marker_width: Pixels,
         gutter_bounds: Bounds<Pixels>,
         hunk: &DisplayDiffHunk,
     ) -> Bounds<Pixels> {
         let scroll_position = snapshot.scroll_position();
         let scroll_top = scroll_position.y * line_height;
-        let gutter_strip_width = Self::gutter_strip_width(line_height);
 
         match hunk {
             DisplayDiffHunk::Folded { display_row, .. } => {
                 let start_y = display_row.as_f32() * line_height - scroll_top;
                 let end_y = start_y + line_height;
                 let highlight_origin = gutter_bounds.origin + point(px(0.), start_y);
-                let highlight_size = size(gutter_strip_width, end_y - start_y);
+                let highlight_size = size(marker_width, end_y - start_y);
                 Bounds::new(highlight_origin, highlight_size)
             }
             DisplayDiffHunk::Unfolded {
@@ -5533,7 +5633,7 @@ impl EditorElement {
                     let start_y = row.as_f32() * line_height - offset - scroll_top;
                     let end_y = start_y + line_height;
 
-                    let width = (0.35 * line_height).floor();
+                    let width = marker_width.max((0.35 * line_height).floor());
                     let highlight_origin = gutter_bounds.origin + point(px(0.), start_y);
                     let highlight_size = size(width, end_y - start_y);
                     Bounds::new(highlight_origin, highlight_size)
@@ -5561,7 +5661,7 @@ impl EditorElement {
                     let end_y = end_row_in_current_excerpt.as_f32() * line_height - scroll_top;
 
                     let highlight_origin = gutter_bounds.origin + point(px(0.), start_y);
-                    let highlight_size = size(gutter_strip_width, end_y - start_y);
+                    let highlight_size = size(marker_width, end_y - start_y);
                     Bounds::new(highlight_origin, highlight_size)
                 }
             }
@@ -5628,7 +5728,7 @@ impl EditorElement {
                 )
             });
         if show_git_gutter {
-            Self::paint_gutter_diff_hunks(layout, window, cx)
+            self.paint_gutter_diff_hunks(layout, window, cx)
         }
 
         let highlight_width = 0.275 * layout.position_map.line_height;
@@ -6883,8 +6983,12 @@ impl EditorElement {
         window: &mut Window,
         cx: &mut App,
     ) -> Pixels {
-        let digit_count = snapshot.widest_line_number().ilog10() + 1;
-        self.column_pixels(digit_count as usize, window, cx)
+        let column_count = match diff_line_numbers(snapshot, cx) {
+            DiffLineNumbers::Both => 2 * diff_line_number_digits(snapshot) + 1,
+            DiffLineNumbers::Old => diff_line_number_digits(snapshot),
+            DiffLineNumbers::New => snapshot.widest_line_number().ilog10() + 1,
+        };
+        self.column_pixels(column_count as usize, window, cx)
     }
 
     fn shape_line_number(
@@ -6971,6 +7075,58 @@ impl AcceptEditPredictionBinding {
     }
 }
 
+/// Returns which line numbers the gutter shows. Without any changes, the lines of the diff base
+/// and the buffer have the same numbers, so only the buffer's are shown.
+fn diff_line_numbers(snapshot: &EditorSnapshot, cx: &App) -> DiffLineNumbers {
+    if snapshot.buffer_snapshot.has_diff_hunks() {
+        EditorSettings::get_global(cx).gutter.diff_line_numbers
+    } else {
+        DiffLineNumbers::New
+    }
+}
+
+/// Returns the number of digits in the widest line number of either the buffers or their diff
+/// bases, so that old and new line numbers share a column width.
+fn diff_line_number_digits(snapshot: &EditorSnapshot) -> u32 {
+    let widest_line_number = cmp::max(
+        snapshot.widest_line_number(),
+        snapshot.buffer_snapshot.widest_diff_base_line_number(),
+    );
+    widest_line_number.ilog10() + 1
+}
+
+/// Returns the rows of the diff base and of the buffer that a row of the gutter shows, either of
+/// which is missing when the row only exists on the other side, or `None` for rows without any.
+fn diff_rows(
+    snapshot: &EditorSnapshot,
+    row_info: &RowInfo,
+    diff_line_numbers: DiffLineNumbers,
+) -> Option<(Option<u32>, Option<u32>)> {
+    let buffer_row = row_info.buffer_row?;
+    // Deleted rows come from the diff base. The status of the other rows is only known when
+    // their hunk is expanded, so whether they changed is taken from the diff itself.
+    Some(match row_info.diff_status {
+        Some(status) if status.is_deleted() => (Some(buffer_row), None),
+        _ if diff_line_numbers == DiffLineNumbers::New => (None, Some(buffer_row)),
+        _ => (
+            row_info
+                .multibuffer_row
+                .and_then(|row| unchanged_base_text_row(snapshot, row)),
+            Some(buffer_row),
+        ),
+    })
+}
+
+/// Returns the row of the diff base that a row of the multibuffer is unchanged from, if any.
+fn unchanged_base_text_row(snapshot: &EditorSnapshot, row: MultiBufferRow) -> Option<u32> {
+    let buffer_snapshot = &snapshot.buffer_snapshot;
+    let (buffer, point, _) = buffer_snapshot.point_to_buffer_point(Point::new(row.0, 0))?;
+    let Some(diff) = buffer_snapshot.diff_for_buffer_id(buffer.remote_id()) else {
+        return Some(point.row);
+    };
+    diff.unchanged_base_text_row(point.row, buffer)
+}
+
 fn prepaint_gutter_button(
     button: IconButton,
     row: DisplayRow,
@@ -7858,8 +8014,10 @@ impl Element for EditorElement {
         window.with_rem_size(rem_size, |window| {
             window.with_text_style(Some(text_style), |window| {
                 window.with_content_mask(Some(ContentMask { bounds }), |window| {
-                    let (mut snapshot, is_read_only) = self.editor.update(cx, |editor, cx| {
-                        (editor.snapshot(window, cx), editor.read_only(cx))
+                    let (mut snapshot, hide_hunk_controls) = self.editor.update(cx, |editor, cx| {
+                        let hide_hunk_controls =
+                            editor.read_only(cx) && !editor.allows_staging_while_read_only();
+                        (editor.snapshot(window, cx), hide_hunk_controls)
                     });
                     let style = self.style.clone();
 
@@ -8833,7 +8991,7 @@ impl Element for EditorElement {
 
                     let mode = snapshot.mode.clone();
 
-                    let (diff_hunk_controls, diff_hunk_control_bounds) = if is_read_only {
+                    let (diff_hunk_controls, diff_hunk_control_bounds) = if hide_hunk_controls {
                         (vec![], vec![])
                     } else {
                         self.layout_diff_hunk_controls(
@@ -10045,6 +10203,7 @@ mod tests {
         Editor, MultiBuffer,
         display_map::{BlockPlacement, BlockProperties},
         editor_tests::{init_test, update_test_language_settings},
+        test::editor_test_context::EditorTestContext,
     };
     use gpui::{TestAppContext, VisualTestContext};
     use language::language_settings;
@@ -10152,6 +10311,75 @@ mod tests {
         assert_eq!(relative_rows[&DisplayRow(2)], 3);
     }
 
+    #[gpui::test]
+    async fn test_diff_rows(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+        cx.set_head_text("one\ntwo\nthree\nfour\n");
+        cx.set_state("ˇone\nTWO\nadded\nthree\nfour\n");
+        cx.run_until_parked();
+
+        let rows = |diff_line_numbers, cx: &mut EditorTestContext| {
+            cx.update_editor(|editor, window, cx| {
+                let snapshot = editor.snapshot(window, cx);
+                snapshot
+                    .row_infos(DisplayRow(0))
+                    .filter_map(|row_info| diff_rows(&snapshot, &row_info, diff_line_numbers))
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        // Rows of collapsed hunks have no diff status, but still have no old row.
+        assert_eq!(
+            rows(DiffLineNumbers::Both, &mut cx),
+            [
+                (Some(0), Some(0)),
+                (None, Some(1)),
+                (None, Some(2)),
+                (Some(2), Some(3)),
+                (Some(3), Some(4)),
+                (Some(4), Some(5)),
+            ]
+        );
+        assert_eq!(
+            rows(DiffLineNumbers::New, &mut cx),
+            (0..6).map(|row| (None, Some(row))).collect::<Vec<_>>()
+        );
+
+        cx.update_editor(|editor, window, cx| {
+            editor.expand_all_diff_hunks(&crate::actions::ExpandAllDiffHunks, window, cx);
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            rows(DiffLineNumbers::Both, &mut cx),
+            [
+                (Some(0), Some(0)),
+                (Some(1), None),
+                (None, Some(1)),
+                (None, Some(2)),
+                (Some(2), Some(3)),
+                (Some(3), Some(4)),
+                (Some(4), Some(5)),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_diff_line_number_digits(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+        // The diff base has more lines than the buffer, so its line numbers are wider.
+        cx.set_head_text(&"line\n".repeat(12));
+        cx.set_state("ˇline\n");
+        cx.run_until_parked();
+
+        cx.update_editor(|editor, window, cx| {
+            let snapshot = editor.snapshot(window, cx);
+            assert_eq!(snapshot.widest_line_number().ilog10() + 1, 1);
+            assert_eq!(diff_line_number_digits(&snapshot), 2);
+        });
+    }
+
     #[gpui::test]
     async fn test_vim_visual_selections(cx: &mut TestAppContext) {
         init_test(cx, |_| {});
@@ -859,6 +859,67 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_blame_entry_at_cursor(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/my-repo"),
+            json!({
+                ".git": {},
+                "file.txt": "Line 1\nLine 2\n",
+            }),
+        )
+        .await;
+        fs.set_blame_for_repo(
+            Path::new(path!("/my-repo/.git")),
+            vec![(
+                "file.txt".into(),
+                Blame {
+                    entries: vec![BlameEntry {
+                        author: Some("Jane Doe".into()),
+                        ..blame_entry("1b1b1b", 0..1)
+                    }],
+                    ..Default::default()
+                },
+            )],
+        );
+        let project = Project::test(fs, [path!("/my-repo").as_ref()], cx).await;
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/my-repo/file.txt"), cx)
+            })
+            .await
+            .unwrap();
+        let (editor, cx) =
+            cx.add_window_view(|window, cx| Editor::for_buffer(buffer, Some(project), window, cx));
+
+        // Blame is not loaded yet.
+        editor.update(cx, |editor, cx| {
+            assert!(editor.blame_entry_at_cursor(cx).is_none());
+        });
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.start_git_blame(true, window, cx);
+        });
+        cx.executor().run_until_parked();
+        editor.update(cx, |editor, cx| {
+            let (blame_entry, _) = editor.blame_entry_at_cursor(cx).unwrap();
+            assert_eq!(blame_entry.author.as_deref(), Some("Jane Doe"));
+        });
+
+        // The second line is uncommitted.
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(None, window, cx, |s| {
+                s.select_ranges([Point::new(1, 0)..Point::new(1, 0)])
+            });
+        });
+        editor.update(cx, |editor, cx| {
+            assert!(editor.blame_entry_at_cursor(cx).is_none());
+        });
+    }
+
     #[gpui::test]
     async fn test_blame_for_rows_with_edits(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -1,7 +1,7 @@
 use crate::{
-    Anchor, Autoscroll, Editor, EditorEvent, EditorSettings, ExcerptId, ExcerptRange, FormatTarget,
-    MultiBuffer, MultiBufferSnapshot, NavigationData, SearchWithinRange, SelectionEffects,
-    ToPoint as _,
+    Anchor, Autoscroll, BreadcrumbSymbolKind, Editor, EditorEvent, EditorSettings, ExcerptId,
+    ExcerptRange, FormatTarget, MultiBuffer, MultiBufferSnapshot, NavigationData,
+    SearchWithinRange, SelectionEffects, ToPoint as _,
     display_map::HighlightKey,
     editor_settings::SeedQuerySetting,
     persistence::{DB, SerializedEditor},
@@ -17,8 +17,8 @@ use gpui::{
     ParentElement, Pixels, SharedString, Styled, Task, WeakEntity, Window, point,
 };
 use language::{
-    Bias, Buffer, BufferRow, CharKind, DiskState, LocalFile, Point, SelectionGoal,
-    proto::serialize_anchor as serialize_text_anchor,
+    Bias, Buffer, BufferRow, CharKind, DiskState, LocalFile, OutlineItem, Point, SelectionGoal,
+    ToOffset as _, proto::serialize_anchor as serialize_text_anchor,
 };
 use lsp::DiagnosticSeverity;
 use project::{
@@ -939,14 +939,52 @@ impl Item for Editor {
         }
     }
 
+    fn has_breadcrumbs(&self, _: &App) -> bool {
+        true
+    }
+
     fn breadcrumbs(&self, variant: &Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
         let cursor = self.selections.newest_anchor().head();
         let multibuffer = &self.buffer().read(cx);
-        let (buffer_id, symbols) =
-            multibuffer.symbols_containing(cursor, Some(variant.syntax()), cx)?;
+        // Deeper symbols are never computed. One more than shown is, to tell whether any are
+        // left out.
+        let (buffer_id, mut symbols) = match self.breadcrumb_max_depth {
+            Some(max_depth) => multibuffer.symbols_containing_to_depth(
+                cursor,
+                max_depth.saturating_add(1),
+                Some(variant.syntax()),
+                cx,
+            ),
+            None => multibuffer.symbols_containing(cursor, Some(variant.syntax()), cx),
+        }?;
+        let max_depth = self.breadcrumb_max_depth.unwrap_or(usize::MAX);
+        let mut truncated = symbols.len() > max_depth;
+        symbols.truncate(max_depth);
         let buffer = multibuffer.buffer(buffer_id)?;
 
         let buffer = buffer.read(cx);
+        let buffer_snapshot = buffer.snapshot();
+        let symbol_node = |symbol: &OutlineItem<Anchor>| {
+            let range = symbol.range.start.text_anchor.to_offset(&buffer_snapshot)
+                ..symbol.range.end.text_anchor.to_offset(&buffer_snapshot);
+            outline_item_node(&buffer_snapshot, range)
+        };
+        if let Some(leaf_kinds) = self
+            .breadcrumb_leaf_kinds
+            .as_ref()
+            .filter(|leaf_kinds| !leaf_kinds.is_empty())
+        {
+            let leaf_ix = symbols.iter().rposition(|symbol| {
+                symbol_node(symbol)
+                    .and_then(|node| BreadcrumbSymbolKind::for_node_kind(node.kind()))
+                    .is_some_and(|kind| leaf_kinds.contains(&kind))
+            });
+            if let Some(leaf_ix) = leaf_ix.filter(|leaf_ix| leaf_ix + 1 < symbols.len()) {
+                // The deeper symbols are left out on purpose, so the leaf isn't marked.
+                symbols.truncate(leaf_ix + 1);
+                truncated = false;
+            }
+        }
         let text = self.breadcrumb_header.clone().unwrap_or_else(|| {
             buffer
                 .snapshot()
@@ -973,13 +1011,42 @@ impl Item for Editor {
             text,
             highlights: None,
             font: Some(settings.buffer_font.clone()),
+            type_hint: None,
+            source_range: None,
         }];
 
-        breadcrumbs.extend(symbols.into_iter().map(|symbol| BreadcrumbText {
-            text: symbol.text,
-            highlights: Some(symbol.highlight_ranges),
-            font: Some(settings.buffer_font.clone()),
+        let leaf_ix = symbols.len().checked_sub(1);
+        breadcrumbs.extend(symbols.into_iter().enumerate().map(|(ix, symbol)| {
+            // Only the innermost symbol's type is shown.
+            let type_hint = if self.breadcrumb_type_hint && Some(ix) == leaf_ix {
+                symbol_node(&symbol)
+                    .and_then(|node| symbol_type_hint(&buffer_snapshot, node))
+                    .filter(|type_hint| !symbol.text.contains(type_hint.as_str()))
+            } else {
+                None
+            };
+            BreadcrumbText {
+                text: symbol.text,
+                highlights: Some(symbol.highlight_ranges),
+                font: Some(settings.buffer_font.clone()),
+                type_hint,
+                source_range: Some(symbol.range.start.text_anchor..symbol.range.end.text_anchor),
+            }
         }));
+        if truncated {
+            if let Some(leaf) = breadcrumbs.iter_mut().skip(1).last() {
+                leaf.text.push(' ');
+                leaf.text
+                    .push_str(self.breadcrumb_ellipsis.as_deref().unwrap_or("⋯"));
+            }
+        }
+        // Show a confirmed rename before the buffer is reparsed. The first
+        // segment is the file path, so only the innermost symbol is considered.
+        if let Some(rename) = self.optimistic_rename.as_ref() {
+            if let Some(innermost) = breadcrumbs.iter_mut().skip(1).last() {
+                rename_breadcrumb(innermost, &rename.old_name, &rename.new_name);
+            }
+        }
         Some(breadcrumbs)
     }
 
@@ -1010,7 +1077,9 @@ impl Item for Editor {
                 f(ItemEvent::UpdateBreadcrumbs);
             }
 
-            EditorEvent::Reparsed(_) => {
+            EditorEvent::Reparsed(_)
+            | EditorEvent::DiffBaseChanged
+            | EditorEvent::BreadcrumbsChanged => {
                 f(ItemEvent::UpdateBreadcrumbs);
             }
 
@@ -1899,6 +1968,74 @@ fn path_for_file<'a>(
     }
 }
 
+/// Returns the syntax node spanning exactly the given range of an outline item.
+fn outline_item_node(
+    buffer: &language::BufferSnapshot,
+    range: Range<usize>,
+) -> Option<language::Node<'_>> {
+    let mut node = buffer.syntax_ancestor(range.start..range.start)?;
+    while node.byte_range() != range {
+        if node.byte_range().len() > range.len() {
+            return None;
+        }
+        node = node.parent()?;
+    }
+    Some(node)
+}
+
+/// Returns the type annotated on the syntax node of an outline item, such as a function's
+/// return type or a field's type.
+fn symbol_type_hint(buffer: &language::BufferSnapshot, node: language::Node) -> Option<String> {
+    let type_node = node
+        .child_by_field_name("return_type")
+        .or_else(|| node.child_by_field_name("type"))?;
+    let type_hint = buffer
+        .text_for_range(type_node.byte_range())
+        .collect::<String>();
+    Some(type_hint.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Replaces the first whole-word occurrence of `old_name` in the segment with
+/// `new_name`, keeping the segment's highlights aligned with the new text.
+fn rename_breadcrumb(segment: &mut BreadcrumbText, old_name: &str, new_name: &str) {
+    if old_name.is_empty() {
+        return;
+    }
+
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let Some(start) = segment
+        .text
+        .match_indices(old_name)
+        .map(|(ix, _)| ix)
+        .find(|&ix| {
+            let before = segment.text[..ix].chars().next_back();
+            let after = segment.text[ix + old_name.len()..].chars().next();
+            !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+        })
+    else {
+        return;
+    };
+    let old_end = start + old_name.len();
+    let new_end = start + new_name.len();
+    segment.text.replace_range(start..old_end, new_name);
+
+    if let Some(highlights) = segment.highlights.as_mut() {
+        highlights.retain_mut(|(range, _)| {
+            if range.end <= start {
+                true
+            } else if range.start >= old_end {
+                *range = range.start - old_end + new_end..range.end - old_end + new_end;
+                true
+            } else if range.start >= start && range.end <= old_end {
+                *range = start..new_end;
+                true
+            } else {
+                false
+            }
+        });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::editor_tests::init_test;
@@ -1906,12 +2043,220 @@ mod tests {
 
     use super::*;
     use fs::MTime;
-    use gpui::{App, VisualTestContext};
+    use gpui::{App, HighlightStyle, VisualTestContext};
     use language::{LanguageMatcher, TestFile};
     use project::FakeFs;
     use std::path::{Path, PathBuf};
+    use theme::ActiveTheme;
     use util::path;
 
+    #[test]
+    fn test_rename_breadcrumb() {
+        let style = HighlightStyle::default();
+        let mut segment = BreadcrumbText {
+            text: "fn format_file(file: File)".into(),
+            highlights: Some(vec![(0..2, style), (3..14, style), (21..25, style)]),
+            font: None,
+            type_hint: None,
+            source_range: None,
+        };
+
+        rename_breadcrumb(&mut segment, "file", "path");
+        assert_eq!(segment.text, "fn format_file(path: File)");
+
+        rename_breadcrumb(&mut segment, "format_file", "fmt");
+        assert_eq!(segment.text, "fn fmt(path: File)");
+        assert_eq!(
+            segment
+                .highlights
+                .unwrap()
+                .into_iter()
+                .map(|(range, _)| range)
+                .collect::<Vec<_>>(),
+            vec![0..2, 3..6, 13..17]
+        );
+    }
+
+    async fn build_nested_symbols_editor(
+        cx: &mut gpui::TestAppContext,
+    ) -> (Entity<Editor>, &mut VisualTestContext) {
+        let language = Arc::new(
+            language::Language::new(
+                language::LanguageConfig {
+                    name: "Rust".into(),
+                    ..Default::default()
+                },
+                Some(tree_sitter_rust::LANGUAGE.into()),
+            )
+            .with_outline_query(
+                r#"
+                (mod_item "mod" @context name: (_) @name) @item
+                (function_item "fn" @context name: (_) @name) @item
+                "#,
+            )
+            .unwrap(),
+        );
+        let text = "mod outer {\n    fn inner() {\n        let x = 1;\n    }\n}\n";
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(language, cx));
+        let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+        let (editor, cx) =
+            cx.add_window_view(|window, cx| crate::test::build_editor(buffer, window, cx));
+        editor
+            .condition::<EditorEvent>(cx, |editor, cx| !editor.buffer.read(cx).is_parsing(cx))
+            .await;
+        (editor, cx)
+    }
+
+    #[gpui::test]
+    async fn test_go_to_breadcrumb_range(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let (editor, cx) = build_nested_symbols_editor(cx).await;
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(None, window, cx, |s| {
+                s.select_ranges([Point::new(2, 8)..Point::new(2, 8)]);
+            });
+
+            let theme = cx.theme().clone();
+            let segments = editor.breadcrumbs(&theme, cx).unwrap();
+            assert_eq!(
+                segments[1..]
+                    .iter()
+                    .map(|segment| segment.text.as_str())
+                    .collect::<Vec<_>>(),
+                ["mod outer", "fn inner"]
+            );
+            assert!(segments[0].source_range.is_none());
+
+            let range = segments[1].source_range.clone().unwrap();
+            editor.go_to_breadcrumb_range(range, window, cx);
+            assert_eq!(
+                editor.selections.newest::<Point>(cx).head(),
+                Point::new(0, 0)
+            );
+
+            let range = segments[2].source_range.clone().unwrap();
+            editor.go_to_breadcrumb_range(range, window, cx);
+            assert_eq!(
+                editor.selections.newest::<Point>(cx).head(),
+                Point::new(1, 4)
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_breadcrumb_max_depth(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let (editor, cx) = build_nested_symbols_editor(cx).await;
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(None, window, cx, |s| {
+                s.select_ranges([Point::new(2, 8)..Point::new(2, 8)]);
+            });
+            let theme = cx.theme().clone();
+            let symbols = |editor: &Editor, cx: &App| {
+                editor.breadcrumbs(&theme, cx).unwrap()[1..]
+                    .iter()
+                    .map(|segment| segment.text.clone())
+                    .collect::<Vec<_>>()
+            };
+
+            assert_eq!(symbols(editor, cx), ["mod outer", "fn inner"]);
+
+            editor.set_breadcrumb_max_depth(Some(2));
+            assert_eq!(symbols(editor, cx), ["mod outer", "fn inner"]);
+
+            editor.set_breadcrumb_max_depth(Some(1));
+            assert_eq!(symbols(editor, cx), ["mod outer ⋯"]);
+
+            editor.set_breadcrumb_ellipsis("...".to_string());
+            assert_eq!(symbols(editor, cx), ["mod outer ..."]);
+
+            editor.set_breadcrumb_max_depth(Some(0));
+            assert_eq!(symbols(editor, cx), Vec::<String>::new());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_has_outline(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let (editor, cx) = build_nested_symbols_editor(cx).await;
+        editor.update(cx, |editor, cx| assert!(editor.has_outline(cx)));
+
+        let buffer = cx.new(|cx| Buffer::local("plain\ntext\n", cx));
+        let buffer = cx.new(|cx| MultiBuffer::singleton(buffer, cx));
+        let plain_text_editor =
+            cx.new_window_entity(|window, cx| crate::test::build_editor(buffer, window, cx));
+        plain_text_editor.update(cx, |editor, cx| assert!(!editor.has_outline(cx)));
+    }
+
+    #[gpui::test]
+    async fn test_breadcrumb_leaf_kinds(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let (editor, cx) = build_nested_symbols_editor(cx).await;
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(None, window, cx, |s| {
+                s.select_ranges([Point::new(2, 8)..Point::new(2, 8)]);
+            });
+            let theme = cx.theme().clone();
+            let symbols = |editor: &Editor, cx: &App| {
+                editor.breadcrumbs(&theme, cx).unwrap()[1..]
+                    .iter()
+                    .map(|segment| segment.text.clone())
+                    .collect::<Vec<_>>()
+            };
+
+            editor.set_breadcrumb_leaf_kinds(Some(vec![BreadcrumbSymbolKind::Module]));
+            assert_eq!(symbols(editor, cx), ["mod outer"]);
+
+            editor.set_breadcrumb_leaf_kinds(Some(vec![
+                BreadcrumbSymbolKind::Function,
+                BreadcrumbSymbolKind::Module,
+            ]));
+            assert_eq!(symbols(editor, cx), ["mod outer", "fn inner"]);
+
+            // Without any symbol of the given kinds, all of them are shown.
+            editor.set_breadcrumb_leaf_kinds(Some(vec![BreadcrumbSymbolKind::Type]));
+            assert_eq!(symbols(editor, cx), ["mod outer", "fn inner"]);
+
+            editor.set_breadcrumb_leaf_kinds(None);
+            assert_eq!(symbols(editor, cx), ["mod outer", "fn inner"]);
+        });
+    }
+
+    #[test]
+    fn test_breadcrumb_symbol_kind_for_node_kind() {
+        assert_eq!(
+            BreadcrumbSymbolKind::for_node_kind("function_item"),
+            Some(BreadcrumbSymbolKind::Function)
+        );
+        assert_eq!(
+            BreadcrumbSymbolKind::for_node_kind("class_declaration"),
+            Some(BreadcrumbSymbolKind::Type)
+        );
+        assert_eq!(
+            BreadcrumbSymbolKind::for_node_kind("mod_item"),
+            Some(BreadcrumbSymbolKind::Module)
+        );
+        // Closures and type annotations don't declare symbols.
+        for node_kind in [
+            "closure_expression",
+            "arrow_function",
+            "function_expression",
+            "lambda",
+            "type_annotation",
+            "type_arguments",
+        ] {
+            assert_eq!(
+                BreadcrumbSymbolKind::for_node_kind(node_kind),
+                None,
+                "{node_kind}"
+            );
+        }
+    }
+
     #[gpui::test]
     fn test_path_for_file(cx: &mut App) {
         let file = TestFile {
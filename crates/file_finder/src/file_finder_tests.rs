@@ -2555,6 +2555,7 @@ fn open_file_picker(
 ) -> Entity<Picker<FileFinderDelegate>> {
     cx.dispatch_action(ToggleFileFinder {
         separate_history: true,
+        ..Default::default()
     });
     active_file_picker(workspace, cx)
 }
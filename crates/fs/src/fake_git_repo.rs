@@ -36,6 +36,12 @@ pub struct FakeGitRepositoryState {
     pub branches: HashSet<String>,
     pub simulated_index_write_error_message: Option<String>,
     pub refs: HashMap<String, String>,
+    /// The merge-base of HEAD and the upstream of the current branch, if it has one.
+    pub upstream_merge_base: Option<String>,
+    /// The contents of files at commits other than HEAD, by SHA.
+    pub commit_contents: HashMap<String, HashMap<RepoPath, String>>,
+    /// The URLs of the repository's remotes, by name.
+    pub remote_urls: HashMap<String, String>,
 }
 
 impl FakeGitRepositoryState {
@@ -50,6 +56,9 @@ impl FakeGitRepositoryState {
             branches: Default::default(),
             simulated_index_write_error_message: Default::default(),
             refs: HashMap::from_iter([("HEAD".into(), "abc".into())]),
+            upstream_merge_base: None,
+            commit_contents: Default::default(),
+            remote_urls: Default::default(),
         }
     }
 }
@@ -104,6 +113,29 @@ impl GitRepository for FakeGitRepository {
         .boxed()
     }
 
+    fn load_text_at_commit(&self, sha: String, path: RepoPath) -> BoxFuture<Option<String>> {
+        async {
+            self.with_state_async(false, move |state| {
+                let contents = if state.refs.get("HEAD") == Some(&sha) {
+                    &state.head_contents
+                } else {
+                    state.commit_contents.get(&sha).context("unknown commit")?
+                };
+                contents
+                    .get(path.as_ref())
+                    .context("not present in commit")
+                    .cloned()
+            })
+            .await
+            .ok()
+        }
+        .boxed()
+    }
+
+    fn merge_base_with_upstream(&self) -> BoxFuture<Result<Option<String>>> {
+        self.with_state_async(false, |state| Ok(state.upstream_merge_base.clone()))
+    }
+
     fn load_commit(
         &self,
         _commit: String,
@@ -130,8 +162,23 @@ impl GitRepository for FakeGitRepository {
         })
     }
 
-    fn remote_url(&self, _name: &str) -> Option<String> {
-        None
+    fn remote_url(&self, name: &str) -> Option<String> {
+        self.fs
+            .with_git_state(&self.dot_git_path, false, |state| {
+                state.remote_urls.get(name).cloned()
+            })
+            .ok()
+            .flatten()
+    }
+
+    fn remote_urls(&self) -> HashMap<String, String> {
+        self.fs
+            .with_git_state(&self.dot_git_path, false, |state| state.remote_urls.clone())
+            .unwrap_or_default()
+    }
+
+    fn is_lfs_tracked(&self, _path: &RepoPath) -> bool {
+        false
     }
 
     fn revparse_batch(&self, revs: Vec<String>) -> BoxFuture<Result<Vec<Option<String>>>> {
@@ -421,6 +468,17 @@ impl GitRepository for FakeGitRepository {
         future::ready(Ok(Vec::new())).boxed()
     }
 
+    fn remote_branches_containing(
+        &self,
+        _sha: String,
+    ) -> BoxFuture<Result<Vec<gpui::SharedString>>> {
+        future::ready(Ok(Vec::new())).boxed()
+    }
+
+    fn paths_at_commit(&self, _sha: String, path: RepoPath) -> BoxFuture<Result<Vec<RepoPath>>> {
+        future::ready(Ok(vec![path])).boxed()
+    }
+
     fn diff(&self, _diff: git::repository::DiffType) -> BoxFuture<Result<String>> {
         unimplemented!()
     }
@@ -1432,6 +1432,18 @@ impl FakeFs {
         .unwrap();
     }
 
+    pub fn set_remote_urls_for_repo(&self, dot_git: &Path, remote_urls: &[(&str, &str)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.remote_urls.clear();
+            state.remote_urls.extend(
+                remote_urls
+                    .iter()
+                    .map(|(name, url)| (name.to_string(), url.to_string())),
+            );
+        })
+        .unwrap();
+    }
+
     pub fn set_unmerged_paths_for_repo(
         &self,
         dot_git: &Path,
@@ -1478,6 +1490,21 @@ impl FakeFs {
         .unwrap();
     }
 
+    pub fn set_merge_base_for_repo(
+        &self,
+        dot_git: &Path,
+        merge_base_state: &[(RepoPath, String)],
+        sha: impl Into<String>,
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            let sha = sha.into();
+            let contents = merge_base_state.iter().cloned().collect();
+            state.commit_contents.insert(sha.clone(), contents);
+            state.upstream_merge_base = Some(sha);
+        })
+        .unwrap();
+    }
+
     pub fn set_git_content_for_repo(
         &self,
         dot_git: &Path,
@@ -0,0 +1,152 @@
+use std::path::Path;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+/// The paths, relative to the root of a repository, where its CODEOWNERS file may live, in
+/// the order they are looked up.
+pub const CODEOWNERS_PATHS: &[&str] = &[
+    ".github/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+];
+
+/// The rules of a CODEOWNERS file, which assign owners to the paths of a repository.
+#[derive(Debug, Default)]
+pub struct CodeOwners {
+    rules: Vec<CodeOwnersRule>,
+}
+
+#[derive(Debug)]
+struct CodeOwnersRule {
+    matcher: GlobSet,
+    owners: Vec<String>,
+}
+
+impl CodeOwners {
+    /// Parses the contents of a CODEOWNERS file, skipping comments, section headers and
+    /// patterns that are not valid globs.
+    pub fn parse(text: &str) -> Self {
+        let rules = text
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim();
+                if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+                    return None;
+                }
+
+                let mut tokens = line.split_whitespace();
+                let matcher = pattern_matcher(tokens.next()?)?;
+                let owners = tokens
+                    .take_while(|token| !token.starts_with('#'))
+                    .map(ToString::to_string)
+                    .collect();
+                Some(CodeOwnersRule { matcher, owners })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Returns the owners of the given repository-relative path. As in Git, the last rule
+    /// whose pattern matches the path wins, so a matching rule without owners leaves the
+    /// path unowned.
+    pub fn owners_for(&self, path: &Path) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matcher.is_match(path))
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// Builds a matcher for a CODEOWNERS pattern, which follows the rules of `.gitignore`
+/// patterns: a pattern containing a slash is relative to the repository root, and a pattern
+/// matching a directory also matches everything within it.
+fn pattern_matcher(pattern: &str) -> Option<GlobSet> {
+    let directory_only = pattern.ends_with('/');
+    let pattern = pattern.trim_end_matches('/');
+    let anchored = pattern.starts_with('/') || pattern.contains('/');
+    let pattern = pattern.trim_start_matches('/');
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let pattern = if anchored {
+        pattern.to_string()
+    } else {
+        format!("**/{pattern}")
+    };
+    let mut globs = Vec::new();
+    if !directory_only {
+        globs.push(pattern.clone());
+    }
+    // Unlike in `.gitignore`, a trailing `/*` only matches the files directly in a directory.
+    if !pattern.ends_with("/*") {
+        globs.push(format!("{pattern}/**"));
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(
+            GlobBuilder::new(&glob)
+                .literal_separator(true)
+                .build()
+                .ok()?,
+        );
+    }
+    builder.build().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_code_owners() {
+        let code_owners = CodeOwners::parse(
+            r#"
+            # Default owners for everything in the repository.
+            *       @zed-industries/everyone
+
+            *.rs    @rust-team @jane # Inline comments are ignored.
+            /docs/  docs@example.com
+            crates/editor/**/*.rs @zed-industries/editor
+            build/  @ops
+            /apps/*  @apps-team
+
+            [Generated]
+            crates/editor/src/generated.rs
+            "#,
+        );
+
+        let owners = |path: &str| code_owners.owners_for(Path::new(path)).to_vec();
+        assert_eq!(owners("README.md"), ["@zed-industries/everyone"]);
+        assert_eq!(owners("crates/git/src/git.rs"), ["@rust-team", "@jane"]);
+        assert_eq!(owners("docs/src/getting-started.md"), ["docs@example.com"]);
+        assert_eq!(
+            owners("crates/editor/src/editor.rs"),
+            ["@zed-industries/editor"]
+        );
+        assert_eq!(
+            owners("crates/editor/src/generated.rs"),
+            Vec::<String>::new()
+        );
+
+        // Unanchored directory patterns match at any depth.
+        assert_eq!(owners("tools/build/script.sh"), ["@ops"]);
+
+        // Anchored patterns only match from the repository root.
+        assert_eq!(owners("crates/docs/notes.md"), ["@zed-industries/everyone"]);
+
+        assert_eq!(owners("apps/main.js"), ["@apps-team"]);
+        assert_eq!(owners("apps/web/main.js"), ["@zed-industries/everyone"]);
+    }
+
+    #[test]
+    fn test_empty_code_owners() {
+        let code_owners = CodeOwners::parse("# No rules yet.\n");
+        assert!(code_owners.owners_for(Path::new("src/main.rs")).is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod blame;
+pub mod codeowners;
 pub mod commit;
 mod hosting_provider;
 mod remote;
@@ -12,6 +13,7 @@ pub use git2 as libgit;
 use gpui::action_with_deprecated_aliases;
 use gpui::actions;
 use gpui::impl_action_with_deprecated_aliases;
+use gpui::impl_actions;
 pub use repository::WORK_DIRECTORY_REPO_PATH;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -58,6 +60,11 @@ actions!(
         GenerateCommitMessage,
         Init,
         OpenModifiedFiles,
+        OpenInExternalApp,
+        OpenSecurityAdvisoriesOnRemote,
+        OpenDiscussionsOnRemote,
+        OpenCiOverviewOnRemote,
+        CreateIssueFromComment,
     ]
 );
 
@@ -67,6 +74,16 @@ pub struct RestoreFile {
     pub skip_prompt: bool,
 }
 
+/// Checks each configured Git hosting provider and reports which ones would
+/// produce broken links.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, JsonSchema)]
+pub struct ValidateGitHostingProviders {
+    /// Whether to also check that each provider's host is reachable.
+    #[serde(default)]
+    pub check_reachability: bool,
+}
+
+impl_actions!(git, [ValidateGitHostingProviders]);
 impl_action_with_deprecated_aliases!(git, RestoreFile, ["editor::RevertFile"]);
 action_with_deprecated_aliases!(git, Restore, ["editor::RevertSelectedHunks"]);
 action_with_deprecated_aliases!(git, Blame, ["editor::ToggleGitBlame"]);
@@ -1,7 +1,8 @@
-use std::{ops::Range, sync::Arc};
+use std::{ops::Range, path::Path, sync::Arc};
 
 use anyhow::Result;
 use async_trait::async_trait;
+use collections::HashMap;
 use derive_more::{Deref, DerefMut};
 use gpui::{App, Global, SharedString};
 use http_client::HttpClient;
@@ -39,14 +40,177 @@ impl GitRemote {
     pub async fn avatar_url(
         &self,
         commit: SharedString,
+        author_email: &str,
         client: Arc<dyn HttpClient>,
     ) -> Option<Url> {
+        // The avatars of authors whose username is known from their email need no lookup.
+        if let Some(avatar_url) = self
+            .host
+            .username_for_email(author_email)
+            .and_then(|username| self.host.build_user_avatar_url(&username))
+        {
+            return Some(avatar_url);
+        }
+
         self.host
             .commit_author_avatar_url(&self.owner, &self.repo, commit, client)
             .await
             .ok()
             .flatten()
     }
+
+    /// Returns a link to the profile of a commit author, if their username can be derived from
+    /// their email.
+    pub fn author_url(&self, author_email: &str) -> Option<Url> {
+        let username = self.host.username_for_email(author_email)?;
+        self.host.build_user_url(&username)
+    }
+
+    /// Returns a permalink to the given lines of a file, as of the given commit.
+    pub fn build_permalink_at_commit(
+        &self,
+        sha: &str,
+        path: &str,
+        selection: Option<Range<u32>>,
+    ) -> Url {
+        self.host.build_permalink(
+            ParsedGitRemote {
+                owner: self.owner.as_str().into(),
+                repo: self.repo.as_str().into(),
+            },
+            BuildPermalinkParams {
+                sha,
+                path,
+                selection,
+            },
+        )
+    }
+
+    /// Returns a link to the repository's security advisories page, if the host has one.
+    pub fn security_url(&self) -> Option<Url> {
+        self.host.security_url(&ParsedGitRemote {
+            owner: self.owner.as_str().into(),
+            repo: self.repo.as_str().into(),
+        })
+    }
+
+    /// Returns a link to the profile of a user or team named as a code owner, such as
+    /// `@octocat` or `@org/team`, if the host has one.
+    pub fn code_owner_url(&self, owner: &str) -> Option<Url> {
+        self.host.build_code_owner_url(owner)
+    }
+
+    /// Returns a link to the repository's discussions page, if the host has the concept.
+    pub fn discussions_url(&self) -> Option<Url> {
+        if !self.host.supports_discussions() {
+            return None;
+        }
+        self.host.discussions_url(&ParsedGitRemote {
+            owner: self.owner.as_str().into(),
+            repo: self.repo.as_str().into(),
+        })
+    }
+
+    /// Returns a link to the overview of the repository's CI pipelines, if the host runs CI.
+    pub fn ci_overview_url(&self) -> Option<Url> {
+        if !self.host.supports_ci_overview() {
+            return None;
+        }
+        self.host.ci_overview_url(&ParsedGitRemote {
+            owner: self.owner.as_str().into(),
+            repo: self.repo.as_str().into(),
+        })
+    }
+
+    /// Returns a direct link to the content of a Git LFS-tracked file, if the host serves LFS
+    /// objects at their own URL.
+    pub fn lfs_media_url(&self, sha: &str, path: &str) -> Option<Url> {
+        self.host.build_lfs_media_url(
+            &ParsedGitRemote {
+                owner: self.owner.as_str().into(),
+                repo: self.repo.as_str().into(),
+            },
+            BuildPermalinkParams {
+                sha,
+                path,
+                selection: None,
+            },
+        )
+    }
+
+    /// Returns a link to the blame of the given lines of a file as of the given commit, if the
+    /// host has a blame view.
+    pub fn blame_url_at(
+        &self,
+        sha: &str,
+        path: &str,
+        selection: Option<Range<u32>>,
+    ) -> Option<Url> {
+        self.host.blame_url_at(
+            &ParsedGitRemote {
+                owner: self.owner.as_str().into(),
+                repo: self.repo.as_str().into(),
+            },
+            BuildPermalinkParams {
+                sha,
+                path,
+                selection,
+            },
+        )
+    }
+
+    /// Returns a link to the host's form for opening a new issue, prefilled with the given
+    /// title and body, if the host has issues.
+    pub fn new_issue_url(&self, title: &str, body: &str) -> Option<Url> {
+        self.host.new_issue_url(
+            &ParsedGitRemote {
+                owner: self.owner.as_str().into(),
+                repo: self.repo.as_str().into(),
+            },
+            title,
+            body,
+        )
+    }
+
+    /// Returns a link to a file within the changes of a pull request, if the host supports
+    /// such links.
+    pub fn pull_request_file_review_url(
+        &self,
+        pull_request_number: u32,
+        path: &str,
+    ) -> Option<Url> {
+        if !self.host.supports_pull_request_file_review_links() {
+            return None;
+        }
+        self.host.build_pull_request_file_review_url(
+            &ParsedGitRemote {
+                owner: self.owner.as_str().into(),
+                repo: self.repo.as_str().into(),
+            },
+            pull_request_number,
+            path,
+        )
+    }
+
+    /// Returns a link to a job of a CI pipeline run, if the host supports such links.
+    pub fn ci_job_url(&self, params: BuildCiJobUrlParams) -> Option<Url> {
+        self.host.build_ci_job_url(
+            &ParsedGitRemote {
+                owner: self.owner.as_str().into(),
+                repo: self.repo.as_str().into(),
+            },
+            params,
+        )
+    }
+}
+
+/// An issue or pull request, parsed from a link to it or to one of its subpages.
+#[derive(Debug, PartialEq)]
+pub struct ParsedIssueUrl {
+    pub remote: ParsedGitRemote,
+    pub number: u32,
+    /// The link to the page of the issue or pull request itself.
+    pub url: Url,
 }
 
 pub struct BuildCommitPermalinkParams<'a> {
@@ -59,13 +223,25 @@ pub struct BuildPermalinkParams<'a> {
     pub selection: Option<Range<u32>>,
 }
 
+pub struct BuildCiJobUrlParams<'a> {
+    /// The ID of the pipeline run (e.g., a GitHub Actions workflow run) that the job belongs to.
+    pub pipeline_id: &'a str,
+    pub job_id: &'a str,
+}
+
 /// A Git hosting provider.
 #[async_trait]
 pub trait GitHostingProvider {
     /// Returns the name of the provider.
     fn name(&self) -> String;
 
-    /// Returns the base URL of the provider.
+    /// Returns the kind of software the provider runs, as named by the `provider` field of
+    /// the `git_hosting_providers` setting (e.g., "github"), if it is one of those kinds.
+    fn kind(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Returns the base URL of the provider, which the links it generates point to.
     fn base_url(&self) -> Url;
 
     /// Returns a permalink to a Git commit on this hosting provider.
@@ -111,6 +287,147 @@ pub trait GitHostingProvider {
         None
     }
 
+    /// Parses a link to an issue, or to one of its subpages, on this provider.
+    fn parse_issue_url(&self, _url: &Url) -> Option<ParsedIssueUrl> {
+        None
+    }
+
+    /// Parses a link to a pull request, or to one of its subpages, on this provider.
+    fn parse_pull_request_url(&self, _url: &Url) -> Option<ParsedIssueUrl> {
+        None
+    }
+
+    /// Returns a link to the repository's security advisories page.
+    ///
+    /// Returns `None` if this provider does not have such a page.
+    fn security_url(&self, _remote: &ParsedGitRemote) -> Option<Url> {
+        None
+    }
+
+    /// Returns whether this provider hosts discussion forums for repositories.
+    fn supports_discussions(&self) -> bool {
+        false
+    }
+
+    /// Returns a link to the repository's discussions page.
+    ///
+    /// Returns `None` if this provider does not have such a page.
+    fn discussions_url(&self, _remote: &ParsedGitRemote) -> Option<Url> {
+        None
+    }
+
+    /// Returns a link to the profile of a user or team named as an owner in a CODEOWNERS
+    /// file, such as `@octocat` or `@org/team`.
+    ///
+    /// Returns `None` if this provider does not have such a page for the owner, such as for
+    /// owners given by email address.
+    fn build_code_owner_url(&self, _owner: &str) -> Option<Url> {
+        None
+    }
+
+    /// Returns a direct link to the content of a Git LFS-tracked file, rather than to the page
+    /// that renders it. The selection of `params` is ignored.
+    ///
+    /// Returns `None` if this provider does not serve LFS objects at their own URL.
+    fn build_lfs_media_url(
+        &self,
+        _remote: &ParsedGitRemote,
+        _params: BuildPermalinkParams,
+    ) -> Option<Url> {
+        None
+    }
+
+    /// Returns a link to the provider's blame view of a file as of the given commit, pointing
+    /// at the lines of the selection, if any.
+    ///
+    /// Returns `None` if this provider does not have a blame view.
+    fn blame_url_at(
+        &self,
+        _remote: &ParsedGitRemote,
+        _params: BuildPermalinkParams,
+    ) -> Option<Url> {
+        None
+    }
+
+    /// Returns a link to the provider's form for opening a new issue in the repository,
+    /// prefilled with the given title and body.
+    ///
+    /// Returns `None` if this provider does not have issues.
+    fn new_issue_url(&self, _remote: &ParsedGitRemote, _title: &str, _body: &str) -> Option<Url> {
+        None
+    }
+
+    /// Returns whether this provider can link to individual CI jobs.
+    fn supports_ci_job_links(&self) -> bool {
+        false
+    }
+
+    /// Returns whether this provider runs CI pipelines for repositories, and has a page
+    /// listing them.
+    fn supports_ci_overview(&self) -> bool {
+        false
+    }
+
+    /// Returns a link to the page listing the repository's CI pipeline runs, such as the
+    /// Actions tab on GitHub or the pipelines page on GitLab.
+    ///
+    /// Returns `None` if this provider does not have such a page.
+    fn ci_overview_url(&self, _remote: &ParsedGitRemote) -> Option<Url> {
+        None
+    }
+
+    /// Returns a link to a job of a CI pipeline run.
+    ///
+    /// Returns `None` if this provider does not support such links.
+    fn build_ci_job_url(
+        &self,
+        _remote: &ParsedGitRemote,
+        _params: BuildCiJobUrlParams,
+    ) -> Option<Url> {
+        None
+    }
+
+    /// Returns whether this provider can link to a file within the changes of a pull request.
+    fn supports_pull_request_file_review_links(&self) -> bool {
+        false
+    }
+
+    /// Returns a link to a file within the changes of a pull request, where reviewers can
+    /// inspect it and mark it as viewed.
+    ///
+    /// Unlike [`Self::build_pull_request_file_permalink`], the link is not anchored to a line,
+    /// so it can be built without knowing the pull request's changes. Returns `None` if the
+    /// provider does not support such links.
+    fn build_pull_request_file_review_url(
+        &self,
+        _remote: &ParsedGitRemote,
+        _pull_request_number: u32,
+        _path: &str,
+    ) -> Option<Url> {
+        None
+    }
+
+    /// Returns whether this provider can link to a line within the changes of a pull request.
+    fn supports_pull_request_file_links(&self) -> bool {
+        false
+    }
+
+    /// Returns a link to the given line of a file in the changes of a pull request.
+    ///
+    /// `line` is the zero-based line in the file at the head of the pull request.
+    /// Returns `None` if the provider does not support such links or the file is not
+    /// changed by the pull request.
+    async fn build_pull_request_file_permalink(
+        &self,
+        _remote: &ParsedGitRemote,
+        _pull_request_number: u32,
+        _path: &str,
+        _line: u32,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<Url>> {
+        Ok(None)
+    }
+
     async fn commit_author_avatar_url(
         &self,
         _repo_owner: &str,
@@ -120,6 +437,34 @@ pub trait GitHostingProvider {
     ) -> Result<Option<Url>> {
         Ok(None)
     }
+
+    /// Returns the username of the commit author with the given email, when it can be derived
+    /// without querying the provider's API.
+    fn username_for_email(&self, _email: &str) -> Option<String> {
+        None
+    }
+
+    /// Returns a link to the profile of the given user.
+    fn build_user_url(&self, _username: &str) -> Option<Url> {
+        None
+    }
+
+    /// Returns the avatar of the given user, when it can be linked to without querying the
+    /// provider's API.
+    fn build_user_avatar_url(&self, _username: &str) -> Option<Url> {
+        None
+    }
+
+    /// Returns a shortened version of the given URL using this provider's URL shortener.
+    ///
+    /// Returns `None` if this provider does not offer a URL shortener.
+    async fn shorten_url(
+        &self,
+        _url: &Url,
+        _http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<Url>> {
+        Ok(None)
+    }
 }
 
 #[derive(Default, Deref, DerefMut)]
@@ -133,9 +478,31 @@ struct GitHostingProviderRegistryState {
     setting_providers: Vec<Arc<dyn GitHostingProvider + Send + Sync + 'static>>,
 }
 
+type ResolvedGitRemote = Option<(
+    Arc<dyn GitHostingProvider + Send + Sync + 'static>,
+    ParsedGitRemote,
+)>;
+
+/// The results of [`resolve_git_remote_url`], keyed by repository root and remote URL.
+#[derive(Default)]
+struct ResolvedGitRemotes {
+    /// Bumped whenever entries are invalidated, so that a result computed before then is not
+    /// inserted afterwards.
+    generation: u64,
+    remotes: HashMap<(Arc<Path>, String), ResolvedGitRemote>,
+}
+
+impl ResolvedGitRemotes {
+    fn invalidate(&mut self, mut retain: impl FnMut(&(Arc<Path>, String)) -> bool) {
+        self.generation += 1;
+        self.remotes.retain(|key, _| retain(key));
+    }
+}
+
 #[derive(Default)]
 pub struct GitHostingProviderRegistry {
     state: RwLock<GitHostingProviderRegistryState>,
+    resolved_remotes: RwLock<ResolvedGitRemotes>,
 }
 
 impl GitHostingProviderRegistry {
@@ -172,6 +539,7 @@ impl GitHostingProviderRegistry {
                 setting_providers: Vec::default(),
                 default_providers: Vec::default(),
             }),
+            resolved_remotes: RwLock::default(),
         }
     }
 
@@ -195,6 +563,7 @@ impl GitHostingProviderRegistry {
         let mut state = self.state.write();
         state.setting_providers.clear();
         state.setting_providers.extend(providers);
+        self.resolved_remotes.write().invalidate(|_| false);
     }
 
     /// Adds the provided [`GitHostingProvider`] to the registry.
@@ -203,10 +572,19 @@ impl GitHostingProviderRegistry {
         provider: Arc<dyn GitHostingProvider + Send + Sync + 'static>,
     ) {
         self.state.write().default_providers.push(provider);
+        self.resolved_remotes.write().invalidate(|_| false);
+    }
+
+    /// Forgets the cached providers of the given repository's remotes, so that they are
+    /// resolved again the next time they are needed.
+    pub fn invalidate_resolved_remotes(&self, repository_root: &Path) {
+        self.resolved_remotes
+            .write()
+            .invalidate(|(root, _)| root.as_ref() != repository_root);
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ParsedGitRemote {
     pub owner: Arc<str>,
     pub repo: Arc<str>,
@@ -228,3 +606,177 @@ pub fn parse_git_remote_url(
                 .map(|parsed_remote| (provider, parsed_remote))
         })
 }
+
+/// Like [`parse_git_remote_url`], but caches the result for the repository at the given root
+/// until the registered providers change or the cache is invalidated for that repository.
+pub fn resolve_git_remote_url(
+    provider_registry: Arc<GitHostingProviderRegistry>,
+    repository_root: &Path,
+    url: &str,
+) -> Option<(
+    Arc<dyn GitHostingProvider + Send + Sync + 'static>,
+    ParsedGitRemote,
+)> {
+    let key = (Arc::from(repository_root), url.to_string());
+    let generation = {
+        let resolved_remotes = provider_registry.resolved_remotes.read();
+        if let Some(resolved) = resolved_remotes.remotes.get(&key) {
+            return resolved.clone();
+        }
+        resolved_remotes.generation
+    };
+
+    let resolved = parse_git_remote_url(provider_registry.clone(), url);
+    let mut resolved_remotes = provider_registry.resolved_remotes.write();
+    if resolved_remotes.generation == generation {
+        resolved_remotes.remotes.insert(key, resolved.clone());
+    }
+    resolved
+}
+
+/// Returns the issue or pull request that the given URL links to, if a registered provider
+/// recognizes it.
+pub fn parse_issue_or_pull_request_url(
+    provider_registry: Arc<GitHostingProviderRegistry>,
+    url: &str,
+) -> Option<(
+    Arc<dyn GitHostingProvider + Send + Sync + 'static>,
+    ParsedIssueUrl,
+)> {
+    let url = Url::parse(url).ok()?;
+    provider_registry
+        .list_hosting_providers()
+        .into_iter()
+        .find_map(|provider| {
+            provider
+                .parse_issue_url(&url)
+                .or_else(|| provider.parse_pull_request_url(&url))
+                .map(|parsed_url| (provider, parsed_url))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use parking_lot::Mutex;
+
+    use super::*;
+
+    struct FakeProvider {
+        parse_count: Arc<AtomicUsize>,
+        on_parse: Mutex<Option<Box<dyn FnOnce() + Send>>>,
+    }
+
+    impl GitHostingProvider for FakeProvider {
+        fn name(&self) -> String {
+            "Fake".to_string()
+        }
+
+        fn base_url(&self) -> Url {
+            Url::parse("https://fake.host").unwrap()
+        }
+
+        fn build_commit_permalink(
+            &self,
+            _remote: &ParsedGitRemote,
+            _params: BuildCommitPermalinkParams,
+        ) -> Url {
+            self.base_url()
+        }
+
+        fn build_permalink(&self, _remote: ParsedGitRemote, _params: BuildPermalinkParams) -> Url {
+            self.base_url()
+        }
+
+        fn supports_avatars(&self) -> bool {
+            false
+        }
+
+        fn format_line_number(&self, line: u32) -> String {
+            format!("L{line}")
+        }
+
+        fn format_line_numbers(&self, start_line: u32, end_line: u32) -> String {
+            format!("L{start_line}-L{end_line}")
+        }
+
+        fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+            self.parse_count.fetch_add(1, Ordering::SeqCst);
+            if let Some(on_parse) = self.on_parse.lock().take() {
+                on_parse();
+            }
+            let (owner, repo) = url.strip_prefix("https://fake.host/")?.split_once('/')?;
+            Some(ParsedGitRemote {
+                owner: owner.into(),
+                repo: repo.into(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_resolve_git_remote_url_caches_until_invalidated() {
+        let parse_count = Arc::new(AtomicUsize::new(0));
+        let registry = Arc::new(GitHostingProviderRegistry::new());
+        registry.register_hosting_provider(Arc::new(FakeProvider {
+            parse_count: parse_count.clone(),
+            on_parse: Mutex::default(),
+        }));
+
+        let root = Path::new("/projects/zed");
+        let url = "https://fake.host/zed-industries/zed";
+        let resolve = || {
+            resolve_git_remote_url(registry.clone(), root, url)
+                .map(|(provider, remote)| (provider.name(), remote))
+        };
+        let expected = Some((
+            "Fake".to_string(),
+            ParsedGitRemote {
+                owner: "zed-industries".into(),
+                repo: "zed".into(),
+            },
+        ));
+
+        assert_eq!(resolve(), expected);
+        assert_eq!(resolve(), expected);
+        assert_eq!(parse_count.load(Ordering::SeqCst), 1);
+
+        // Changing the configured providers invalidates every repository's remotes.
+        registry.set_setting_providers([]);
+        assert_eq!(resolve(), expected);
+        assert_eq!(parse_count.load(Ordering::SeqCst), 2);
+
+        // Invalidating another repository leaves this one cached.
+        registry.invalidate_resolved_remotes(Path::new("/projects/other"));
+        assert_eq!(resolve(), expected);
+        assert_eq!(parse_count.load(Ordering::SeqCst), 2);
+
+        registry.invalidate_resolved_remotes(root);
+        assert_eq!(resolve(), expected);
+        assert_eq!(parse_count.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_resolve_git_remote_url_discards_results_invalidated_while_resolving() {
+        let parse_count = Arc::new(AtomicUsize::new(0));
+        let registry = Arc::new(GitHostingProviderRegistry::new());
+        let root = Path::new("/projects/zed");
+        let url = "https://fake.host/zed-industries/zed";
+        registry.register_hosting_provider(Arc::new(FakeProvider {
+            parse_count: parse_count.clone(),
+            on_parse: Mutex::new(Some(Box::new({
+                let registry = registry.clone();
+                move || registry.invalidate_resolved_remotes(root)
+            }))),
+        }));
+
+        // The remotes were invalidated while the first call was resolving, so its result
+        // isn't cached.
+        assert!(resolve_git_remote_url(registry.clone(), root, url).is_some());
+        assert!(resolve_git_remote_url(registry.clone(), root, url).is_some());
+        assert_eq!(parse_count.load(Ordering::SeqCst), 2);
+
+        assert!(resolve_git_remote_url(registry.clone(), root, url).is_some());
+        assert_eq!(parse_count.load(Ordering::SeqCst), 2);
+    }
+}
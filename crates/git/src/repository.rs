@@ -310,6 +310,14 @@ pub trait GitRepository: Send + Sync {
     /// Also returns `None` for symlinks.
     fn load_committed_text(&self, path: RepoPath) -> BoxFuture<Option<String>>;
 
+    /// Returns the contents of an entry at the given commit, or None if the commit does not exist or has no entry for the given path.
+    ///
+    /// Also returns `None` for symlinks.
+    fn load_text_at_commit(&self, sha: String, path: RepoPath) -> BoxFuture<Option<String>>;
+
+    /// Returns the merge-base of HEAD and the upstream of the current branch, or None if the branch has no upstream.
+    fn merge_base_with_upstream(&self) -> BoxFuture<Result<Option<String>>>;
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -320,6 +328,12 @@ pub trait GitRepository: Send + Sync {
     /// Returns the URL of the remote with the given name.
     fn remote_url(&self, name: &str) -> Option<String>;
 
+    /// Returns the URLs of the repository's remotes, keyed by remote name.
+    fn remote_urls(&self) -> HashMap<String, String>;
+
+    /// Returns whether `.gitattributes` routes the given path through the Git LFS filter.
+    fn is_lfs_tracked(&self, path: &RepoPath) -> bool;
+
     /// Resolve a list of refs to SHAs.
     fn revparse_batch(&self, revs: Vec<String>) -> BoxFuture<Result<Vec<Option<String>>>>;
 
@@ -432,6 +446,13 @@ pub trait GitRepository: Send + Sync {
     /// returns a list of remote branches that contain HEAD
     fn check_for_pushed_commit(&self) -> BoxFuture<Result<Vec<SharedString>>>;
 
+    /// Returns a list of remote branches that contain the given commit.
+    fn remote_branches_containing(&self, sha: String) -> BoxFuture<Result<Vec<SharedString>>>;
+
+    /// Returns the paths that a working tree file may have had at the given commit, following
+    /// renames. The result is empty if the file didn't exist at that commit.
+    fn paths_at_commit(&self, sha: String, path: RepoPath) -> BoxFuture<Result<Vec<RepoPath>>>;
+
     /// Run git diff
     fn diff(&self, diff: DiffType) -> BoxFuture<Result<String>>;
 
@@ -816,6 +837,43 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn load_text_at_commit(&self, sha: String, path: RepoPath) -> BoxFuture<'_, Option<String>> {
+        let repo = self.repository.clone();
+        self.executor
+            .spawn(async move {
+                let repo = repo.lock();
+                let tree = repo.revparse_single(&sha).ok()?.peel_to_tree().log_err()?;
+                let entry = tree.get_path(&path).ok()?;
+                if entry.filemode() == i32::from(git2::FileMode::Link) {
+                    return None;
+                }
+                let content = repo.find_blob(entry.id()).log_err()?.content().to_owned();
+                String::from_utf8(content).ok()
+            })
+            .boxed()
+    }
+
+    fn merge_base_with_upstream(&self) -> BoxFuture<'_, Result<Option<String>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let git = GitBinary::new(git_binary_path, working_directory, executor);
+                if git
+                    .run(&["rev-parse", "--verify", "--quiet", "@{upstream}"])
+                    .await
+                    .is_err()
+                {
+                    return Ok(None);
+                }
+                let merge_base = git.run(&["merge-base", "HEAD", "@{upstream}"]).await?;
+                Ok(Some(merge_base))
+            })
+            .boxed()
+    }
+
     fn set_index_text(
         &self,
         path: RepoPath,
@@ -885,6 +943,30 @@ impl GitRepository for RealGitRepository {
         remote.url().map(|url| url.to_string())
     }
 
+    fn remote_urls(&self) -> HashMap<String, String> {
+        let repo = self.repository.lock();
+        let Some(remote_names) = repo.remotes().log_err() else {
+            return HashMap::default();
+        };
+        remote_names
+            .iter()
+            .flatten()
+            .filter_map(|name| {
+                let remote = repo.find_remote(name).ok()?;
+                Some((name.to_string(), remote.url()?.to_string()))
+            })
+            .collect()
+    }
+
+    fn is_lfs_tracked(&self, path: &RepoPath) -> bool {
+        let repo = self.repository.lock();
+        let filter = repo
+            .get_attr(path, "filter", git2::AttrCheckFlags::FILE_THEN_INDEX)
+            .log_err()
+            .flatten();
+        filter == Some("lfs")
+    }
+
     fn revparse_batch(&self, revs: Vec<String>) -> BoxFuture<'_, Result<Vec<Option<String>>>> {
         let working_directory = self.working_directory();
         self.executor
@@ -1407,6 +1489,59 @@ impl GitRepository for RealGitRepository {
             .boxed()
     }
 
+    fn remote_branches_containing(&self, sha: String) -> BoxFuture<Result<Vec<SharedString>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let git = GitBinary::new(git_binary_path, working_directory, executor);
+                let output = git
+                    .run(&[
+                        "branch",
+                        "--remotes",
+                        "--format=%(refname:short)",
+                        "--contains",
+                        sha.as_str(),
+                    ])
+                    .await?;
+                Ok(output
+                    .lines()
+                    .map(|branch| branch.trim().to_owned().into())
+                    .collect())
+            })
+            .boxed()
+    }
+
+    fn paths_at_commit(&self, sha: String, path: RepoPath) -> BoxFuture<Result<Vec<RepoPath>>> {
+        let working_directory = self.working_directory();
+        let git_binary_path = self.git_binary_path.clone();
+        let executor = self.executor.clone();
+        self.executor
+            .spawn(async move {
+                let working_directory = working_directory?;
+                let git = GitBinary::new(git_binary_path, working_directory, executor);
+                let unix_path = path.to_unix_style().to_string_lossy().into_owned();
+                let object = format!("{sha}:{unix_path}");
+                if git.run(&["cat-file", "-e", object.as_str()]).await.is_ok() {
+                    return Ok(vec![path]);
+                }
+
+                let output = git
+                    .run(&[
+                        "diff",
+                        "--name-status",
+                        "-z",
+                        "--find-renames",
+                        sha.as_str(),
+                    ])
+                    .await?;
+                Ok(parse_renamed_from_paths(&output, &unix_path))
+            })
+            .boxed()
+    }
+
     fn checkpoint(&self) -> BoxFuture<'static, Result<GitRepositoryCheckpoint>> {
         let working_directory = self.working_directory();
         let git_binary_path = self.git_binary_path.clone();
@@ -2016,6 +2151,26 @@ fn checkpoint_author_envs() -> HashMap<String, String> {
     ])
 }
 
+/// Parses the output of `git diff --name-status -z`, returning the source paths of the entries
+/// that were renamed or copied to `new_path`.
+fn parse_renamed_from_paths(output: &str, new_path: &str) -> Vec<RepoPath> {
+    let mut fields = output.split('\0');
+    let mut old_paths = Vec::new();
+    while let Some(status) = fields.next() {
+        if status.starts_with('R') || status.starts_with('C') {
+            let (Some(old_path), Some(path)) = (fields.next(), fields.next()) else {
+                break;
+            };
+            if path == new_path {
+                old_paths.push(RepoPath::from_str(old_path));
+            }
+        } else if fields.next().is_none() {
+            break;
+        }
+    }
+    old_paths
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2266,6 +2421,17 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_renamed_from_paths_parsing() {
+        let output = "M\0src/lib.rs\0R087\0src/old.rs\0src/new.rs\0A\0src/added.rs\0";
+        assert_eq!(
+            parse_renamed_from_paths(output, "src/new.rs"),
+            vec![RepoPath::from_str("src/old.rs")]
+        );
+        assert!(parse_renamed_from_paths(output, "src/added.rs").is_empty());
+        assert!(parse_renamed_from_paths("", "src/new.rs").is_empty());
+    }
+
     impl RealGitRepository {
         /// Force a Git garbage collection on the repository.
         fn gc(&self) -> BoxFuture<Result<()>> {
@@ -62,10 +62,32 @@ pub fn get_host_from_git_remote_url(remote_url: &str) -> Result<String> {
     .context("URL has no host")
 }
 
+/// Returns whether a remote on the given host belongs to the provider served from `base_url`,
+/// either because it is on the same host or because the host is one of the provider's aliases.
+pub(crate) fn is_provider_host(host: &str, base_url: &Url, remote_host_aliases: &[String]) -> bool {
+    base_url.host_str() == Some(host)
+        || remote_host_aliases
+            .iter()
+            .any(|alias| alias.eq_ignore_ascii_case(host))
+}
+
+/// Returns whether the given URL is on the host of the provider served from `base_url` or on
+/// one of its subdomains, such as `api.github.com` for `github.com`.
+pub(crate) fn is_on_provider_host(url: &Url, base_url: &Url) -> bool {
+    let (Some(host), Some(base_host)) = (url.host_str(), base_url.host_str()) else {
+        return false;
+    };
+    host == base_host
+        || host
+            .strip_suffix(base_host)
+            .is_some_and(|subdomain| subdomain.ends_with('.'))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::get_host_from_git_remote_url;
+    use super::{get_host_from_git_remote_url, is_on_provider_host};
     use pretty_assertions::assert_eq;
+    use url::Url;
 
     #[test]
     fn test_get_host_from_git_remote_url() {
@@ -89,4 +111,16 @@ mod tests {
             assert_eq!(host, expected_host);
         }
     }
+
+    #[test]
+    fn test_is_on_provider_host() {
+        let base_url = Url::parse("https://code.corp.big.com").unwrap();
+        let is_on_host = |url: &str| is_on_provider_host(&Url::parse(url).unwrap(), &base_url);
+
+        assert!(is_on_host("https://code.corp.big.com/api/v3"));
+        assert!(is_on_host("https://api.code.corp.big.com"));
+        assert!(!is_on_host("https://corp.big.com"));
+        assert!(!is_on_host("https://evilcode.corp.big.com"));
+        assert!(!is_on_host("https://code.corp.big.com.evil.com"));
+    }
 }
@@ -1,6 +1,7 @@
 mod bitbucket;
 mod chromium;
 mod codeberg;
+mod gitea;
 mod gitee;
 mod github;
 mod gitlab;
@@ -9,6 +10,7 @@ mod sourcehut;
 pub use bitbucket::*;
 pub use chromium::*;
 pub use codeberg::*;
+pub use gitea::*;
 pub use gitee::*;
 pub use github::*;
 pub use gitlab::*;
@@ -1,28 +1,98 @@
 use std::str::FromStr;
+use std::sync::LazyLock;
 
+use regex::Regex;
 use url::Url;
 
 use git::{
     BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
-    RemoteUrl,
+    PullRequest, RemoteUrl,
 };
 
+use crate::is_provider_host;
+
+/// Matches the pull request number in the subject of a merge commit made by Bitbucket Cloud,
+/// such as `Merged in fix-build (pull request #123)`.
+fn cloud_pull_request_number_regex() -> &'static Regex {
+    static CLOUD_PULL_REQUEST_NUMBER_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\(pull request #(\d+)\)").unwrap());
+    &CLOUD_PULL_REQUEST_NUMBER_REGEX
+}
+
+/// Matches the pull request number in the subject of a merge commit made by Bitbucket Server,
+/// such as `Merge pull request #123 in PROJ/repo from fix-build to main`, or of a squashed
+/// pull request, such as `Pull request #123: Fix the build`.
+fn server_pull_request_number_regex() -> &'static Regex {
+    static SERVER_PULL_REQUEST_NUMBER_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^(?:Merge pull request|Pull request) #(\d+)\b").unwrap());
+    &SERVER_PULL_REQUEST_NUMBER_REGEX
+}
+
+/// The edition of Bitbucket that an instance runs, which determines the layout of its URLs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BitbucketFlavor {
+    /// Bitbucket Cloud, which serves repositories at `{owner}/{repo}`.
+    #[default]
+    Cloud,
+    /// Bitbucket Server or Data Center, which serves repositories at
+    /// `projects/{KEY}/repos/{repo}`, or at `users/{user}/repos/{repo}` for personal ones.
+    Server,
+}
+
 pub struct Bitbucket {
     name: String,
     base_url: Url,
+    public_url: Url,
+    remote_host_aliases: Vec<String>,
+    flavor: BitbucketFlavor,
 }
 
 impl Bitbucket {
     pub fn new(name: impl Into<String>, base_url: Url) -> Self {
         Self {
             name: name.into(),
+            public_url: base_url.clone(),
             base_url,
+            remote_host_aliases: Vec::new(),
+            flavor: BitbucketFlavor::Cloud,
         }
     }
 
+    /// Makes links point to the given URL, such as that of a reverse proxy, instead of the
+    /// `base_url`, which is still used to recognize remotes on the provider's host.
+    pub fn with_public_url(mut self, public_url: Url) -> Self {
+        self.public_url = public_url;
+        self
+    }
+
+    /// Makes remotes on the given hosts, such as SSH host aliases, be recognized as belonging
+    /// to the provider, in addition to those on the host of the `base_url`.
+    pub fn with_remote_host_aliases(mut self, remote_host_aliases: Vec<String>) -> Self {
+        self.remote_host_aliases = remote_host_aliases;
+        self
+    }
+
+    /// Sets the edition of Bitbucket that the instance runs.
+    pub fn with_flavor(mut self, flavor: BitbucketFlavor) -> Self {
+        self.flavor = flavor;
+        self
+    }
+
     pub fn public_instance() -> Self {
         Self::new("Bitbucket", Url::parse("https://bitbucket.org").unwrap())
     }
+
+    /// Returns the path of the repository's pages, relative to the base URL.
+    fn repo_path(&self, remote: &ParsedGitRemote) -> String {
+        let ParsedGitRemote { owner, repo } = remote;
+        match self.flavor {
+            BitbucketFlavor::Cloud => format!("{owner}/{repo}"),
+            BitbucketFlavor::Server => match owner.strip_prefix('~') {
+                Some(user) => format!("users/{user}/repos/{repo}"),
+                None => format!("projects/{owner}/repos/{repo}"),
+            },
+        }
+    }
 }
 
 impl GitHostingProvider for Bitbucket {
@@ -30,8 +100,15 @@ impl GitHostingProvider for Bitbucket {
         self.name.clone()
     }
 
+    fn kind(&self) -> Option<&'static str> {
+        Some(match self.flavor {
+            BitbucketFlavor::Cloud => "bitbucket",
+            BitbucketFlavor::Server => "bitbucket_server",
+        })
+    }
+
     fn base_url(&self) -> Url {
-        self.base_url.clone()
+        self.public_url.clone()
     }
 
     fn supports_avatars(&self) -> bool {
@@ -39,24 +116,48 @@ impl GitHostingProvider for Bitbucket {
     }
 
     fn format_line_number(&self, line: u32) -> String {
-        format!("lines-{line}")
+        match self.flavor {
+            BitbucketFlavor::Cloud => format!("lines-{line}"),
+            BitbucketFlavor::Server => format!("{line}"),
+        }
     }
 
     fn format_line_numbers(&self, start_line: u32, end_line: u32) -> String {
-        format!("lines-{start_line}:{end_line}")
+        match self.flavor {
+            BitbucketFlavor::Cloud => format!("lines-{start_line}:{end_line}"),
+            BitbucketFlavor::Server => format!("{start_line}-{end_line}"),
+        }
     }
 
     fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
         let url = RemoteUrl::from_str(url).ok()?;
 
         let host = url.host_str()?;
-        if host != "bitbucket.org" {
+        if !is_provider_host(host, &self.base_url, &self.remote_host_aliases) {
             return None;
         }
 
-        let mut path_segments = url.path_segments()?;
-        let owner = path_segments.next()?;
-        let repo = path_segments.next()?.trim_end_matches(".git");
+        let (owner, repo) = match self.flavor {
+            BitbucketFlavor::Cloud => {
+                let mut path_segments = url.path_segments()?;
+                let owner = path_segments.next()?;
+                let repo = path_segments.next()?;
+                (owner, repo)
+            }
+            // Remotes are served under `scm/` over HTTP and at the root over SSH, and may be
+            // nested under the path that the instance is served under, so only the last two
+            // segments identify the repository.
+            BitbucketFlavor::Server => {
+                let mut path_segments = url
+                    .path_segments()?
+                    .filter(|segment| !segment.is_empty())
+                    .rev();
+                let repo = path_segments.next()?;
+                let owner = path_segments.next()?;
+                (owner, repo)
+            }
+        };
+        let repo = repo.trim_end_matches(".git");
 
         Some(ParsedGitRemote {
             owner: owner.into(),
@@ -64,31 +165,59 @@ impl GitHostingProvider for Bitbucket {
         })
     }
 
+    fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
+        let line = message.lines().next()?;
+        let regex = match self.flavor {
+            BitbucketFlavor::Cloud => cloud_pull_request_number_regex(),
+            BitbucketFlavor::Server => server_pull_request_number_regex(),
+        };
+        let capture = regex.captures(line)?;
+        let number = capture.get(1)?.as_str().parse::<u32>().ok()?;
+
+        let repo_path = self.repo_path(remote);
+        let url = self
+            .base_url()
+            .join(&format!("{repo_path}/pull-requests/{number}"))
+            .ok()?;
+
+        Some(PullRequest { number, url })
+    }
+
     fn build_commit_permalink(
         &self,
         remote: &ParsedGitRemote,
         params: BuildCommitPermalinkParams,
     ) -> Url {
         let BuildCommitPermalinkParams { sha } = params;
-        let ParsedGitRemote { owner, repo } = remote;
+        let repo_path = self.repo_path(remote);
 
         self.base_url()
-            .join(&format!("{owner}/{repo}/commits/{sha}"))
+            .join(&format!("{repo_path}/commits/{sha}"))
             .unwrap()
     }
 
     fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
-        let ParsedGitRemote { owner, repo } = remote;
+        let repo_path = self.repo_path(&remote);
         let BuildPermalinkParams {
             sha,
             path,
             selection,
         } = params;
 
-        let mut permalink = self
-            .base_url()
-            .join(&format!("{owner}/{repo}/src/{sha}/{path}"))
-            .unwrap();
+        let mut permalink = match self.flavor {
+            BitbucketFlavor::Cloud => self
+                .base_url()
+                .join(&format!("{repo_path}/src/{sha}/{path}"))
+                .unwrap(),
+            BitbucketFlavor::Server => {
+                let mut permalink = self
+                    .base_url()
+                    .join(&format!("{repo_path}/browse/{path}"))
+                    .unwrap();
+                permalink.query_pairs_mut().append_pair("at", sha);
+                permalink
+            }
+        };
         permalink.set_fragment(
             selection
                 .map(|selection| self.line_fragment(&selection))
@@ -96,6 +225,30 @@ impl GitHostingProvider for Bitbucket {
         );
         permalink
     }
+
+    fn blame_url_at(&self, remote: &ParsedGitRemote, params: BuildPermalinkParams) -> Option<Url> {
+        let BuildPermalinkParams {
+            sha,
+            path,
+            selection,
+        } = params;
+
+        // Bitbucket Server only toggles blame within the file view, so it has no link of its own.
+        let BitbucketFlavor::Cloud = self.flavor else {
+            return None;
+        };
+        let repo_path = self.repo_path(remote);
+        let mut url = self
+            .base_url()
+            .join(&format!("{repo_path}/annotate/{sha}/{path}"))
+            .ok()?;
+        url.set_fragment(
+            selection
+                .map(|selection| self.line_fragment(&selection))
+                .as_deref(),
+        );
+        Some(url)
+    }
 }
 
 #[cfg(test)]
@@ -203,4 +356,186 @@ mod tests {
             "https://bitbucket.org/zed-industries/zed/src/f00b4r/main.rs#lines-24:48";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
+
+    fn bitbucket_server() -> Bitbucket {
+        Bitbucket::new(
+            "Bitbucket Server",
+            Url::parse("https://bitbucket.example.com").unwrap(),
+        )
+        .with_flavor(BitbucketFlavor::Server)
+    }
+
+    #[test]
+    fn test_parse_bitbucket_server_remote_url() {
+        let bitbucket = bitbucket_server();
+        for (url, owner) in [
+            ("https://bitbucket.example.com/scm/PROJ/zed.git", "PROJ"),
+            (
+                "https://jdoe@bitbucket.example.com/scm/PROJ/zed.git",
+                "PROJ",
+            ),
+            ("ssh://git@bitbucket.example.com:7999/proj/zed.git", "proj"),
+            ("https://bitbucket.example.com/scm/~jdoe/zed.git", "~jdoe"),
+        ] {
+            assert_eq!(
+                bitbucket.parse_remote_url(url),
+                Some(ParsedGitRemote {
+                    owner: owner.into(),
+                    repo: "zed".into(),
+                }),
+                "{url}"
+            );
+        }
+
+        let bitbucket = Bitbucket::new(
+            "Bitbucket Server",
+            Url::parse("https://example.com/bitbucket/").unwrap(),
+        )
+        .with_flavor(BitbucketFlavor::Server);
+        assert_eq!(
+            bitbucket.parse_remote_url("https://example.com/bitbucket/scm/PROJ/zed.git"),
+            Some(ParsedGitRemote {
+                owner: "PROJ".into(),
+                repo: "zed".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_build_bitbucket_server_permalink() {
+        let permalink = |owner: &str, selection| {
+            bitbucket_server()
+                .build_permalink(
+                    ParsedGitRemote {
+                        owner: owner.into(),
+                        repo: "zed".into(),
+                    },
+                    BuildPermalinkParams {
+                        sha: "f00b4r",
+                        path: "crates/editor/src/git/permalink.rs",
+                        selection,
+                    },
+                )
+                .to_string()
+        };
+
+        assert_eq!(
+            permalink("PROJ", None),
+            "https://bitbucket.example.com/projects/PROJ/repos/zed/browse/crates/editor/src/git/permalink.rs?at=f00b4r"
+        );
+        assert_eq!(
+            permalink("PROJ", Some(6..6)),
+            "https://bitbucket.example.com/projects/PROJ/repos/zed/browse/crates/editor/src/git/permalink.rs?at=f00b4r#7"
+        );
+        assert_eq!(
+            permalink("PROJ", Some(23..47)),
+            "https://bitbucket.example.com/projects/PROJ/repos/zed/browse/crates/editor/src/git/permalink.rs?at=f00b4r#24-48"
+        );
+        assert_eq!(
+            permalink("~jdoe", None),
+            "https://bitbucket.example.com/users/jdoe/repos/zed/browse/crates/editor/src/git/permalink.rs?at=f00b4r"
+        );
+    }
+
+    #[test]
+    fn test_build_bitbucket_commit_permalink() {
+        let remote = ParsedGitRemote {
+            owner: "PROJ".into(),
+            repo: "zed".into(),
+        };
+        let params = || BuildCommitPermalinkParams { sha: "f00b4r" };
+
+        assert_eq!(
+            Bitbucket::public_instance()
+                .build_commit_permalink(&remote, params())
+                .as_str(),
+            "https://bitbucket.org/PROJ/zed/commits/f00b4r"
+        );
+        assert_eq!(
+            bitbucket_server()
+                .build_commit_permalink(&remote, params())
+                .as_str(),
+            "https://bitbucket.example.com/projects/PROJ/repos/zed/commits/f00b4r"
+        );
+    }
+
+    #[test]
+    fn test_bitbucket_pull_requests() {
+        let remote = ParsedGitRemote {
+            owner: "PROJ".into(),
+            repo: "zed".into(),
+        };
+
+        let cloud = Bitbucket::public_instance();
+        let pull_request = cloud
+            .extract_pull_request(
+                &remote,
+                "Merged in fix-build (pull request #123)\n\nDetails.",
+            )
+            .unwrap();
+        assert_eq!(pull_request.number, 123);
+        assert_eq!(
+            pull_request.url.as_str(),
+            "https://bitbucket.org/PROJ/zed/pull-requests/123"
+        );
+        assert!(
+            cloud
+                .extract_pull_request(&remote, "Pull request #123: Fix the build")
+                .is_none()
+        );
+
+        let server = bitbucket_server();
+        let pull_request = server
+            .extract_pull_request(
+                &remote,
+                "Merge pull request #45 in PROJ/zed from fix-build to main",
+            )
+            .unwrap();
+        assert_eq!(pull_request.number, 45);
+        assert_eq!(
+            pull_request.url.as_str(),
+            "https://bitbucket.example.com/projects/PROJ/repos/zed/pull-requests/45"
+        );
+        let squashed = server.extract_pull_request(&remote, "Pull request #46: Fix the build");
+        assert_eq!(squashed.unwrap().number, 46);
+        assert!(
+            server
+                .extract_pull_request(&remote, "Mention pull request #45 in passing")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_build_bitbucket_blame_url_at() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+        let params = |selection| BuildPermalinkParams {
+            sha: "f00b4r",
+            path: "crates/editor/src/git/permalink.rs",
+            selection,
+        };
+
+        let url = Bitbucket::public_instance()
+            .blame_url_at(&remote, params(Some(6..6)))
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://bitbucket.org/zed-industries/zed/annotate/f00b4r/crates/editor/src/git/permalink.rs#lines-7"
+        );
+
+        let url = Bitbucket::public_instance()
+            .blame_url_at(&remote, params(Some(23..47)))
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://bitbucket.org/zed-industries/zed/annotate/f00b4r/crates/editor/src/git/permalink.rs#lines-24:48"
+        );
+
+        assert_eq!(
+            bitbucket_server().blame_url_at(&remote, params(Some(6..6))),
+            None
+        );
+    }
 }
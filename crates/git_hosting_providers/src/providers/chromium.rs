@@ -151,6 +151,26 @@ impl GitHostingProvider for Chromium {
         permalink
     }
 
+    fn blame_url_at(&self, remote: &ParsedGitRemote, params: BuildPermalinkParams) -> Option<Url> {
+        let ParsedGitRemote { owner: _, repo } = remote;
+        let BuildPermalinkParams {
+            sha,
+            path,
+            selection,
+        } = params;
+
+        let mut url = self
+            .base_url()
+            .join(&format!("{repo}/+blame/{sha}/{path}"))
+            .ok()?;
+        url.set_fragment(
+            selection
+                .map(|selection| self.line_fragment(&selection))
+                .as_deref(),
+        );
+        Some(url)
+    }
+
     fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
         let capture = pull_request_regex().captures(message)?;
         let url = Url::parse(capture.get(1)?.as_str()).unwrap();
@@ -299,4 +319,24 @@ mod tests {
             "https://chromium-review.googlesource.com/c/chromium/src/+/3310961"
         );
     }
+
+    #[test]
+    fn test_build_chromium_blame_url_at() {
+        let url = Chromium
+            .blame_url_at(
+                &ParsedGitRemote {
+                    owner: Arc::from(""),
+                    repo: "chromium/src".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "fea5080b182fc92e3be0c01c5dece602fe70b588",
+                    path: "ui/base/cursor/cursor.h",
+                    selection: Some(18..30),
+                },
+            )
+            .unwrap();
+
+        let expected_url = "https://chromium.googlesource.com/chromium/src/+blame/fea5080b182fc92e3be0c01c5dece602fe70b588/ui/base/cursor/cursor.h#19";
+        assert_eq!(url.to_string(), expected_url.to_string())
+    }
 }
@@ -0,0 +1,369 @@
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use url::Url;
+
+use git::{
+    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
+    PullRequest, RemoteUrl,
+};
+
+use crate::{EmailToUsername, is_provider_host};
+
+/// Matches the pull request number in the subject of a squashed pull request, such as
+/// `Fix the build (#123)`, or of a merge commit, such as
+/// `Merge pull request 'Fix the build' (#123) from fix-build into main`.
+fn pull_request_number_regex() -> &'static Regex {
+    static PULL_REQUEST_NUMBER_REGEX: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\(#(\d+)\)(?:$| from )").unwrap());
+    &PULL_REQUEST_NUMBER_REGEX
+}
+
+/// A self-hosted Gitea instance.
+#[derive(Debug)]
+pub struct Gitea {
+    name: String,
+    base_url: Url,
+    public_url: Url,
+    remote_host_aliases: Vec<String>,
+    email_to_username: EmailToUsername,
+}
+
+/// A self-hosted Forgejo instance. Forgejo is a fork of Gitea and serves the same routes.
+pub type Forgejo = Gitea;
+
+impl Gitea {
+    pub fn new(name: impl Into<String>, base_url: Url) -> Self {
+        Self {
+            name: name.into(),
+            public_url: base_url.clone(),
+            base_url,
+            remote_host_aliases: Vec::new(),
+            email_to_username: EmailToUsername::default(),
+        }
+    }
+
+    /// Makes links point to the given URL, such as that of a reverse proxy, instead of the
+    /// `base_url`, which is still used to recognize remotes on the provider's host.
+    pub fn with_public_url(mut self, public_url: Url) -> Self {
+        self.public_url = public_url;
+        self
+    }
+
+    /// Makes remotes on the given hosts, such as SSH host aliases, be recognized as belonging
+    /// to the provider, in addition to those on the host of the `base_url`.
+    pub fn with_remote_host_aliases(mut self, remote_host_aliases: Vec<String>) -> Self {
+        self.remote_host_aliases = remote_host_aliases;
+        self
+    }
+
+    /// Sets the rules used to derive the usernames of commit authors from their emails.
+    pub fn with_email_to_username(mut self, email_to_username: EmailToUsername) -> Self {
+        self.email_to_username = email_to_username;
+        self
+    }
+}
+
+impl GitHostingProvider for Gitea {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn kind(&self) -> Option<&'static str> {
+        Some("gitea")
+    }
+
+    fn base_url(&self) -> Url {
+        self.public_url.clone()
+    }
+
+    fn supports_avatars(&self) -> bool {
+        false
+    }
+
+    fn format_line_number(&self, line: u32) -> String {
+        format!("L{line}")
+    }
+
+    fn format_line_numbers(&self, start_line: u32, end_line: u32) -> String {
+        format!("L{start_line}-L{end_line}")
+    }
+
+    fn username_for_email(&self, email: &str) -> Option<String> {
+        self.email_to_username.username_for_email(email)
+    }
+
+    fn build_user_url(&self, username: &str) -> Option<Url> {
+        self.base_url().join(username).ok()
+    }
+
+    fn parse_remote_url(&self, url: &str) -> Option<ParsedGitRemote> {
+        let url = RemoteUrl::from_str(url).ok()?;
+
+        let host = url.host_str()?;
+        if !is_provider_host(host, &self.base_url, &self.remote_host_aliases) {
+            return None;
+        }
+
+        // Repositories are always nested directly under their owner, so any segments before
+        // them belong to the path that the instance is served under.
+        let mut path_segments = url
+            .path_segments()?
+            .filter(|segment| !segment.is_empty())
+            .rev();
+        let repo = path_segments.next()?.trim_end_matches(".git");
+        let owner = path_segments.next()?;
+
+        Some(ParsedGitRemote {
+            owner: owner.into(),
+            repo: repo.into(),
+        })
+    }
+
+    fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
+        let line = message.lines().next()?;
+        let capture = pull_request_number_regex().captures(line)?;
+        let number = capture.get(1)?.as_str().parse::<u32>().ok()?;
+
+        let ParsedGitRemote { owner, repo } = remote;
+        let url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/pulls/{number}"))
+            .ok()?;
+
+        Some(PullRequest { number, url })
+    }
+
+    fn build_commit_permalink(
+        &self,
+        remote: &ParsedGitRemote,
+        params: BuildCommitPermalinkParams,
+    ) -> Url {
+        let BuildCommitPermalinkParams { sha } = params;
+        let ParsedGitRemote { owner, repo } = remote;
+
+        self.base_url()
+            .join(&format!("{owner}/{repo}/commit/{sha}"))
+            .unwrap()
+    }
+
+    fn build_permalink(&self, remote: ParsedGitRemote, params: BuildPermalinkParams) -> Url {
+        let ParsedGitRemote { owner, repo } = remote;
+        let BuildPermalinkParams {
+            sha,
+            path,
+            selection,
+        } = params;
+
+        let mut permalink = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/src/commit/{sha}/{path}"))
+            .unwrap();
+        if path.ends_with(".md") {
+            permalink.set_query(Some("display=source"));
+        }
+        permalink.set_fragment(
+            selection
+                .map(|selection| self.line_fragment(&selection))
+                .as_deref(),
+        );
+        permalink
+    }
+
+    fn blame_url_at(&self, remote: &ParsedGitRemote, params: BuildPermalinkParams) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let BuildPermalinkParams {
+            sha,
+            path,
+            selection,
+        } = params;
+
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/blame/commit/{sha}/{path}"))
+            .ok()?;
+        url.set_fragment(
+            selection
+                .map(|selection| self.line_fragment(&selection))
+                .as_deref(),
+        );
+        Some(url)
+    }
+
+    fn new_issue_url(&self, remote: &ParsedGitRemote, title: &str, body: &str) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/issues/new"))
+            .ok()?;
+        url.query_pairs_mut()
+            .append_pair("title", title)
+            .append_pair("body", body);
+        Some(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn forgejo() -> Forgejo {
+        Forgejo::new(
+            "Forgejo",
+            Url::parse("https://forgejo.example.com").unwrap(),
+        )
+    }
+
+    fn remote() -> ParsedGitRemote {
+        ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        }
+    }
+
+    #[test]
+    fn test_parse_remote_url() {
+        let gitea = Gitea::new("Gitea", Url::parse("https://gitea.example.com").unwrap());
+        for url in [
+            "git@gitea.example.com:zed-industries/zed.git",
+            "ssh://git@gitea.example.com:2222/zed-industries/zed.git",
+            "https://gitea.example.com/zed-industries/zed.git",
+            "https://gitea.example.com/zed-industries/zed",
+        ] {
+            assert_eq!(gitea.parse_remote_url(url), Some(remote()), "{url}");
+        }
+
+        assert_eq!(
+            gitea.parse_remote_url("https://gitlab.com/zed-industries/zed.git"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_remote_url_with_path_prefix() {
+        let gitea = Gitea::new("Gitea", Url::parse("https://example.com/git/").unwrap());
+        assert_eq!(
+            gitea.parse_remote_url("https://example.com/git/zed-industries/zed.git"),
+            Some(remote())
+        );
+
+        let permalink = gitea.build_commit_permalink(
+            &remote(),
+            BuildCommitPermalinkParams {
+                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+            },
+        );
+        let expected_url = "https://example.com/git/zed-industries/zed/commit/faa6f979be417239b2e070dbbf6392b909224e0b";
+        assert_eq!(permalink.to_string(), expected_url)
+    }
+
+    #[test]
+    fn test_build_forgejo_commit_permalink() {
+        let permalink = forgejo().build_commit_permalink(
+            &remote(),
+            BuildCommitPermalinkParams {
+                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+            },
+        );
+
+        let expected_url = "https://forgejo.example.com/zed-industries/zed/commit/faa6f979be417239b2e070dbbf6392b909224e0b";
+        assert_eq!(permalink.to_string(), expected_url)
+    }
+
+    #[test]
+    fn test_build_forgejo_permalink() {
+        let permalink = |selection| {
+            forgejo()
+                .build_permalink(
+                    remote(),
+                    BuildPermalinkParams {
+                        sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                        path: "crates/editor/src/git/permalink.rs",
+                        selection,
+                    },
+                )
+                .to_string()
+        };
+
+        assert_eq!(
+            permalink(None),
+            "https://forgejo.example.com/zed-industries/zed/src/commit/faa6f979be417239b2e070dbbf6392b909224e0b/crates/editor/src/git/permalink.rs"
+        );
+        assert_eq!(
+            permalink(Some(6..6)),
+            "https://forgejo.example.com/zed-industries/zed/src/commit/faa6f979be417239b2e070dbbf6392b909224e0b/crates/editor/src/git/permalink.rs#L7"
+        );
+        assert_eq!(
+            permalink(Some(23..47)),
+            "https://forgejo.example.com/zed-industries/zed/src/commit/faa6f979be417239b2e070dbbf6392b909224e0b/crates/editor/src/git/permalink.rs#L24-L48"
+        );
+    }
+
+    #[test]
+    fn test_build_forgejo_markdown_permalink() {
+        let permalink = forgejo().build_permalink(
+            remote(),
+            BuildPermalinkParams {
+                sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                path: "README.md",
+                selection: Some(2..2),
+            },
+        );
+
+        let expected_url = "https://forgejo.example.com/zed-industries/zed/src/commit/faa6f979be417239b2e070dbbf6392b909224e0b/README.md?display=source#L3";
+        assert_eq!(permalink.to_string(), expected_url)
+    }
+
+    #[test]
+    fn test_build_forgejo_blame_url_at() {
+        let blame_url = forgejo()
+            .blame_url_at(
+                &remote(),
+                BuildPermalinkParams {
+                    sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: Some(23..47),
+                },
+            )
+            .unwrap();
+
+        let expected_url = "https://forgejo.example.com/zed-industries/zed/blame/commit/faa6f979be417239b2e070dbbf6392b909224e0b/crates/editor/src/git/permalink.rs#L24-L48";
+        assert_eq!(blame_url.to_string(), expected_url)
+    }
+
+    #[test]
+    fn test_forgejo_pull_requests() {
+        let forgejo = forgejo();
+        let pull_request = |message| forgejo.extract_pull_request(&remote(), message);
+
+        let squashed = pull_request("Fix the build (#123)\n\nDetails.").unwrap();
+        assert_eq!(squashed.number, 123);
+        assert_eq!(
+            squashed.url.as_str(),
+            "https://forgejo.example.com/zed-industries/zed/pulls/123"
+        );
+
+        let merged =
+            pull_request("Merge pull request 'Fix the build' (#45) from fix-build into main");
+        assert_eq!(merged.unwrap().number, 45);
+
+        assert!(pull_request("Mention #123 in passing").is_none());
+        assert!(pull_request("Fix the build\n\n(#123)").is_none());
+    }
+
+    #[test]
+    fn test_build_forgejo_new_issue_url() {
+        let url = forgejo()
+            .new_issue_url(&remote(), "Crash on start", "Steps:\n1. Open Zed")
+            .unwrap();
+
+        assert_eq!(
+            url.as_str(),
+            "https://forgejo.example.com/zed-industries/zed/issues/new?title=Crash+on+start&body=Steps%3A%0A1.+Open+Zed"
+        );
+    }
+}
@@ -80,6 +80,26 @@ impl GitHostingProvider for Gitee {
         );
         permalink
     }
+
+    fn blame_url_at(&self, remote: &ParsedGitRemote, params: BuildPermalinkParams) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let BuildPermalinkParams {
+            sha,
+            path,
+            selection,
+        } = params;
+
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/blame/{sha}/{path}"))
+            .ok()?;
+        url.set_fragment(
+            selection
+                .map(|selection| self.line_fragment(&selection))
+                .as_deref(),
+        );
+        Some(url)
+    }
 }
 
 #[cfg(test)]
@@ -171,4 +191,24 @@ mod tests {
         let expected_url = "https://gitee.com/zed-industries/zed/blob/e5fe811d7ad0fc26934edd76f891d20bdc3bb194/crates/editor/src/git/permalink.rs#L24-48";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
+
+    #[test]
+    fn test_build_gitee_blame_url_at() {
+        let url = Gitee
+            .blame_url_at(
+                &ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "e5fe811d7ad0fc26934edd76f891d20bdc3bb194",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: Some(23..47),
+                },
+            )
+            .unwrap();
+
+        let expected_url = "https://gitee.com/zed-industries/zed/blame/e5fe811d7ad0fc26934edd76f891d20bdc3bb194/crates/editor/src/git/permalink.rs#L24-48";
+        assert_eq!(url.to_string(), expected_url.to_string())
+    }
 }
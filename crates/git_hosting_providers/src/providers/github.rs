@@ -8,14 +8,18 @@ use gpui::SharedString;
 use http_client::{AsyncBody, HttpClient, HttpRequestExt, Request};
 use regex::Regex;
 use serde::Deserialize;
+use sha2::{Digest as _, Sha256};
 use url::Url;
 
 use git::{
-    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
-    PullRequest, RemoteUrl,
+    BuildCiJobUrlParams, BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider,
+    ParsedGitRemote, ParsedIssueUrl, PullRequest, RemoteUrl,
 };
 
-use crate::get_host_from_git_remote_url;
+use crate::{
+    EmailToUsername, ProviderHttpClient, get_host_from_git_remote_url, is_on_provider_host,
+    is_provider_host,
+};
 
 fn pull_request_number_regex() -> &'static Regex {
     static PULL_REQUEST_NUMBER_REGEX: LazyLock<Regex> =
@@ -45,18 +49,191 @@ struct User {
     pub avatar_url: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct PullRequestFile {
+    filename: String,
+    patch: Option<String>,
+}
+
+/// The maximum number of files returned per page by the pull request files API.
+const PULL_REQUEST_FILES_PER_PAGE: usize = 100;
+
+/// Returns the anchor of a file within the changes of a pull request, which GitHub derives from
+/// the SHA-256 of the file's path.
+fn pull_request_file_anchor(path: &str) -> String {
+    format!("diff-{:x}", Sha256::digest(path.as_bytes()))
+}
+
+/// Returns whether the given one-based line of the new file appears in the patch,
+/// either as an added line or as context.
+fn patch_contains_new_line(patch: &str, line: u32) -> bool {
+    let mut new_line = None;
+    for patch_line in patch.lines() {
+        if let Some(header) = patch_line.strip_prefix("@@ ") {
+            new_line = header
+                .split(' ')
+                .find_map(|range| range.strip_prefix('+'))
+                .and_then(|range| range.split(',').next())
+                .and_then(|start| start.parse::<u32>().ok());
+            continue;
+        }
+
+        let Some(current_line) = new_line.as_mut() else {
+            continue;
+        };
+        if patch_line.starts_with('-') || patch_line.starts_with('\\') {
+            continue;
+        }
+        if *current_line == line {
+            return true;
+        }
+        *current_line += 1;
+    }
+    false
+}
+
 #[derive(Debug)]
 pub struct Github {
     name: String,
     base_url: Url,
+    public_url: Url,
+    remote_host_aliases: Vec<String>,
+    api_base_url: Option<Url>,
+    raw_url: Option<Url>,
+    avatar_url: Option<Url>,
+    url_shortener_url: Option<Url>,
+    email_to_username: EmailToUsername,
+    http_client: Option<ProviderHttpClient>,
+    access_token: Option<AccessToken>,
+}
+
+/// An access token for the API, which is left out of debug output.
+struct AccessToken(String);
+
+impl std::fmt::Debug for AccessToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AccessToken(..)")
+    }
 }
 
 impl Github {
     pub fn new(name: impl Into<String>, base_url: Url) -> Self {
         Self {
             name: name.into(),
+            public_url: base_url.clone(),
             base_url,
+            remote_host_aliases: Vec::new(),
+            api_base_url: None,
+            raw_url: None,
+            avatar_url: None,
+            url_shortener_url: None,
+            email_to_username: EmailToUsername::default(),
+            http_client: None,
+            access_token: None,
+        }
+    }
+
+    /// Makes links point to the given URL, such as that of a reverse proxy, instead of the
+    /// `base_url`, which is still used to recognize remotes on the provider's host.
+    pub fn with_public_url(mut self, public_url: Url) -> Self {
+        self.public_url = public_url;
+        self
+    }
+
+    /// Makes remotes on the given hosts, such as SSH host aliases, be recognized as belonging
+    /// to the provider, in addition to those on the host of the `base_url`.
+    pub fn with_remote_host_aliases(mut self, remote_host_aliases: Vec<String>) -> Self {
+        self.remote_host_aliases = remote_host_aliases;
+        self
+    }
+
+    /// Sets the rules used to derive the usernames of commit authors from their emails.
+    pub fn with_email_to_username(mut self, email_to_username: EmailToUsername) -> Self {
+        self.email_to_username = email_to_username;
+        self
+    }
+
+    /// Sets the HTTP client used to call the API instead of the one passed by the caller.
+    pub fn with_http_client(mut self, http_client: Arc<dyn HttpClient>) -> Self {
+        self.http_client = Some(ProviderHttpClient(http_client));
+        self
+    }
+
+    /// Returns the client to call the API with, preferring the one set for this instance.
+    fn api_http_client(&self, http_client: Arc<dyn HttpClient>) -> Arc<dyn HttpClient> {
+        self.http_client
+            .as_ref()
+            .map_or(http_client, |http_client| http_client.0.clone())
+    }
+
+    /// Sets the token to call the API with instead of the one in the `GITHUB_TOKEN`
+    /// environment variable.
+    pub fn with_access_token(mut self, access_token: String) -> Self {
+        self.access_token = Some(AccessToken(access_token));
+        self
+    }
+
+    /// Returns the token to call the API with, preferring the one set for this instance.
+    fn api_access_token(&self) -> Option<String> {
+        match &self.access_token {
+            Some(access_token) => Some(access_token.0.clone()),
+            None => std::env::var("GITHUB_TOKEN").ok(),
+        }
+    }
+
+    /// Overrides the base URL of the REST API derived from the `base_url`.
+    pub fn with_api_base_url(mut self, api_base_url: Url) -> Self {
+        self.api_base_url = Some(api_base_url);
+        self
+    }
+
+    /// Makes links to the raw content of files point to the given host, under which files are
+    /// served at `{owner}/{repo}/{sha}/{path}`, instead of the raw endpoint of the instance.
+    pub fn with_raw_url(mut self, raw_url: Url) -> Self {
+        self.raw_url = Some(raw_url);
+        self
+    }
+
+    /// Makes links to the avatars of users point to the given host, under which avatars are
+    /// served at `{username}`, instead of the instance itself.
+    pub fn with_avatar_url(mut self, avatar_url: Url) -> Self {
+        self.avatar_url = Some(avatar_url);
+        self
+    }
+
+    /// Sets the URL shortener of the instance, to which URLs are posted as the `url` form
+    /// field, and which responds with the shortened URL in its `Location` header or body.
+    ///
+    /// The access token is only sent along when the shortener is on the instance's host or one
+    /// of its subdomains.
+    pub fn with_url_shortener_url(mut self, url_shortener_url: Url) -> Self {
+        self.url_shortener_url = Some(url_shortener_url);
+        self
+    }
+
+    /// Returns the base URL of the REST API for this instance.
+    ///
+    /// github.com and the data residency tenants of GitHub Enterprise Cloud, on
+    /// `{tenant}.ghe.com`, serve their API from the `api` subdomain of their host,
+    /// while GitHub Enterprise Server serves it from `/api/v3` on the instance
+    /// itself.
+    pub fn api_base_url(&self) -> Url {
+        if let Some(api_base_url) = &self.api_base_url {
+            return api_base_url.clone();
         }
+
+        if let Some(host) = self
+            .base_url
+            .host_str()
+            .filter(|host| *host == "github.com" || host.ends_with(".ghe.com"))
+        {
+            return Url::parse(&format!("https://api.{host}")).unwrap();
+        }
+
+        let mut api_base_url = self.base_url.clone();
+        let path = format!("{}/api/v3", api_base_url.path().trim_end_matches('/'));
+        api_base_url.set_path(&path);
+        api_base_url
     }
 
     pub fn public_instance() -> Self {
@@ -82,6 +259,93 @@ impl Github {
         ))
     }
 
+    /// Parses a link whose path continues after the repository with `kind` (`issues` or `pull`)
+    /// and the number of the issue or pull request.
+    fn parse_numbered_url(&self, url: &Url, kind: &str) -> Option<ParsedIssueUrl> {
+        // Links point to the public URL, including the path the instance is served under.
+        if url.host_str()? != self.public_url.host_str()? {
+            return None;
+        }
+        let path = url
+            .path()
+            .strip_prefix(self.public_url.path().trim_end_matches('/'))?;
+
+        let mut path_segments = path.strip_prefix('/')?.split('/');
+        let owner = path_segments.next()?;
+        let repo = path_segments.next()?;
+        if path_segments.next()? != kind {
+            return None;
+        }
+        let number = path_segments.next()?.parse::<u32>().ok()?;
+        let url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/{kind}/{number}"))
+            .ok()?;
+
+        Some(ParsedIssueUrl {
+            remote: ParsedGitRemote {
+                owner: owner.into(),
+                repo: repo.into(),
+            },
+            number,
+            url,
+        })
+    }
+
+    async fn fetch_pull_request_files(
+        &self,
+        repo_owner: &str,
+        repo: &str,
+        pull_request_number: u32,
+        client: &Arc<dyn HttpClient>,
+    ) -> Result<Vec<PullRequestFile>> {
+        let api_base_url = self.api_base_url();
+        let files_url = format!(
+            "{}/repos/{repo_owner}/{repo}/pulls/{pull_request_number}/files",
+            api_base_url.as_str().trim_end_matches('/')
+        );
+
+        let mut files = Vec::new();
+        for page in 1.. {
+            let url = format!("{files_url}?per_page={PULL_REQUEST_FILES_PER_PAGE}&page={page}");
+
+            let mut request = Request::get(&url)
+                .header("Content-Type", "application/json")
+                .follow_redirects(http_client::RedirectPolicy::FollowAll);
+
+            if let Some(github_token) = self.api_access_token() {
+                request = request.header("Authorization", format!("Bearer {}", github_token));
+            }
+
+            let mut response = client
+                .send(request.body(AsyncBody::default())?)
+                .await
+                .with_context(|| {
+                    format!("error fetching GitHub pull request files at {:?}", url)
+                })?;
+
+            let mut body = Vec::new();
+            response.body_mut().read_to_end(&mut body).await?;
+
+            if response.status().is_client_error() {
+                let text = String::from_utf8_lossy(body.as_slice());
+                bail!(
+                    "status error {}, response: {text:?}",
+                    response.status().as_u16()
+                );
+            }
+
+            let page_files = serde_json::from_slice::<Vec<PullRequestFile>>(&body)
+                .context("failed to deserialize GitHub pull request files")?;
+            let is_last_page = page_files.len() < PULL_REQUEST_FILES_PER_PAGE;
+            files.extend(page_files);
+            if is_last_page {
+                break;
+            }
+        }
+        Ok(files)
+    }
+
     async fn fetch_github_commit_author(
         &self,
         repo_owner: &str,
@@ -89,16 +353,17 @@ impl Github {
         commit: &str,
         client: &Arc<dyn HttpClient>,
     ) -> Result<Option<User>> {
-        let Some(host) = self.base_url.host_str() else {
-            bail!("failed to get host from github base url");
-        };
-        let url = format!("https://api.{host}/repos/{repo_owner}/{repo}/commits/{commit}");
+        let api_base_url = self.api_base_url();
+        let url = format!(
+            "{}/repos/{repo_owner}/{repo}/commits/{commit}",
+            api_base_url.as_str().trim_end_matches('/')
+        );
 
         let mut request = Request::get(&url)
             .header("Content-Type", "application/json")
             .follow_redirects(http_client::RedirectPolicy::FollowAll);
 
-        if let Ok(github_token) = std::env::var("GITHUB_TOKEN") {
+        if let Some(github_token) = self.api_access_token() {
             request = request.header("Authorization", format!("Bearer {}", github_token));
         }
 
@@ -132,14 +397,19 @@ impl GitHostingProvider for Github {
         self.name.clone()
     }
 
+    fn kind(&self) -> Option<&'static str> {
+        Some("github")
+    }
+
     fn base_url(&self) -> Url {
-        self.base_url.clone()
+        self.public_url.clone()
     }
 
     fn supports_avatars(&self) -> bool {
-        // Avatars are not supported for self-hosted GitHub instances
+        // Avatars are not supported for self-hosted GitHub instances, unless the usernames of
+        // authors can be derived from their emails.
         // See tracking issue: https://github.com/zed-industries/zed/issues/11043
-        &self.name == "GitHub"
+        &self.name == "GitHub" || !self.email_to_username.is_empty()
     }
 
     fn format_line_number(&self, line: u32) -> String {
@@ -154,7 +424,7 @@ impl GitHostingProvider for Github {
         let url = RemoteUrl::from_str(url).ok()?;
 
         let host = url.host_str()?;
-        if host != self.base_url.host_str()? {
+        if !is_provider_host(host, &self.base_url, &self.remote_host_aliases) {
             return None;
         }
 
@@ -168,6 +438,14 @@ impl GitHostingProvider for Github {
         })
     }
 
+    fn parse_issue_url(&self, url: &Url) -> Option<ParsedIssueUrl> {
+        self.parse_numbered_url(url, "issues")
+    }
+
+    fn parse_pull_request_url(&self, url: &Url) -> Option<ParsedIssueUrl> {
+        self.parse_numbered_url(url, "pull")
+    }
+
     fn build_commit_permalink(
         &self,
         remote: &ParsedGitRemote,
@@ -204,6 +482,127 @@ impl GitHostingProvider for Github {
         permalink
     }
 
+    fn security_url(&self, remote: &ParsedGitRemote) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+
+        self.base_url()
+            .join(&format!("{owner}/{repo}/security"))
+            .ok()
+    }
+
+    fn supports_discussions(&self) -> bool {
+        true
+    }
+
+    fn discussions_url(&self, remote: &ParsedGitRemote) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+
+        self.base_url()
+            .join(&format!("{owner}/{repo}/discussions"))
+            .ok()
+    }
+
+    fn build_code_owner_url(&self, owner: &str) -> Option<Url> {
+        let owner = owner.strip_prefix('@')?;
+        let path = match owner.split_once('/') {
+            Some((org, team)) => format!("orgs/{org}/teams/{team}"),
+            None => owner.to_string(),
+        };
+
+        self.base_url().join(&path).ok()
+    }
+
+    fn build_lfs_media_url(
+        &self,
+        remote: &ParsedGitRemote,
+        params: BuildPermalinkParams,
+    ) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let BuildPermalinkParams { sha, path, .. } = params;
+
+        // github.com serves LFS objects from a separate domain, whereas self-hosted instances
+        // resolve them through the raw endpoint, unless they have a host for raw content.
+        if let Some(raw_url) = &self.raw_url {
+            raw_url.join(&format!("{owner}/{repo}/{sha}/{path}")).ok()
+        } else if &self.name == "GitHub" {
+            Url::parse(&format!(
+                "https://media.githubusercontent.com/media/{owner}/{repo}/{sha}/{path}"
+            ))
+            .ok()
+        } else {
+            self.base_url()
+                .join(&format!("{owner}/{repo}/raw/{sha}/{path}"))
+                .ok()
+        }
+    }
+
+    fn blame_url_at(&self, remote: &ParsedGitRemote, params: BuildPermalinkParams) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let BuildPermalinkParams {
+            sha,
+            path,
+            selection,
+        } = params;
+
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/blame/{sha}/{path}"))
+            .ok()?;
+        url.set_fragment(
+            selection
+                .map(|selection| self.line_fragment(&selection))
+                .as_deref(),
+        );
+        Some(url)
+    }
+
+    fn new_issue_url(&self, remote: &ParsedGitRemote, title: &str, body: &str) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/issues/new"))
+            .ok()?;
+        url.query_pairs_mut()
+            .append_pair("title", title)
+            .append_pair("body", body);
+        Some(url)
+    }
+
+    fn supports_ci_job_links(&self) -> bool {
+        true
+    }
+
+    fn supports_ci_overview(&self) -> bool {
+        true
+    }
+
+    fn ci_overview_url(&self, remote: &ParsedGitRemote) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+
+        self.base_url()
+            .join(&format!("{owner}/{repo}/actions"))
+            .ok()
+    }
+
+    fn build_ci_job_url(
+        &self,
+        remote: &ParsedGitRemote,
+        params: BuildCiJobUrlParams,
+    ) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let BuildCiJobUrlParams {
+            pipeline_id,
+            job_id,
+        } = params;
+
+        self.base_url()
+            .join(&format!(
+                "{owner}/{repo}/actions/runs/{pipeline_id}/job/{job_id}"
+            ))
+            .ok()
+    }
+
     fn extract_pull_request(&self, remote: &ParsedGitRemote, message: &str) -> Option<PullRequest> {
         let line = message.lines().next()?;
         let capture = pull_request_number_regex().captures(line)?;
@@ -216,6 +615,85 @@ impl GitHostingProvider for Github {
         Some(PullRequest { number, url })
     }
 
+    fn supports_pull_request_file_review_links(&self) -> bool {
+        true
+    }
+
+    fn build_pull_request_file_review_url(
+        &self,
+        remote: &ParsedGitRemote,
+        pull_request_number: u32,
+        path: &str,
+    ) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/pull/{pull_request_number}/files"))
+            .ok()?;
+        url.set_fragment(Some(&pull_request_file_anchor(path)));
+        Some(url)
+    }
+
+    fn supports_pull_request_file_links(&self) -> bool {
+        true
+    }
+
+    async fn build_pull_request_file_permalink(
+        &self,
+        remote: &ParsedGitRemote,
+        pull_request_number: u32,
+        path: &str,
+        line: u32,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<Url>> {
+        let http_client = self.api_http_client(http_client);
+        let files = self
+            .fetch_pull_request_files(
+                &remote.owner,
+                &remote.repo,
+                pull_request_number,
+                &http_client,
+            )
+            .await?;
+        let Some(file) = files.into_iter().find(|file| file.filename == path) else {
+            return Ok(None);
+        };
+
+        let mut permalink = self.base_url().join(&format!(
+            "{}/{}/pull/{pull_request_number}/files",
+            remote.owner, remote.repo
+        ))?;
+        // Lines are anchored by their number in the new version of the file.
+        let mut fragment = pull_request_file_anchor(path);
+        let line = line + 1;
+        if file
+            .patch
+            .is_some_and(|patch| patch_contains_new_line(&patch, line))
+        {
+            fragment.push_str(&format!("R{line}"));
+        }
+        permalink.set_fragment(Some(&fragment));
+        Ok(Some(permalink))
+    }
+
+    fn username_for_email(&self, email: &str) -> Option<String> {
+        self.email_to_username.username_for_email(email)
+    }
+
+    fn build_user_url(&self, username: &str) -> Option<Url> {
+        self.base_url().join(username).ok()
+    }
+
+    fn build_user_avatar_url(&self, username: &str) -> Option<Url> {
+        let mut url = match &self.avatar_url {
+            Some(avatar_url) => avatar_url.join(username).ok()?,
+            None => self.base_url().join(&format!("{username}.png")).ok()?,
+        };
+        url.set_query(Some("size=128"));
+        Some(url)
+    }
+
     async fn commit_author_avatar_url(
         &self,
         repo_owner: &str,
@@ -223,6 +701,7 @@ impl GitHostingProvider for Github {
         commit: SharedString,
         http_client: Arc<dyn HttpClient>,
     ) -> Result<Option<Url>> {
+        let http_client = self.api_http_client(http_client);
         let commit = commit.to_string();
         let avatar_url = self
             .fetch_github_commit_author(repo_owner, repo, &commit, &http_client)
@@ -235,6 +714,51 @@ impl GitHostingProvider for Github {
             .transpose()?;
         Ok(avatar_url)
     }
+
+    async fn shorten_url(
+        &self,
+        url: &Url,
+        http_client: Arc<dyn HttpClient>,
+    ) -> Result<Option<Url>> {
+        let Some(url_shortener_url) = &self.url_shortener_url else {
+            return Ok(None);
+        };
+        let http_client = self.api_http_client(http_client);
+
+        let mut request = Request::post(url_shortener_url.as_str())
+            .header("Content-Type", "application/x-www-form-urlencoded");
+        let github_token = self
+            .api_access_token()
+            .filter(|_| is_on_provider_host(url_shortener_url, &self.base_url));
+        if let Some(github_token) = github_token {
+            request = request.header("Authorization", format!("Bearer {}", github_token));
+        }
+        let body = format!("url={}", urlencoding::encode(url.as_str()));
+
+        let mut response = http_client
+            .send(request.body(AsyncBody::from(body))?)
+            .await
+            .with_context(|| format!("error shortening URL at {:?}", url_shortener_url))?;
+
+        let mut body = Vec::new();
+        response.body_mut().read_to_end(&mut body).await?;
+
+        if !response.status().is_success() && !response.status().is_redirection() {
+            let text = String::from_utf8_lossy(body.as_slice());
+            bail!(
+                "status error {}, response: {text:?}",
+                response.status().as_u16()
+            );
+        }
+
+        let short_url = match response.headers().get("Location") {
+            Some(location) => location.to_str()?.to_string(),
+            None => String::from_utf8(body)?.trim().to_string(),
+        };
+        Url::parse(&short_url)
+            .map(Some)
+            .with_context(|| format!("invalid shortened URL: {short_url:?}"))
+    }
 }
 
 #[cfg(test)]
@@ -244,6 +768,104 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_access_token_is_attached_to_api_requests() {
+        let authorization = Arc::new(std::sync::Mutex::new(None));
+        let http_client = http_client::FakeHttpClient::create({
+            let authorization = authorization.clone();
+            move |request| {
+                *authorization.lock().unwrap() = request
+                    .headers()
+                    .get("Authorization")
+                    .and_then(|value| value.to_str().ok())
+                    .map(ToString::to_string);
+                async move {
+                    Ok(http_client::Response::builder()
+                        .status(404)
+                        .body(Default::default())
+                        .unwrap())
+                }
+            }
+        });
+
+        let github = Github::new(
+            "GitHub Enterprise",
+            Url::parse("https://github.corp").unwrap(),
+        )
+        .with_access_token("secret-token".into());
+        assert!(!format!("{github:?}").contains("secret-token"));
+        let avatar_url = futures::executor::block_on(github.commit_author_avatar_url(
+            "zed-industries",
+            "zed",
+            "f00b4r".into(),
+            http_client,
+        ));
+        assert!(avatar_url.is_err());
+        assert_eq!(
+            authorization.lock().unwrap().as_deref(),
+            Some("Bearer secret-token")
+        );
+    }
+
+    #[test]
+    fn test_shorten_url() {
+        let permalink =
+            Url::parse("https://github.corp/zed-industries/zed/blob/f00b4r/README.md#L1").unwrap();
+
+        let github = Github::new("Corp GitHub", Url::parse("https://github.corp").unwrap());
+        let http_client = http_client::FakeHttpClient::with_404_response();
+        let short_url = futures::executor::block_on(github.shorten_url(&permalink, http_client));
+        assert_eq!(short_url.unwrap(), None);
+
+        let request = Arc::new(std::sync::Mutex::new((None, String::new())));
+        let http_client = http_client::FakeHttpClient::create({
+            let request = request.clone();
+            move |mut sent_request| {
+                let request = request.clone();
+                async move {
+                    let authorization = sent_request
+                        .headers()
+                        .get("Authorization")
+                        .and_then(|value| value.to_str().ok())
+                        .map(ToString::to_string);
+                    let mut body = String::new();
+                    sent_request.body_mut().read_to_string(&mut body).await?;
+                    *request.lock().unwrap() = (authorization, body);
+                    Ok(http_client::Response::builder()
+                        .status(201)
+                        .header("Location", "https://go.github.corp/abc")
+                        .body(Default::default())
+                        .unwrap())
+                }
+            }
+        });
+        let shorten = |url_shortener_url: &str| {
+            let github = Github::new("Corp GitHub", Url::parse("https://github.corp").unwrap())
+                .with_url_shortener_url(Url::parse(url_shortener_url).unwrap())
+                .with_access_token("secret-token".into());
+            futures::executor::block_on(github.shorten_url(&permalink, http_client.clone()))
+                .unwrap()
+                .map(String::from)
+        };
+
+        assert_eq!(
+            shorten("https://go.github.corp/api").as_deref(),
+            Some("https://go.github.corp/abc")
+        );
+        assert_eq!(
+            *request.lock().unwrap(),
+            (
+                Some("Bearer secret-token".to_string()),
+                "url=https%3A%2F%2Fgithub.corp%2Fzed-industries%2Fzed%2Fblob%2Ff00b4r%2FREADME.md%23L1"
+                    .to_string()
+            )
+        );
+
+        // The access token isn't sent to shorteners on other hosts.
+        shorten("https://shortener.example.com/api");
+        assert_eq!(request.lock().unwrap().0, None);
+    }
+
     #[test]
     fn test_invalid_self_hosted_remote_url() {
         let remote_url = "git@github.com:zed-industries/zed.git";
@@ -277,6 +899,97 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_github_enterprise_raw_and_avatar_urls() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+        let params = || BuildPermalinkParams {
+            sha: "b2efec9824c45fcc90c9a7eb107a50d1772a60aa",
+            path: "assets/images/logo.png",
+            selection: None,
+        };
+        let github = || Github::new("Corp GitHub", Url::parse("https://github.corp").unwrap());
+
+        let without_overrides = github();
+        assert_eq!(
+            without_overrides
+                .build_lfs_media_url(&remote, params())
+                .unwrap()
+                .as_str(),
+            "https://github.corp/zed-industries/zed/raw/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/assets/images/logo.png"
+        );
+        assert_eq!(
+            without_overrides
+                .build_user_avatar_url("octocat")
+                .unwrap()
+                .as_str(),
+            "https://github.corp/octocat.png?size=128"
+        );
+
+        let with_overrides = github()
+            .with_raw_url(Url::parse("https://raw.github.corp").unwrap())
+            .with_avatar_url(Url::parse("https://avatars.github.corp").unwrap());
+        assert_eq!(
+            with_overrides
+                .build_lfs_media_url(&remote, params())
+                .unwrap()
+                .as_str(),
+            "https://raw.github.corp/zed-industries/zed/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/assets/images/logo.png"
+        );
+        assert_eq!(
+            with_overrides
+                .build_user_avatar_url("octocat")
+                .unwrap()
+                .as_str(),
+            "https://avatars.github.corp/octocat?size=128"
+        );
+
+        // Permalinks still point to the instance itself.
+        let permalink = with_overrides.build_permalink(remote, params());
+        assert_eq!(
+            permalink.as_str(),
+            "https://github.corp/zed-industries/zed/blob/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/assets/images/logo.png"
+        );
+    }
+
+    #[test]
+    fn test_api_base_url() {
+        assert_eq!(
+            Github::public_instance().api_base_url().as_str(),
+            "https://api.github.com/"
+        );
+
+        let github =
+            Github::from_remote_url("git@github.my-enterprise.com:zed-industries/zed.git").unwrap();
+        assert_eq!(
+            github.api_base_url().as_str(),
+            "https://github.my-enterprise.com/api/v3"
+        );
+
+        let github = Github::new(
+            "BigCorp GitHub",
+            Url::parse("https://big-corp.ghe.com").unwrap(),
+        );
+        assert_eq!(
+            github.api_base_url().as_str(),
+            "https://api.big-corp.ghe.com/"
+        );
+
+        let github = Github::new(
+            "BigCorp GitHub",
+            Url::parse("https://code.big-corp.com/github").unwrap(),
+        );
+        assert_eq!(
+            github.api_base_url().as_str(),
+            "https://code.big-corp.com/github/api/v3"
+        );
+
+        let github = github.with_api_base_url(Url::parse("https://api.big-corp.com").unwrap());
+        assert_eq!(github.api_base_url().as_str(), "https://api.big-corp.com/");
+    }
+
     #[test]
     fn test_parse_remote_url_given_self_hosted_ssh_url() {
         let remote_url = "git@github.my-enterprise.com:zed-industries/zed.git";
@@ -429,6 +1142,284 @@ mod tests {
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
 
+    #[test]
+    fn test_github_security_url() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+
+        let url = Github::public_instance().security_url(&remote).unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://github.com/zed-industries/zed/security"
+        );
+
+        let self_hosted =
+            Github::from_remote_url("git@github.my-enterprise.com:zed-industries/zed.git").unwrap();
+        let url = self_hosted.security_url(&remote).unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://github.my-enterprise.com/zed-industries/zed/security"
+        );
+    }
+
+    #[test]
+    fn test_github_discussions_url() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+
+        let url = Github::public_instance().discussions_url(&remote).unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://github.com/zed-industries/zed/discussions"
+        );
+
+        let self_hosted =
+            Github::from_remote_url("git@github.my-enterprise.com:zed-industries/zed.git").unwrap();
+        let url = self_hosted.discussions_url(&remote).unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://github.my-enterprise.com/zed-industries/zed/discussions"
+        );
+    }
+
+    #[test]
+    fn test_build_github_code_owner_url() {
+        let github = Github::public_instance();
+        let url = |owner| {
+            github
+                .build_code_owner_url(owner)
+                .map(|url| url.to_string())
+        };
+
+        assert_eq!(
+            url("@octocat").as_deref(),
+            Some("https://github.com/octocat")
+        );
+        assert_eq!(
+            url("@zed-industries/editor").as_deref(),
+            Some("https://github.com/orgs/zed-industries/teams/editor")
+        );
+        assert_eq!(url("octocat@example.com"), None);
+    }
+
+    #[test]
+    fn test_github_ci_overview_url() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+
+        let url = Github::public_instance().ci_overview_url(&remote).unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://github.com/zed-industries/zed/actions"
+        );
+
+        let self_hosted =
+            Github::from_remote_url("git@github.my-enterprise.com:zed-industries/zed.git").unwrap();
+        let url = self_hosted.ci_overview_url(&remote).unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://github.my-enterprise.com/zed-industries/zed/actions"
+        );
+    }
+
+    #[test]
+    fn test_build_github_ci_job_url() {
+        let url = Github::public_instance()
+            .build_ci_job_url(
+                &ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildCiJobUrlParams {
+                    pipeline_id: "15219105807",
+                    job_id: "42863374264",
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://github.com/zed-industries/zed/actions/runs/15219105807/job/42863374264"
+        );
+    }
+
+    #[test]
+    fn test_parse_github_issue_and_pull_request_urls() {
+        let github = Github::public_instance();
+
+        let url = Url::parse("https://github.com/zed-industries/zed/issues/26393").unwrap();
+        let parsed = github.parse_issue_url(&url).unwrap();
+        assert_eq!(parsed.remote.owner.as_ref(), "zed-industries");
+        assert_eq!(parsed.remote.repo.as_ref(), "zed");
+        assert_eq!(parsed.number, 26393);
+        assert_eq!(github.parse_pull_request_url(&url), None);
+
+        let url =
+            Url::parse("https://github.com/zed-industries/zed/pull/27878/files#diff-1").unwrap();
+        let parsed = github.parse_pull_request_url(&url).unwrap();
+        assert_eq!(parsed.number, 27878);
+        assert_eq!(
+            parsed.url.to_string(),
+            "https://github.com/zed-industries/zed/pull/27878"
+        );
+
+        let url = Url::parse("https://github.com/zed-industries/zed/issues/new").unwrap();
+        assert_eq!(github.parse_issue_url(&url), None);
+
+        let self_hosted =
+            Github::from_remote_url("git@github.my-enterprise.com:zed-industries/zed.git").unwrap();
+        let url =
+            Url::parse("https://github.my-enterprise.com/zed-industries/zed/issues/1").unwrap();
+        assert_eq!(github.parse_issue_url(&url), None);
+        assert_eq!(self_hosted.parse_issue_url(&url).unwrap().number, 1);
+
+        // Links are recognized by the public URL, rather than by the host of the remotes.
+        let behind_proxy = Github::new("Corp GitHub", Url::parse("https://git.corp").unwrap())
+            .with_public_url(Url::parse("https://code.big-corp.com/forge/").unwrap());
+        let url = Url::parse("https://code.big-corp.com/forge/owner/repo/pull/7").unwrap();
+        let parsed = behind_proxy.parse_pull_request_url(&url).unwrap();
+        assert_eq!(parsed.number, 7);
+        assert_eq!(parsed.url.as_str(), url.as_str());
+        let url = Url::parse("https://git.corp/owner/repo/pull/7").unwrap();
+        assert_eq!(behind_proxy.parse_pull_request_url(&url), None);
+    }
+
+    #[test]
+    fn test_pull_request_file_anchor() {
+        assert_eq!(
+            pull_request_file_anchor("crates/editor/src/editor.rs"),
+            "diff-a3da3181e4ab4f73aa1697d7b6dc0caa0c17b2a187fb83b076dfc0234ec91f54"
+        );
+    }
+
+    #[test]
+    fn test_build_github_pull_request_file_review_url() {
+        let url = Github::public_instance()
+            .build_pull_request_file_review_url(
+                &ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                27878,
+                "crates/editor/src/editor.rs",
+            )
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://github.com/zed-industries/zed/pull/27878/files#diff-a3da3181e4ab4f73aa1697d7b6dc0caa0c17b2a187fb83b076dfc0234ec91f54"
+        );
+    }
+
+    #[test]
+    fn test_build_github_lfs_media_url() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+        let params = || BuildPermalinkParams {
+            sha: "b2efec9824c45fcc90c9a7eb107a50d1772a60aa",
+            path: "assets/images/logo.png",
+            selection: Some(0..0),
+        };
+
+        let url = Github::public_instance()
+            .build_lfs_media_url(&remote, params())
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://media.githubusercontent.com/media/zed-industries/zed/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/assets/images/logo.png"
+        );
+
+        let url = Github::from_remote_url("git@github.my-enterprise.com:zed-industries/zed.git")
+            .unwrap()
+            .build_lfs_media_url(&remote, params())
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://github.my-enterprise.com/zed-industries/zed/raw/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/assets/images/logo.png"
+        );
+    }
+
+    #[test]
+    fn test_build_github_blame_url_at() {
+        let remote = git::GitRemote {
+            host: Arc::new(Github::public_instance()),
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+        let sha = "b2efec9824c45fcc90c9a7eb107a50d1772a60aa";
+
+        let url = remote
+            .blame_url_at(sha, "crates/editor/src/git/permalink.rs", Some(6..6))
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://github.com/zed-industries/zed/blame/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/crates/editor/src/git/permalink.rs#L7"
+        );
+
+        let url = remote
+            .blame_url_at(sha, "crates/editor/src/git/permalink.rs", Some(23..47))
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://github.com/zed-industries/zed/blame/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/crates/editor/src/git/permalink.rs#L24-L48"
+        );
+
+        let url = remote.blame_url_at(sha, "README.md", None).unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://github.com/zed-industries/zed/blame/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/README.md"
+        );
+    }
+
+    #[test]
+    fn test_build_github_new_issue_url() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+        let body = "Handle a & b\n\nhttps://github.com/zed-industries/zed/blob/main/README.md#L1";
+
+        let url = Github::public_instance()
+            .new_issue_url(&remote, "TODO: fix this", body)
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://github.com/zed-industries/zed/issues/new?title=TODO%3A+fix+this&body=Handle+a+%26+b%0A%0Ahttps%3A%2F%2Fgithub.com%2Fzed-industries%2Fzed%2Fblob%2Fmain%2FREADME.md%23L1"
+        );
+    }
+
+    #[test]
+    fn test_patch_contains_new_line() {
+        let patch = indoc! {"
+            @@ -1,4 +1,5 @@
+             use std::fmt;
+            -use std::io;
+            +use std::io::{self, Write};
+            +use std::path::Path;
+             // The entry point.
+             fn main() {
+            @@ -20,2 +21,3 @@ fn helper() {
+                 let a = 1;
+            +    let b = 2;
+             }
+        "};
+
+        assert!(patch_contains_new_line(patch, 1));
+        assert!(patch_contains_new_line(patch, 3));
+        assert!(patch_contains_new_line(patch, 5));
+        assert!(!patch_contains_new_line(patch, 6));
+        assert!(!patch_contains_new_line(patch, 20));
+        assert!(patch_contains_new_line(patch, 22));
+        assert!(patch_contains_new_line(patch, 23));
+        assert!(!patch_contains_new_line(patch, 24));
+    }
+
     #[test]
     fn test_github_pull_requests() {
         let remote = ParsedGitRemote {
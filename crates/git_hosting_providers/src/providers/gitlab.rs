@@ -4,26 +4,52 @@ use anyhow::{Result, bail};
 use url::Url;
 
 use git::{
-    BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider, ParsedGitRemote,
-    RemoteUrl,
+    BuildCiJobUrlParams, BuildCommitPermalinkParams, BuildPermalinkParams, GitHostingProvider,
+    ParsedGitRemote, ParsedIssueUrl, RemoteUrl,
 };
 
-use crate::get_host_from_git_remote_url;
+use crate::{EmailToUsername, get_host_from_git_remote_url, is_provider_host};
 
 #[derive(Debug)]
 pub struct Gitlab {
     name: String,
     base_url: Url,
+    public_url: Url,
+    remote_host_aliases: Vec<String>,
+    email_to_username: EmailToUsername,
 }
 
 impl Gitlab {
     pub fn new(name: impl Into<String>, base_url: Url) -> Self {
         Self {
             name: name.into(),
+            public_url: base_url.clone(),
             base_url,
+            remote_host_aliases: Vec::new(),
+            email_to_username: EmailToUsername::default(),
         }
     }
 
+    /// Makes links point to the given URL, such as that of a reverse proxy, instead of the
+    /// `base_url`, which is still used to recognize remotes on the provider's host.
+    pub fn with_public_url(mut self, public_url: Url) -> Self {
+        self.public_url = public_url;
+        self
+    }
+
+    /// Makes remotes on the given hosts, such as SSH host aliases, be recognized as belonging
+    /// to the provider, in addition to those on the host of the `base_url`.
+    pub fn with_remote_host_aliases(mut self, remote_host_aliases: Vec<String>) -> Self {
+        self.remote_host_aliases = remote_host_aliases;
+        self
+    }
+
+    /// Sets the rules used to derive the usernames of commit authors from their emails.
+    pub fn with_email_to_username(mut self, email_to_username: EmailToUsername) -> Self {
+        self.email_to_username = email_to_username;
+        self
+    }
+
     pub fn public_instance() -> Self {
         Self::new("GitLab", Url::parse("https://gitlab.com").unwrap())
     }
@@ -46,6 +72,52 @@ impl Gitlab {
             Url::parse(&format!("https://{}", host))?,
         ))
     }
+
+    /// Removes the path that the instance is served under, if any, from the start of the
+    /// segments of a path on its host.
+    fn strip_path_prefix(&self, path_segments: &mut Vec<&str>) {
+        let prefix_segments = self
+            .base_url
+            .path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+        if path_segments.starts_with(&prefix_segments) {
+            path_segments.drain(..prefix_segments.len());
+        }
+    }
+
+    /// Parses a link whose path continues after the repository with `/-/`, `kind` (`issues` or
+    /// `merge_requests`) and the number of the issue or merge request.
+    fn parse_numbered_url(&self, url: &Url, kind: &str) -> Option<ParsedIssueUrl> {
+        if url.host_str()? != self.base_url.host_str()? {
+            return None;
+        }
+
+        let mut path_segments = url.path_segments()?.collect::<Vec<_>>();
+        self.strip_path_prefix(&mut path_segments);
+        let separator_ix = path_segments.iter().position(|segment| *segment == "-")?;
+        let (owner, repo) = path_segments[..separator_ix].split_last()?;
+        if owner.is_empty() || path_segments.get(separator_ix + 1) != Some(&kind) {
+            return None;
+        }
+        let number = path_segments.get(separator_ix + 2)?.parse::<u32>().ok()?;
+        let owner = owner.join("/");
+        let url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/-/{kind}/{number}"))
+            .ok()?;
+
+        Some(ParsedIssueUrl {
+            remote: ParsedGitRemote {
+                owner: owner.into(),
+                repo: (*repo).into(),
+            },
+            number,
+            url,
+        })
+    }
 }
 
 impl GitHostingProvider for Gitlab {
@@ -53,8 +125,12 @@ impl GitHostingProvider for Gitlab {
         self.name.clone()
     }
 
+    fn kind(&self) -> Option<&'static str> {
+        Some("gitlab")
+    }
+
     fn base_url(&self) -> Url {
-        self.base_url.clone()
+        self.public_url.clone()
     }
 
     fn supports_avatars(&self) -> bool {
@@ -65,6 +141,14 @@ impl GitHostingProvider for Gitlab {
         format!("L{line}")
     }
 
+    fn username_for_email(&self, email: &str) -> Option<String> {
+        self.email_to_username.username_for_email(email)
+    }
+
+    fn build_user_url(&self, username: &str) -> Option<Url> {
+        self.base_url().join(username).ok()
+    }
+
     fn format_line_numbers(&self, start_line: u32, end_line: u32) -> String {
         format!("L{start_line}-{end_line}")
     }
@@ -73,11 +157,12 @@ impl GitHostingProvider for Gitlab {
         let url = RemoteUrl::from_str(url).ok()?;
 
         let host = url.host_str()?;
-        if host != self.base_url.host_str()? {
+        if !is_provider_host(host, &self.base_url, &self.remote_host_aliases) {
             return None;
         }
 
         let mut path_segments = url.path_segments()?.collect::<Vec<_>>();
+        self.strip_path_prefix(&mut path_segments);
         let repo = path_segments.pop()?.trim_end_matches(".git");
         let owner = path_segments.join("/");
 
@@ -87,6 +172,14 @@ impl GitHostingProvider for Gitlab {
         })
     }
 
+    fn parse_issue_url(&self, url: &Url) -> Option<ParsedIssueUrl> {
+        self.parse_numbered_url(url, "issues")
+    }
+
+    fn parse_pull_request_url(&self, url: &Url) -> Option<ParsedIssueUrl> {
+        self.parse_numbered_url(url, "merge_requests")
+    }
+
     fn build_commit_permalink(
         &self,
         remote: &ParsedGitRemote,
@@ -122,6 +215,94 @@ impl GitHostingProvider for Gitlab {
         );
         permalink
     }
+
+    fn security_url(&self, remote: &ParsedGitRemote) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+
+        self.base_url()
+            .join(&format!("{owner}/{repo}/-/security"))
+            .ok()
+    }
+
+    fn build_code_owner_url(&self, owner: &str) -> Option<Url> {
+        // Users and (possibly nested) groups share the same namespace.
+        self.base_url().join(owner.strip_prefix('@')?).ok()
+    }
+
+    fn supports_ci_job_links(&self) -> bool {
+        true
+    }
+
+    fn build_lfs_media_url(
+        &self,
+        remote: &ParsedGitRemote,
+        params: BuildPermalinkParams,
+    ) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let BuildPermalinkParams { sha, path, .. } = params;
+
+        self.base_url()
+            .join(&format!("{owner}/{repo}/-/raw/{sha}/{path}"))
+            .ok()
+    }
+
+    fn blame_url_at(&self, remote: &ParsedGitRemote, params: BuildPermalinkParams) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let BuildPermalinkParams {
+            sha,
+            path,
+            selection,
+        } = params;
+
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/-/blame/{sha}/{path}"))
+            .ok()?;
+        url.set_fragment(
+            selection
+                .map(|selection| self.line_fragment(&selection))
+                .as_deref(),
+        );
+        Some(url)
+    }
+
+    fn new_issue_url(&self, remote: &ParsedGitRemote, title: &str, body: &str) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+
+        let mut url = self
+            .base_url()
+            .join(&format!("{owner}/{repo}/-/issues/new"))
+            .ok()?;
+        url.query_pairs_mut()
+            .append_pair("issue[title]", title)
+            .append_pair("issue[description]", body);
+        Some(url)
+    }
+
+    fn supports_ci_overview(&self) -> bool {
+        true
+    }
+
+    fn ci_overview_url(&self, remote: &ParsedGitRemote) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+
+        self.base_url()
+            .join(&format!("{owner}/{repo}/-/pipelines"))
+            .ok()
+    }
+
+    fn build_ci_job_url(
+        &self,
+        remote: &ParsedGitRemote,
+        params: BuildCiJobUrlParams,
+    ) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let BuildCiJobUrlParams { job_id, .. } = params;
+
+        self.base_url()
+            .join(&format!("{owner}/{repo}/-/jobs/{job_id}"))
+            .ok()
+    }
 }
 
 #[cfg(test)]
@@ -202,6 +383,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_gitlab_with_path_prefix() {
+        let gitlab = Gitlab::new(
+            "Corp GitLab",
+            Url::parse("https://devtools.corp/gitlab/").unwrap(),
+        );
+
+        for remote_url in [
+            "https://devtools.corp/gitlab/group/subgroup/zed.git",
+            "git@devtools.corp:group/subgroup/zed.git",
+        ] {
+            assert_eq!(
+                gitlab.parse_remote_url(remote_url).unwrap(),
+                ParsedGitRemote {
+                    owner: "group/subgroup".into(),
+                    repo: "zed".into(),
+                }
+            );
+        }
+
+        let permalink = gitlab.build_permalink(
+            ParsedGitRemote {
+                owner: "group".into(),
+                repo: "zed".into(),
+            },
+            BuildPermalinkParams {
+                sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+                path: "crates/zed/src/main.rs",
+                selection: None,
+            },
+        );
+        let expected_url = "https://devtools.corp/gitlab/group/zed/-/blob/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/zed/src/main.rs";
+        assert_eq!(permalink.to_string(), expected_url.to_string());
+
+        let url = Url::parse("https://devtools.corp/gitlab/group/zed/-/issues/3").unwrap();
+        let parsed = gitlab.parse_issue_url(&url).unwrap();
+        assert_eq!(parsed.remote.owner.as_ref(), "group");
+        assert_eq!(parsed.number, 3);
+    }
+
     #[test]
     fn test_build_gitlab_permalink() {
         let permalink = Gitlab::public_instance().build_permalink(
@@ -297,4 +518,199 @@ mod tests {
         let expected_url = "https://gitlab-instance.big-co.com/zed-industries/zed/-/blob/b2efec9824c45fcc90c9a7eb107a50d1772a60aa/crates/zed/src/main.rs";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
+
+    #[test]
+    fn test_gitlab_ci_overview_url() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+
+        let url = Gitlab::public_instance().ci_overview_url(&remote).unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://gitlab.com/zed-industries/zed/-/pipelines"
+        );
+
+        let self_hosted =
+            Gitlab::from_remote_url("git@gitlab.my-enterprise.com:zed-industries/zed.git").unwrap();
+        let url = self_hosted.ci_overview_url(&remote).unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://gitlab.my-enterprise.com/zed-industries/zed/-/pipelines"
+        );
+    }
+
+    #[test]
+    fn test_build_gitlab_ci_job_url() {
+        let url = Gitlab::public_instance()
+            .build_ci_job_url(
+                &ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildCiJobUrlParams {
+                    pipeline_id: "15219105807",
+                    job_id: "42863374264",
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://gitlab.com/zed-industries/zed/-/jobs/42863374264"
+        );
+    }
+
+    #[test]
+    fn test_parse_gitlab_issue_and_merge_request_urls() {
+        let gitlab = Gitlab::public_instance();
+
+        let url = Url::parse("https://gitlab.com/zed-industries/zed/-/issues/42").unwrap();
+        let parsed = gitlab.parse_issue_url(&url).unwrap();
+        assert_eq!(parsed.remote.owner.as_ref(), "zed-industries");
+        assert_eq!(parsed.remote.repo.as_ref(), "zed");
+        assert_eq!(parsed.number, 42);
+        assert_eq!(gitlab.parse_pull_request_url(&url), None);
+
+        let url = Url::parse(
+            "https://gitlab.com/zed-industries/group/zed/-/merge_requests/7/diffs#note_1",
+        )
+        .unwrap();
+        let parsed = gitlab.parse_pull_request_url(&url).unwrap();
+        assert_eq!(parsed.remote.owner.as_ref(), "zed-industries/group");
+        assert_eq!(parsed.number, 7);
+        assert_eq!(
+            parsed.url.to_string(),
+            "https://gitlab.com/zed-industries/group/zed/-/merge_requests/7"
+        );
+
+        let url = Url::parse("https://gitlab.com/zed/-/issues/42").unwrap();
+        assert_eq!(gitlab.parse_issue_url(&url), None);
+
+        let self_hosted =
+            Gitlab::from_remote_url("git@gitlab.my-enterprise.com:group/zed.git").unwrap();
+        let url = Url::parse("https://gitlab.my-enterprise.com/group/zed/-/issues/3").unwrap();
+        assert_eq!(gitlab.parse_issue_url(&url), None);
+        assert_eq!(self_hosted.parse_issue_url(&url).unwrap().number, 3);
+    }
+
+    #[test]
+    fn test_build_gitlab_lfs_media_url() {
+        let url = Gitlab::public_instance()
+            .build_lfs_media_url(
+                &ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+                    path: "assets/images/logo.png",
+                    selection: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://gitlab.com/zed-industries/zed/-/raw/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/assets/images/logo.png"
+        );
+    }
+
+    #[test]
+    fn test_build_gitlab_blame_url_at() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+        let params = |selection| BuildPermalinkParams {
+            sha: "e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7",
+            path: "crates/editor/src/git/permalink.rs",
+            selection,
+        };
+
+        let url = Gitlab::public_instance()
+            .blame_url_at(&remote, params(Some(6..6)))
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://gitlab.com/zed-industries/zed/-/blame/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/editor/src/git/permalink.rs#L7"
+        );
+
+        let url = Gitlab::from_remote_url("git@gitlab.my-enterprise.com:zed-industries/zed.git")
+            .unwrap()
+            .blame_url_at(&remote, params(Some(23..47)))
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://gitlab.my-enterprise.com/zed-industries/zed/-/blame/e6ebe7974deb6bb6cc0e2595c8ec31f0c71084b7/crates/editor/src/git/permalink.rs#L24-48"
+        );
+    }
+
+    #[test]
+    fn test_build_gitlab_new_issue_url() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+
+        let url = Gitlab::from_remote_url("git@gitlab.my-enterprise.com:zed-industries/zed.git")
+            .unwrap()
+            .new_issue_url(&remote, "FIXME: a & b", "Line one\nLine two")
+            .unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://gitlab.my-enterprise.com/zed-industries/zed/-/issues/new?issue%5Btitle%5D=FIXME%3A+a+%26+b&issue%5Bdescription%5D=Line+one%0ALine+two"
+        );
+    }
+
+    #[test]
+    fn test_gitlab_security_url() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+
+        let url = Gitlab::public_instance().security_url(&remote).unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://gitlab.com/zed-industries/zed/-/security"
+        );
+
+        let self_hosted =
+            Gitlab::from_remote_url("git@gitlab.some-enterprise.com:zed-industries/zed.git")
+                .unwrap();
+        let url = self_hosted.security_url(&remote).unwrap();
+        assert_eq!(
+            url.to_string(),
+            "https://gitlab.some-enterprise.com/zed-industries/zed/-/security"
+        );
+    }
+
+    #[test]
+    fn test_gitlab_has_no_discussions() {
+        let remote = ParsedGitRemote {
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+
+        let gitlab = Gitlab::public_instance();
+        assert!(!gitlab.supports_discussions());
+        assert_eq!(gitlab.discussions_url(&remote), None);
+    }
+
+    #[test]
+    fn test_build_gitlab_code_owner_url() {
+        let gitlab = Gitlab::public_instance();
+        let url = |owner| {
+            gitlab
+                .build_code_owner_url(owner)
+                .map(|url| url.to_string())
+        };
+
+        assert_eq!(url("@jane").as_deref(), Some("https://gitlab.com/jane"));
+        assert_eq!(
+            url("@zed-industries/editors").as_deref(),
+            Some("https://gitlab.com/zed-industries/editors")
+        );
+        assert_eq!(url("jane@example.com"), None);
+    }
 }
@@ -85,6 +85,26 @@ impl GitHostingProvider for Sourcehut {
         );
         permalink
     }
+
+    fn blame_url_at(&self, remote: &ParsedGitRemote, params: BuildPermalinkParams) -> Option<Url> {
+        let ParsedGitRemote { owner, repo } = remote;
+        let BuildPermalinkParams {
+            sha,
+            path,
+            selection,
+        } = params;
+
+        let mut url = self
+            .base_url()
+            .join(&format!("~{owner}/{repo}/blame/{sha}/{path}"))
+            .ok()?;
+        url.set_fragment(
+            selection
+                .map(|selection| self.line_fragment(&selection))
+                .as_deref(),
+        );
+        Some(url)
+    }
 }
 
 #[cfg(test)]
@@ -209,4 +229,24 @@ mod tests {
         let expected_url = "https://git.sr.ht/~zed-industries/zed/tree/faa6f979be417239b2e070dbbf6392b909224e0b/item/crates/editor/src/git/permalink.rs#L24-48";
         assert_eq!(permalink.to_string(), expected_url.to_string())
     }
+
+    #[test]
+    fn test_build_sourcehut_blame_url_at() {
+        let url = Sourcehut
+            .blame_url_at(
+                &ParsedGitRemote {
+                    owner: "zed-industries".into(),
+                    repo: "zed".into(),
+                },
+                BuildPermalinkParams {
+                    sha: "faa6f979be417239b2e070dbbf6392b909224e0b",
+                    path: "crates/editor/src/git/permalink.rs",
+                    selection: Some(23..47),
+                },
+            )
+            .unwrap();
+
+        let expected_url = "https://git.sr.ht/~zed-industries/zed/blame/faa6f979be417239b2e070dbbf6392b909224e0b/crates/editor/src/git/permalink.rs#L24-48";
+        assert_eq!(url.to_string(), expected_url.to_string())
+    }
 }
@@ -1,66 +1,332 @@
-use std::sync::Arc;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, iter,
+    path::PathBuf,
+    sync::Arc,
+};
 
-use anyhow::Result;
-use git::GitHostingProviderRegistry;
-use gpui::App;
+use anyhow::{Context as _, Result, anyhow};
+use credentials_provider::CredentialsProvider;
+use futures::{FutureExt as _, future::BoxFuture};
+use git::{BuildPermalinkParams, GitHostingProvider, GitHostingProviderRegistry, GitRemote};
+use gpui::{App, Global};
+use http_client::{AsyncBody, HttpClient};
+use regex::Regex;
+use reqwest_client::{CertificateOptions, ReqwestClient};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
 use url::Url;
 use util::ResultExt as _;
 
-use crate::{Bitbucket, Github, Gitlab};
+use crate::{Bitbucket, BitbucketFlavor, Forgejo, Gitea, Github, Gitlab, is_on_provider_host};
 
 pub(crate) fn init(cx: &mut App) {
     GitHostingProviderSettings::register(cx);
 
-    init_git_hosting_provider_settings(cx);
+    let credentials_provider = <dyn CredentialsProvider>::global(cx);
+    init_git_hosting_provider_settings(credentials_provider, cx);
 }
 
-fn init_git_hosting_provider_settings(cx: &mut App) {
-    update_git_hosting_providers_from_settings(cx);
+fn init_git_hosting_provider_settings(
+    credentials_provider: Arc<dyn CredentialsProvider>,
+    cx: &mut App,
+) {
+    update_git_hosting_providers_from_settings(&credentials_provider, cx);
 
-    cx.observe_global::<SettingsStore>(update_git_hosting_providers_from_settings)
-        .detach();
+    cx.observe_global::<SettingsStore>(move |cx| {
+        update_git_hosting_providers_from_settings(&credentials_provider, cx)
+    })
+    .detach();
 }
 
-fn update_git_hosting_providers_from_settings(cx: &mut App) {
+/// The access tokens of the configured providers that were read from the credential store,
+/// by their `credential_key`.
+#[derive(Default)]
+struct GitHostingProviderAccessTokens {
+    tokens: HashMap<String, String>,
+    /// The keys that were looked up, including those without a stored token, so that each
+    /// key is only looked up once while it is configured. Keys whose lookup failed are
+    /// looked up again the next time the settings change.
+    requested_keys: HashSet<String>,
+}
+
+impl Global for GitHostingProviderAccessTokens {}
+
+/// Registers the providers configured in the settings.
+///
+/// A provider may be configured both in the global settings and in the settings of one or
+/// more projects, in which case only one config is used for each provider and for each name:
+/// project settings take precedence over global ones, and later project settings over
+/// earlier ones. See [`merge_git_hosting_provider_configs`] for the fields that are only read
+/// from the global settings.
+///
+/// Providers with a `credential_key` are registered again once their access token has been
+/// read from the credential store.
+fn update_git_hosting_providers_from_settings(
+    credentials_provider: &Arc<dyn CredentialsProvider>,
+    cx: &mut App,
+) {
     let settings_store = cx.global::<SettingsStore>();
     let settings = GitHostingProviderSettings::get_global(cx);
     let provider_registry = GitHostingProviderRegistry::global(cx);
 
-    let local_values: Vec<GitHostingProviderConfig> = settings_store
+    let local_values = settings_store
         .get_all_locals::<GitHostingProviderSettings>()
         .into_iter()
-        .flat_map(|(_, _, providers)| providers.git_hosting_providers.clone())
-        .collect();
+        .map(|(_, _, providers)| providers.git_hosting_providers.clone());
+
+    let configs =
+        merge_git_hosting_provider_configs(settings.git_hosting_providers.clone(), local_values);
+    read_access_tokens(&configs, credentials_provider, cx);
+
+    let access_tokens = &cx.default_global::<GitHostingProviderAccessTokens>().tokens;
+    let (providers, errors) = build_git_hosting_providers(configs, access_tokens);
+    for error in &errors {
+        log::error!("invalid Git hosting provider: {error}");
+    }
+
+    provider_registry.set_setting_providers(providers);
+    cx.set_global(GitHostingProviderSettingsErrors(errors));
+}
 
-    let iter = settings
-        .git_hosting_providers
-        .clone()
+/// Reads the access tokens of the given configs that haven't been looked up yet from the
+/// credential store, then registers the providers again.
+fn read_access_tokens(
+    configs: &[GitHostingProviderConfig],
+    credentials_provider: &Arc<dyn CredentialsProvider>,
+    cx: &mut App,
+) {
+    let configured_keys = configs
+        .iter()
+        .filter_map(|config| config.credential_key.clone())
+        .collect::<HashSet<_>>();
+    let access_tokens = cx.default_global::<GitHostingProviderAccessTokens>();
+    access_tokens
+        .requested_keys
+        .retain(|key| configured_keys.contains(key));
+    access_tokens
+        .tokens
+        .retain(|key, _| configured_keys.contains(key));
+    let keys = configured_keys
         .into_iter()
-        .chain(local_values)
-        .filter_map(|provider| {
-            let url = Url::parse(&provider.base_url).log_err()?;
+        .filter(|key| access_tokens.requested_keys.insert(key.clone()))
+        .collect::<Vec<_>>();
+    if keys.is_empty() {
+        return;
+    }
 
-            Some(match provider.provider {
-                GitHostingProviderKind::Bitbucket => {
-                    Arc::new(Bitbucket::new(&provider.name, url)) as _
+    let credentials_provider = credentials_provider.clone();
+    cx.spawn(async move |cx| {
+        let mut tokens = Vec::new();
+        let mut failed_keys = Vec::new();
+        for key in keys {
+            let token = credentials_provider
+                .read_credentials(&key, cx)
+                .await
+                .with_context(|| format!("failed to read the access token for {key:?}"))
+                .and_then(|credentials| {
+                    credentials
+                        .map(|(_, token)| String::from_utf8(token))
+                        .transpose()
+                        .with_context(|| format!("the access token for {key:?} is not UTF-8"))
+                });
+            match token {
+                Ok(Some(token)) => tokens.push((key, token)),
+                Ok(None) => {}
+                Err(error) => {
+                    log::error!("{error:#}");
+                    failed_keys.push(key);
                 }
-                GitHostingProviderKind::Github => Arc::new(Github::new(&provider.name, url)) as _,
-                GitHostingProviderKind::Gitlab => Arc::new(Gitlab::new(&provider.name, url)) as _,
-            })
-        });
+            }
+        }
 
-    provider_registry.set_setting_providers(iter);
+        cx.update(|cx| {
+            let access_tokens = cx.default_global::<GitHostingProviderAccessTokens>();
+            for key in &failed_keys {
+                access_tokens.requested_keys.remove(key);
+            }
+            access_tokens.tokens.extend(tokens);
+            update_git_hosting_providers_from_settings(&credentials_provider, cx);
+        })
+        .log_err();
+    })
+    .detach();
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+/// Combines the configs of the global settings with those of the given project settings
+/// files, from the one with the lowest precedence to the one with the highest.
+///
+/// A config replaces the configs of earlier files that are for the same provider, meaning
+/// the same kind, base URL and API path prefix, or that have the same name, taking the
+/// position of the first of them. Configs of the same file are all kept, so that
+/// [`build_git_hosting_providers`] reports their duplicate names.
+///
+/// Fields that make Zed send credentials, such as `credential_key` and `url_shortener_url`, or
+/// that change how the certificates of the hosts they are sent to are verified, are ignored in
+/// project settings, which are controlled by whoever wrote the repository. A project config
+/// keeps those of the global config for the same provider that it replaces.
+fn merge_git_hosting_provider_configs(
+    global_configs: Vec<GitHostingProviderConfig>,
+    local_configs: impl IntoIterator<Item = Vec<GitHostingProviderConfig>>,
+) -> Vec<GitHostingProviderConfig> {
+    let local_configs = local_configs.into_iter().map(|configs| {
+        configs
+            .into_iter()
+            .map(GitHostingProviderConfig::without_global_only_fields)
+            .collect()
+    });
+
+    // Each config along with the index of the file it is from.
+    let mut configs: Vec<(usize, GitHostingProviderConfig)> = Vec::new();
+    let sources = iter::once(global_configs).chain(local_configs);
+    for (source_ix, source) in sources.enumerate() {
+        for mut config in source {
+            let key = config.merge_key();
+            let name = config.name.trim().to_string();
+            let is_replaced = |(earlier_source_ix, earlier): &(usize, GitHostingProviderConfig)| {
+                *earlier_source_ix < source_ix
+                    && (earlier.merge_key() == key || earlier.name.trim() == name)
+            };
+            if let Some(ix) = configs.iter().position(is_replaced) {
+                if let Some((_, earlier)) = configs
+                    .iter()
+                    .find(|entry| is_replaced(entry) && entry.1.merge_key() == key)
+                {
+                    config.keep_global_only_fields(earlier);
+                }
+                configs[ix] = (source_ix, config);
+                configs.retain(|entry| !is_replaced(entry));
+            } else {
+                configs.push((source_ix, config));
+            }
+        }
+    }
+    configs.into_iter().map(|(_, config)| config).collect()
+}
+
+/// Returns the base URL in a form that is equal for URLs of the same location, such as
+/// `https://GitLab.corp` and `https://gitlab.corp/`.
+fn canonical_base_url(base_url: &str) -> String {
+    let base_url = base_url.trim();
+    Url::parse(base_url)
+        .map(|url| url.as_str().trim_end_matches('/').to_string())
+        .unwrap_or_else(|_| base_url.trim_end_matches('/').to_string())
+}
+
+/// Builds the providers of the given configs, returning the errors of those that could
+/// not be built separately, so that one broken config doesn't prevent the others from
+/// being registered. Of configs with the same name, only the first valid one is built.
+///
+/// The providers of configs with a `credential_key` call their API with the token stored under
+/// that key in `access_tokens`, if there is one.
+pub fn build_git_hosting_providers(
+    configs: impl IntoIterator<Item = GitHostingProviderConfig>,
+    access_tokens: &HashMap<String, String>,
+) -> (
+    Vec<Arc<dyn GitHostingProvider + Send + Sync + 'static>>,
+    Vec<GitHostingProviderConfigError>,
+) {
+    let mut names = HashSet::new();
+    let mut providers = Vec::new();
+    let mut errors = Vec::new();
+    for config in configs {
+        let name = config.name.trim().to_string();
+        if name.is_empty() {
+            errors.push(GitHostingProviderConfigError::EmptyName {
+                base_url: config.base_url,
+            });
+        } else if Url::parse(&config.base_url).is_err() {
+            errors.push(GitHostingProviderConfigError::InvalidUrl {
+                name,
+                base_url: config.base_url,
+            });
+        } else if names.contains(&name) {
+            errors.push(GitHostingProviderConfigError::DuplicateName { name });
+        } else {
+            let access_token = config
+                .credential_key
+                .as_ref()
+                .and_then(|key| access_tokens.get(key))
+                .cloned();
+            match config.build_provider_with_access_token(access_token) {
+                Ok(provider) => {
+                    providers.push(provider);
+                    names.insert(name);
+                }
+                Err(error) => errors.push(GitHostingProviderConfigError::Invalid {
+                    name,
+                    error: format!("{error:#}"),
+                }),
+            }
+        }
+    }
+    (providers, errors)
+}
+
+/// Why a configured Git hosting provider was not registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitHostingProviderConfigError {
+    /// The provider's `name` is empty.
+    EmptyName { base_url: String },
+    /// The provider's `base_url` is not a valid URL.
+    InvalidUrl { name: String, base_url: String },
+    /// An earlier provider has the same `name`.
+    DuplicateName { name: String },
+    /// The provider could not be built for another reason, such as an invalid
+    /// `email_to_username` rule.
+    Invalid { name: String, error: String },
+}
+
+impl fmt::Display for GitHostingProviderConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyName { base_url } => {
+                write!(f, "the provider for {base_url:?} has an empty name")
+            }
+            Self::InvalidUrl { name, base_url } => {
+                write!(f, "{name}: invalid base URL {base_url:?}")
+            }
+            Self::DuplicateName { name } => {
+                write!(f, "{name}: another provider already has this name")
+            }
+            Self::Invalid { name, error } => write!(f, "{name}: {error}"),
+        }
+    }
+}
+
+/// The errors of the configured Git hosting providers that were not registered, as of the
+/// last time the settings changed.
+#[derive(Debug, Default)]
+pub struct GitHostingProviderSettingsErrors(pub Vec<GitHostingProviderConfigError>);
+
+impl Global for GitHostingProviderSettingsErrors {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum GitHostingProviderKind {
     Github,
     Gitlab,
     Bitbucket,
+    /// A self-hosted Bitbucket Server or Data Center instance, whose URLs differ from those
+    /// of Bitbucket Cloud.
+    BitbucketServer,
+    Gitea,
+    Forgejo,
+}
+
+impl GitHostingProviderKind {
+    /// Returns the kind reported by the providers built for this kind of config.
+    fn provider_kind(self) -> &'static str {
+        match self {
+            Self::Github => "github",
+            Self::Gitlab => "gitlab",
+            Self::Bitbucket => "bitbucket",
+            Self::BitbucketServer => "bitbucket_server",
+            // Forgejo instances are served by the Gitea provider.
+            Self::Gitea | Self::Forgejo => "gitea",
+        }
+    }
 }
 
 /// A custom Git hosting provider.
@@ -68,7 +334,8 @@ pub enum GitHostingProviderKind {
 pub struct GitHostingProviderConfig {
     /// The type of the provider.
     ///
-    /// Must be one of `github`, `gitlab`, or `bitbucket`.
+    /// Must be one of `github`, `gitlab`, `bitbucket`, `bitbucket_server`, `gitea`, or
+    /// `forgejo`.
     pub provider: GitHostingProviderKind,
 
     /// The base URL for the provider (e.g., "https://code.corp.big.com").
@@ -76,6 +343,538 @@ pub struct GitHostingProviderConfig {
 
     /// The display name for the provider (e.g., "BigCorp GitHub").
     pub name: String,
+
+    /// The base URL for the provider's API (e.g., "https://api.code.corp.big.com").
+    ///
+    /// Only used by `github` providers. When unset, the API is assumed to live
+    /// at `/api/v3` on the `base_url`, as it does for GitHub Enterprise Server,
+    /// or on the `api` subdomain of github.com and of `*.ghe.com` hosts.
+    #[serde(default)]
+    pub api_base_url: Option<String>,
+
+    /// The path under which the provider is served on its host, for instances
+    /// behind a reverse proxy (e.g., "/gitlab" for a `base_url` of
+    /// "https://devtools.corp").
+    ///
+    /// Generated links include the prefix, and `gitlab` providers also remove
+    /// it from the paths of remotes. When unset, the provider is assumed to be
+    /// served from the root of its host, or from the path of the `base_url`.
+    #[serde(default)]
+    pub api_path_prefix: Option<String>,
+
+    /// The URL that links to the provider point to (e.g., "https://git.big-corp.com"),
+    /// for instances exposed through a reverse proxy under a different host than
+    /// the one of their remotes.
+    ///
+    /// Remotes are still recognized by the host of the `base_url`. When unset,
+    /// links point to the `base_url`.
+    #[serde(default)]
+    pub public_url: Option<String>,
+
+    /// The URL of the host that serves the raw content of files (e.g.,
+    /// "https://raw.code.corp.big.com"), for GitHub Enterprise instances with
+    /// subdomain isolation. Files are expected at `{owner}/{repo}/{sha}/{path}`
+    /// under it.
+    ///
+    /// Only used by `github` providers. When unset, raw content is linked from
+    /// the `raw` endpoint of the instance.
+    #[serde(default)]
+    pub raw_url: Option<String>,
+
+    /// The URL of the host that serves the avatars of users (e.g.,
+    /// "https://avatars.code.corp.big.com"), for GitHub Enterprise instances
+    /// with subdomain isolation. Avatars are expected at `{username}` under it.
+    ///
+    /// Only used by `github` providers. When unset, avatars are linked from the
+    /// instance itself.
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+
+    /// The URL of the instance's URL shortener (e.g., "https://go.code.corp.big.com/api"),
+    /// which is used to shorten permalinks when `shorten_permalinks` is enabled.
+    /// The full URL is posted to it as the `url` form field, and the shortened
+    /// URL is read from the `Location` header or the body of the response. The
+    /// access token is sent along when the shortener is on the host of the
+    /// `base_url` or one of its subdomains.
+    ///
+    /// Only used by `github` providers, and only read from the user's settings,
+    /// not from those of projects.
+    #[serde(default)]
+    pub url_shortener_url: Option<String>,
+
+    /// Rules deriving the usernames of commit authors from their emails, which
+    /// are used to link to their profiles and avatars without querying the
+    /// provider's API. The first rule whose `email` matches is used.
+    ///
+    /// Only used by `github` and `gitlab` providers.
+    #[serde(default)]
+    pub email_to_username: Vec<EmailToUsernameRule>,
+
+    /// Whether to accept any TLS certificate from the provider's API, including
+    /// self-signed and expired ones.
+    ///
+    /// WARNING: This turns off certificate verification for the provider, which
+    /// lets anyone able to intercept the connection impersonate it and read the
+    /// requests, including any access token. Prefer `ca_certificate_path` for
+    /// hosts with an internal certificate authority. Requests to other hosts
+    /// are verified as usual, but requests to this provider's API do not use
+    /// the configured proxy.
+    ///
+    /// Only used by `github` providers, and only read from the user's settings,
+    /// not from those of projects.
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+
+    /// The path to a PEM file of certificate authorities to trust, in addition
+    /// to the system's, when calling the provider's API (e.g.,
+    /// "/etc/ssl/certs/corp-ca.pem"). As with `accept_invalid_certs`, requests
+    /// to the provider's API do not use the configured proxy.
+    ///
+    /// Only used by `github` providers, and only read from the user's settings,
+    /// not from those of projects.
+    #[serde(default)]
+    pub ca_certificate_path: Option<PathBuf>,
+
+    /// The key of the entry in the system's credential store, such as the
+    /// macOS Keychain, that holds an access token for the provider's API
+    /// (e.g., "https://api.code.corp.big.com"). The token itself must not be
+    /// put in the settings.
+    ///
+    /// Only supported by `github` providers, and only read from the user's
+    /// settings, not from those of projects. The token is only sent to an API
+    /// on the host of the `base_url` or one of its subdomains. When unset, the
+    /// token is read from the `GITHUB_TOKEN` environment variable, if set.
+    #[serde(default)]
+    pub credential_key: Option<String>,
+
+    /// Additional hosts whose remotes belong to the provider, such as SSH host
+    /// aliases from `~/.ssh/config` (e.g., "gh-work"). Links to such remotes
+    /// still point to the `base_url`, or to the `public_url` when set.
+    #[serde(default)]
+    pub remote_host_aliases: Vec<String>,
+}
+
+/// A rule deriving the username of a commit author from their email.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct EmailToUsernameRule {
+    /// A regex that must match the whole email (e.g., "(.+)@corp\\.big\\.com").
+    pub email: String,
+
+    /// The username, in which `$1`, `$2`, and so on are replaced with the
+    /// groups captured by `email` (e.g., "$1").
+    pub username: String,
+}
+
+/// The compiled [`EmailToUsernameRule`]s of a provider.
+#[derive(Debug, Default)]
+pub struct EmailToUsername {
+    rules: Vec<(Regex, String)>,
+}
+
+impl EmailToUsername {
+    pub fn new(rules: &[EmailToUsernameRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let email = Regex::new(&format!("^(?:{})$", rule.email))
+                    .with_context(|| format!("invalid email pattern {:?}", rule.email))?;
+                Ok((email, rule.username.clone()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the username derived from the given email by the first matching rule.
+    pub fn username_for_email(&self, email: &str) -> Option<String> {
+        self.rules.iter().find_map(|(pattern, username)| {
+            let mut result = String::new();
+            pattern.captures(email)?.expand(username, &mut result);
+            Some(result).filter(|username| !username.is_empty())
+        })
+    }
+}
+
+/// The HTTP client a provider calls its API with instead of the app's.
+#[derive(Clone)]
+pub struct ProviderHttpClient(pub Arc<dyn HttpClient>);
+
+impl std::fmt::Debug for ProviderHttpClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ProviderHttpClient")
+            .field(&self.0.type_name())
+            .finish()
+    }
+}
+
+/// The HTTP client for a provider whose config changes how certificates are verified.
+///
+/// The CA bundle is read, and the underlying client built, when the first request is sent
+/// rather than while the settings are loading.
+struct CertificateHttpClient {
+    name: String,
+    accept_invalid_certs: bool,
+    ca_certificate_path: Option<PathBuf>,
+    client: Arc<futures::lock::Mutex<Option<Arc<ReqwestClient>>>>,
+}
+
+impl CertificateHttpClient {
+    fn build_client(
+        name: &str,
+        accept_invalid_certs: bool,
+        ca_certificate_path: Option<&PathBuf>,
+    ) -> Result<ReqwestClient> {
+        let ca_bundle = ca_certificate_path
+            .map(|path| {
+                std::fs::read(path).with_context(|| {
+                    format!("failed to read CA certificates from {}", path.display())
+                })
+            })
+            .transpose()?;
+        let options = CertificateOptions {
+            accept_invalid_certs,
+            ca_bundle,
+        };
+        ReqwestClient::user_agent_and_certificates("Zed", &options)
+            .with_context(|| format!("failed to build the HTTP client for {name}"))
+    }
+}
+
+impl HttpClient for CertificateHttpClient {
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn proxy(&self) -> Option<&Url> {
+        None
+    }
+
+    fn send(
+        &self,
+        req: http_client::Request<AsyncBody>,
+    ) -> BoxFuture<'static, Result<http_client::Response<AsyncBody>>> {
+        let name = self.name.clone();
+        let accept_invalid_certs = self.accept_invalid_certs;
+        let ca_certificate_path = self.ca_certificate_path.clone();
+        let client = self.client.clone();
+        async move {
+            let client = {
+                let mut client = client.lock().await;
+                match client.as_ref() {
+                    Some(client) => client.clone(),
+                    None => {
+                        // Failures aren't cached, so a fixed CA bundle is picked up by the
+                        // next request.
+                        let built = Arc::new(Self::build_client(
+                            &name,
+                            accept_invalid_certs,
+                            ca_certificate_path.as_ref(),
+                        )?);
+                        client.insert(built).clone()
+                    }
+                }
+            };
+            client.send(req).await
+        }
+        .boxed()
+    }
+}
+
+impl GitHostingProviderConfig {
+    /// Returns the HTTP client for the provider's API when the config changes how its
+    /// certificates are verified. Otherwise, the app's client is used.
+    fn build_http_client(&self) -> Option<Arc<dyn HttpClient>> {
+        if !self.accept_invalid_certs && self.ca_certificate_path.is_none() {
+            return None;
+        }
+
+        Some(Arc::new(CertificateHttpClient {
+            name: self.name.clone(),
+            accept_invalid_certs: self.accept_invalid_certs,
+            ca_certificate_path: self.ca_certificate_path.clone(),
+            client: Arc::default(),
+        }))
+    }
+
+    /// Returns what identifies the provider a config is for when configs are merged: its
+    /// kind, its base URL and its API path prefix, each in a form that is equal for equivalent
+    /// values.
+    fn merge_key(&self) -> (GitHostingProviderKind, String, Option<String>) {
+        let api_path_prefix = self.api_path_prefix.as_deref().map(|path_prefix| {
+            normalize_path_prefix(path_prefix).unwrap_or_else(|_| path_prefix.to_string())
+        });
+        (
+            self.provider,
+            canonical_base_url(&self.base_url),
+            api_path_prefix,
+        )
+    }
+
+    /// Returns the config without the fields that are only read from the global settings.
+    fn without_global_only_fields(self) -> Self {
+        Self {
+            url_shortener_url: None,
+            accept_invalid_certs: false,
+            ca_certificate_path: None,
+            credential_key: None,
+            ..self
+        }
+    }
+
+    /// Takes the fields that are only read from the global settings from the given config, which
+    /// is for the same provider.
+    fn keep_global_only_fields(&mut self, global_config: &Self) {
+        self.url_shortener_url = global_config.url_shortener_url.clone();
+        self.accept_invalid_certs = global_config.accept_invalid_certs;
+        self.ca_certificate_path = global_config.ca_certificate_path.clone();
+        self.credential_key = global_config.credential_key.clone();
+    }
+
+    /// Returns the `base_url`, including the `api_path_prefix` if there is one.
+    fn remote_base_url(&self) -> Result<Url> {
+        let mut url = Url::parse(&self.base_url)
+            .with_context(|| format!("invalid base URL {:?}", self.base_url))?;
+        if let Some(path_prefix) = self.api_path_prefix.as_deref() {
+            let path_prefix = normalize_path_prefix(path_prefix)?;
+            let base_path = url.path().trim_end_matches('/');
+            anyhow::ensure!(
+                base_path.is_empty() || base_path == path_prefix,
+                "path prefix {path_prefix:?} does not match the path of the base URL {url}"
+            );
+            url.set_path(&format!("{path_prefix}/"));
+        }
+        Ok(url)
+    }
+
+    /// Returns the URL that links point to, which is the `base_url` unless a `public_url` is
+    /// set.
+    fn public_url(&self, remote_base_url: &Url) -> Result<Url> {
+        match self.public_url.as_deref() {
+            Some(public_url) => parse_url_to_join_onto(public_url, "public URL"),
+            None => Ok(remote_base_url.clone()),
+        }
+    }
+
+    /// Builds the hosting provider described by this config.
+    pub fn build_provider(&self) -> Result<Arc<dyn GitHostingProvider + Send + Sync + 'static>> {
+        self.build_provider_with_access_token(None)
+    }
+
+    /// Builds the hosting provider described by this config, calling its API with the given
+    /// access token.
+    pub fn build_provider_with_access_token(
+        &self,
+        access_token: Option<String>,
+    ) -> Result<Arc<dyn GitHostingProvider + Send + Sync + 'static>> {
+        anyhow::ensure!(
+            self.credential_key.is_none() || self.provider == GitHostingProviderKind::Github,
+            "credential_key is only supported by github providers"
+        );
+        let url = self.remote_base_url()?;
+        let public_url = self.public_url(&url)?;
+        let email_to_username = EmailToUsername::new(&self.email_to_username)?;
+        let remote_host_aliases = self.remote_host_aliases.clone();
+
+        Ok(match self.provider {
+            GitHostingProviderKind::Bitbucket => Arc::new(
+                Bitbucket::new(&self.name, url)
+                    .with_public_url(public_url)
+                    .with_remote_host_aliases(remote_host_aliases),
+            ) as _,
+            GitHostingProviderKind::BitbucketServer => Arc::new(
+                Bitbucket::new(&self.name, url)
+                    .with_public_url(public_url)
+                    .with_remote_host_aliases(remote_host_aliases)
+                    .with_flavor(BitbucketFlavor::Server),
+            ) as _,
+            GitHostingProviderKind::Github => {
+                let mut github = Github::new(&self.name, url)
+                    .with_public_url(public_url)
+                    .with_remote_host_aliases(remote_host_aliases)
+                    .with_email_to_username(email_to_username);
+                if let Some(api_base_url) = self.api_base_url.as_deref() {
+                    github = github.with_api_base_url(
+                        Url::parse(api_base_url)
+                            .with_context(|| format!("invalid API base URL {api_base_url:?}"))?,
+                    );
+                }
+                if let Some(raw_url) = self.raw_url.as_deref() {
+                    github = github.with_raw_url(parse_url_to_join_onto(raw_url, "raw URL")?);
+                }
+                if let Some(avatar_url) = self.avatar_url.as_deref() {
+                    github =
+                        github.with_avatar_url(parse_url_to_join_onto(avatar_url, "avatar URL")?);
+                }
+                if let Some(url_shortener_url) = self.url_shortener_url.as_deref() {
+                    github = github.with_url_shortener_url(
+                        Url::parse(url_shortener_url).context("invalid URL shortener URL")?,
+                    );
+                }
+                if let Some(http_client) = self.build_http_client() {
+                    github = github.with_http_client(http_client);
+                }
+                if let Some(access_token) = access_token {
+                    let api_base_url = github.api_base_url();
+                    anyhow::ensure!(
+                        is_on_provider_host(&api_base_url, &url),
+                        "refusing to send the access token to {api_base_url}, \
+                        which is not on the host of the base URL"
+                    );
+                    github = github.with_access_token(access_token);
+                }
+                Arc::new(github) as _
+            }
+            GitHostingProviderKind::Gitlab => Arc::new(
+                Gitlab::new(&self.name, url)
+                    .with_public_url(public_url)
+                    .with_remote_host_aliases(remote_host_aliases)
+                    .with_email_to_username(email_to_username),
+            ) as _,
+            GitHostingProviderKind::Gitea => Arc::new(
+                Gitea::new(&self.name, url)
+                    .with_public_url(public_url)
+                    .with_remote_host_aliases(remote_host_aliases)
+                    .with_email_to_username(email_to_username),
+            ) as _,
+            GitHostingProviderKind::Forgejo => Arc::new(
+                Forgejo::new(&self.name, url)
+                    .with_public_url(public_url)
+                    .with_remote_host_aliases(remote_host_aliases)
+                    .with_email_to_username(email_to_username),
+            ) as _,
+        })
+    }
+
+    /// Checks that this config produces working links, without touching the
+    /// network, and returns a sample permalink on success.
+    ///
+    /// This verifies that the URLs parse, that remotes on the provider's host
+    /// are recognized, and that generated permalinks point to the host of the
+    /// `public_url`, or back to that host.
+    pub fn validate(&self) -> Result<Url> {
+        let provider = self.build_provider()?;
+        let base_url = provider.base_url();
+        anyhow::ensure!(
+            matches!(base_url.scheme(), "http" | "https"),
+            "base URL {base_url} must use http or https"
+        );
+
+        let remote_url = self.remote_base_url()?.join("owner/repo.git")?;
+        let remote = provider
+            .parse_remote_url(remote_url.as_str())
+            .ok_or_else(|| anyhow!("remotes such as {remote_url} are not recognized"))?;
+        let permalink = provider.build_permalink(
+            remote,
+            BuildPermalinkParams {
+                sha: "0000000000000000000000000000000000000000",
+                path: "src/main.rs",
+                selection: Some(9..9),
+            },
+        );
+        anyhow::ensure!(
+            permalink.host_str() == base_url.host_str(),
+            "permalinks would point to {permalink} instead of {base_url}"
+        );
+
+        Ok(permalink)
+    }
+
+    /// Checks that the URL that links point to responds to requests, using the provider's own
+    /// HTTP client when its config changes how certificates are verified, or the given one.
+    pub async fn check_reachability(&self, http_client: Arc<dyn HttpClient>) -> Result<()> {
+        let url = self.public_url(&self.remote_base_url()?)?;
+        let http_client = self.build_http_client().unwrap_or(http_client);
+        let response = http_client
+            .get(url.as_str(), AsyncBody::default(), true)
+            .await
+            .with_context(|| format!("failed to reach {url}"))?;
+        anyhow::ensure!(
+            !response.status().is_server_error(),
+            "{url} responded with {}",
+            response.status()
+        );
+
+        Ok(())
+    }
+}
+
+/// Parses a URL that paths are joined onto, keeping the last segment of its path when they
+/// are.
+fn parse_url_to_join_onto(url: &str, description: &str) -> Result<Url> {
+    let mut parsed_url =
+        Url::parse(url).with_context(|| format!("invalid {description} {url:?}"))?;
+    if !parsed_url.path().ends_with('/') {
+        parsed_url.set_path(&format!("{}/", parsed_url.path()));
+    }
+    Ok(parsed_url)
+}
+
+/// Returns the path prefix with a leading slash and without a trailing one.
+fn normalize_path_prefix(path_prefix: &str) -> Result<String> {
+    let segments = path_prefix.trim_matches('/').split('/').collect::<Vec<_>>();
+    anyhow::ensure!(
+        segments
+            .iter()
+            .all(|segment| !segment.is_empty() && !segment.contains(['?', '#'])),
+        "invalid path prefix {path_prefix:?}"
+    );
+    Ok(format!("/{}", segments.join("/")))
+}
+
+/// An external Git client that can open a repository through a deep link.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GitExternalAppConfig {
+    /// The display name for the application (e.g., "GitHub Desktop").
+    pub name: String,
+
+    /// The URL used to open the repository in the application
+    /// (e.g., "x-github-client://openRepo/{repository_url}").
+    ///
+    /// `{repository_url}` is replaced with the URL of the repository on its
+    /// Git hosting provider, and `{owner}` and `{repo}` with the parsed remote.
+    /// The values are percent-encoded.
+    pub url_template: String,
+
+    /// The kinds of Git hosting providers whose repositories the application
+    /// can open (e.g., `["github"]`).
+    ///
+    /// When empty, the application is offered for repositories on any provider.
+    #[serde(default)]
+    pub providers: Vec<GitHostingProviderKind>,
+}
+
+impl GitExternalAppConfig {
+    /// Returns whether this application can open the given remote.
+    pub fn supports(&self, remote: &GitRemote) -> bool {
+        self.providers.is_empty()
+            || remote.host.kind().is_some_and(|kind| {
+                self.providers
+                    .iter()
+                    .any(|provider| provider.provider_kind() == kind)
+            })
+    }
+
+    /// Returns the deep link that opens the given remote in this application.
+    pub fn build_url(&self, remote: &GitRemote) -> Result<Url> {
+        let repository_url = remote
+            .host
+            .base_url()
+            .join(&format!("{}/{}", remote.owner, remote.repo))?;
+
+        let url = self
+            .url_template
+            .replace(
+                "{repository_url}",
+                &urlencoding::encode(repository_url.as_str()),
+            )
+            .replace("{owner}", &urlencoding::encode(&remote.owner))
+            .replace("{repo}", &urlencoding::encode(&remote.repo));
+
+        Url::parse(&url).with_context(|| format!("invalid URL for {}: {url:?}", self.name))
+    }
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -83,6 +882,45 @@ pub struct GitHostingProviderSettings {
     /// The list of custom Git hosting providers.
     #[serde(default)]
     pub git_hosting_providers: Vec<GitHostingProviderConfig>,
+
+    /// The name of the Git remote used to resolve the hosting provider and
+    /// generate links (e.g., "upstream").
+    ///
+    /// When unset, the remotes in `remote_priority` are tried first, and then
+    /// the remote of the current branch's upstream is used, falling back to
+    /// "origin". Project settings take precedence over the global setting.
+    #[serde(default)]
+    pub permalink_remote: Option<String>,
+
+    /// The names of the Git remotes to try, in order, when resolving the
+    /// hosting provider and generating links (e.g., `["origin", "upstream"]`).
+    ///
+    /// The first remote on a known hosting provider is used. When none is,
+    /// the remote chosen as described for `permalink_remote` is used, which
+    /// also takes precedence over this list when it is set.
+    #[serde(default)]
+    pub remote_priority: Vec<String>,
+
+    /// Whether to shorten permalinks using the Git hosting provider's URL
+    /// shortener, when it offers one, such as the `url_shortener_url` of a
+    /// custom `github` provider.
+    ///
+    /// This requires a network round-trip, and falls back to the full
+    /// permalink when shortening fails.
+    #[serde(default)]
+    pub shorten_permalinks: bool,
+
+    /// Whether permalinks may point at the empty line that follows a file's
+    /// final newline.
+    ///
+    /// Git hosting providers don't show this line, so by default selections
+    /// that include it are clamped to the file's last line.
+    #[serde(default)]
+    pub permalink_include_trailing_line: bool,
+
+    /// The list of external Git clients that repositories can be opened in.
+    #[serde(default)]
+    pub git_external_apps: Vec<GitExternalAppConfig>,
 }
 
 impl Settings for GitHostingProviderSettings {
@@ -96,3 +934,745 @@ impl Settings for GitHostingProviderSettings {
 
     fn import_from_vscode(_vscode: &settings::VsCodeSettings, _current: &mut Self::FileContent) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        future::Future,
+        path::Path,
+        pin::Pin,
+        sync::{Arc, Mutex},
+    };
+
+    use anyhow::Result;
+    use credentials_provider::CredentialsProvider;
+    use futures::FutureExt as _;
+    use git::{BuildPermalinkParams, GitHostingProviderRegistry, GitRemote};
+    use gpui::{AsyncApp, TestAppContext};
+    use http_client::{AsyncBody, FakeHttpClient, HttpClient as _, Response};
+    use pretty_assertions::assert_eq;
+    use settings::{Settings as _, SettingsStore};
+
+    use crate::{
+        EmailToUsername, EmailToUsernameRule, GitExternalAppConfig, GitHostingProviderConfig,
+        GitHostingProviderConfigError, GitHostingProviderKind, Github, Gitlab,
+        build_git_hosting_providers,
+    };
+
+    use super::{
+        GitHostingProviderSettings, init_git_hosting_provider_settings,
+        merge_git_hosting_provider_configs,
+    };
+
+    /// Returns a config for a provider of the given kind, with every optional field unset.
+    fn test_config(
+        provider: GitHostingProviderKind,
+        name: &str,
+        base_url: &str,
+    ) -> GitHostingProviderConfig {
+        GitHostingProviderConfig {
+            provider,
+            base_url: base_url.into(),
+            name: name.into(),
+            api_base_url: None,
+            api_path_prefix: None,
+            public_url: None,
+            raw_url: None,
+            avatar_url: None,
+            url_shortener_url: None,
+            email_to_username: Vec::new(),
+            accept_invalid_certs: false,
+            ca_certificate_path: None,
+            credential_key: None,
+            remote_host_aliases: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_build_git_hosting_providers() {
+        let config = |name: &str, base_url: &str| {
+            test_config(GitHostingProviderKind::Gitlab, name, base_url)
+        };
+        let invalid_rules = GitHostingProviderConfig {
+            email_to_username: vec![EmailToUsernameRule {
+                email: "(".into(),
+                username: "$1".into(),
+            }],
+            ..config("Rules", "https://rules.corp")
+        };
+
+        let (providers, errors) = build_git_hosting_providers(
+            [
+                config("Corp GitLab", "https://gitlab.corp"),
+                config("  ", "https://unnamed.corp"),
+                config("Broken", "not a url"),
+                invalid_rules,
+                config("Corp GitLab", "https://gitlab2.corp"),
+                config("Rules", "https://rules.corp"),
+            ],
+            &HashMap::default(),
+        );
+        let registry = GitHostingProviderRegistry::new();
+        registry.set_setting_providers(providers);
+        let registered = registry
+            .list_hosting_providers()
+            .iter()
+            .map(|provider| (provider.name(), provider.base_url().to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            registered,
+            [
+                (
+                    "Corp GitLab".to_string(),
+                    "https://gitlab.corp/".to_string()
+                ),
+                ("Rules".to_string(), "https://rules.corp/".to_string()),
+            ]
+        );
+
+        assert_eq!(errors.len(), 4);
+        assert_eq!(
+            errors[0],
+            GitHostingProviderConfigError::EmptyName {
+                base_url: "https://unnamed.corp".into()
+            }
+        );
+        assert_eq!(
+            errors[1],
+            GitHostingProviderConfigError::InvalidUrl {
+                name: "Broken".into(),
+                base_url: "not a url".into()
+            }
+        );
+        assert!(matches!(
+            &errors[2],
+            GitHostingProviderConfigError::Invalid { name, .. } if name == "Rules"
+        ));
+        assert_eq!(
+            errors[3],
+            GitHostingProviderConfigError::DuplicateName {
+                name: "Corp GitLab".into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_merge_git_hosting_provider_configs() {
+        let config = |name: &str, base_url: &str| {
+            test_config(GitHostingProviderKind::Github, name, base_url)
+        };
+        let global = vec![
+            config("Global GitHub", "https://github.corp"),
+            config("Other GitHub", "https://github.other.corp"),
+            config("Shared GitHub", "https://github.shared.corp"),
+            GitHostingProviderConfig {
+                api_path_prefix: Some("/github".into()),
+                ..config("Prefixed GitHub", "https://github.corp")
+            },
+        ];
+        let local = vec![
+            config("Project GitHub", "https://GitHub.corp/"),
+            GitHostingProviderConfig {
+                provider: GitHostingProviderKind::Gitlab,
+                ..config("Project GitLab", "https://github.corp")
+            },
+            // A project config replaces a global one of the same name, even for another host.
+            config("Shared GitHub", "https://github.project.corp"),
+        ];
+
+        let (providers, errors) = build_git_hosting_providers(
+            merge_git_hosting_provider_configs(global, [local]),
+            &HashMap::default(),
+        );
+        assert_eq!(errors, []);
+        let registry = GitHostingProviderRegistry::new();
+        registry.set_setting_providers(providers);
+        let registered = |registry: &GitHostingProviderRegistry| {
+            registry
+                .list_hosting_providers()
+                .iter()
+                .map(|provider| (provider.name(), provider.base_url().to_string()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            registered(&registry),
+            [
+                (
+                    "Project GitHub".to_string(),
+                    "https://github.corp/".to_string()
+                ),
+                (
+                    "Other GitHub".to_string(),
+                    "https://github.other.corp/".to_string()
+                ),
+                (
+                    "Shared GitHub".to_string(),
+                    "https://github.project.corp/".to_string()
+                ),
+                (
+                    "Prefixed GitHub".to_string(),
+                    "https://github.corp/github/".to_string()
+                ),
+                (
+                    "Project GitLab".to_string(),
+                    "https://github.corp/".to_string()
+                ),
+            ]
+        );
+
+        // Within one settings file, the first config of a name is used.
+        let (providers, errors) = build_git_hosting_providers(
+            merge_git_hosting_provider_configs(
+                vec![
+                    config("Corp GitHub", "https://github.corp"),
+                    config("Corp GitHub", "https://github.other.corp"),
+                ],
+                [],
+            ),
+            &HashMap::default(),
+        );
+        assert_eq!(
+            errors,
+            [GitHostingProviderConfigError::DuplicateName {
+                name: "Corp GitHub".into()
+            }]
+        );
+        registry.set_setting_providers(providers);
+        assert_eq!(
+            registered(&registry),
+            [(
+                "Corp GitHub".to_string(),
+                "https://github.corp/".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_project_configs_cannot_set_global_only_fields() {
+        let config = |name: &str, base_url: &str| GitHostingProviderConfig {
+            url_shortener_url: Some("https://go.github.corp/api".into()),
+            accept_invalid_certs: true,
+            ca_certificate_path: Some("/etc/ssl/certs/corp-ca.pem".into()),
+            credential_key: Some("https://github.corp".into()),
+            ..test_config(GitHostingProviderKind::Github, name, base_url)
+        };
+        let global = vec![config("Corp GitHub", "https://github.corp")];
+        let local = vec![
+            // Replaces the global config for the same provider, keeping its global-only fields.
+            test_config(
+                GitHostingProviderKind::Github,
+                "Project GitHub",
+                "https://github.corp",
+            ),
+            config("Evil GitHub", "https://github.evil.com"),
+        ];
+
+        let configs = merge_git_hosting_provider_configs(global, [local]);
+        let global_only_fields = configs
+            .iter()
+            .map(|config| {
+                (
+                    config.name.as_str(),
+                    config.url_shortener_url.as_deref(),
+                    config.accept_invalid_certs,
+                    config.ca_certificate_path.as_deref(),
+                    config.credential_key.as_deref(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            global_only_fields,
+            [
+                (
+                    "Project GitHub",
+                    Some("https://go.github.corp/api"),
+                    true,
+                    Some(Path::new("/etc/ssl/certs/corp-ca.pem")),
+                    Some("https://github.corp"),
+                ),
+                ("Evil GitHub", None, false, None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_access_token_requires_api_on_provider_host() {
+        let config = |api_base_url: Option<&str>| GitHostingProviderConfig {
+            api_base_url: api_base_url.map(Into::into),
+            credential_key: Some("https://code.corp.big.com".into()),
+            ..test_config(
+                GitHostingProviderKind::Github,
+                "BigCorp GitHub",
+                "https://code.corp.big.com",
+            )
+        };
+        let access_token = || Some("secret-token".to_string());
+
+        assert!(
+            config(None)
+                .build_provider_with_access_token(access_token())
+                .is_ok()
+        );
+        assert!(
+            config(Some("https://api.code.corp.big.com"))
+                .build_provider_with_access_token(access_token())
+                .is_ok()
+        );
+        let error = config(Some("https://api.evil.com"))
+            .build_provider_with_access_token(access_token())
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "refusing to send the access token to https://api.evil.com/, \
+            which is not on the host of the base URL"
+        );
+        // Without a token, the API may be on any host.
+        assert!(
+            config(Some("https://api.evil.com"))
+                .build_provider()
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_build_external_app_url() {
+        let github_desktop = GitExternalAppConfig {
+            name: "GitHub Desktop".into(),
+            url_template: "x-github-client://openRepo/{repository_url}".into(),
+            providers: vec![GitHostingProviderKind::Github],
+        };
+        let github_remote = GitRemote {
+            host: Arc::new(Github::public_instance()),
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+        assert_eq!(
+            github_desktop.build_url(&github_remote).unwrap().as_str(),
+            "x-github-client://openRepo/https%3A%2F%2Fgithub.com%2Fzed-industries%2Fzed"
+        );
+
+        let custom = GitExternalAppConfig {
+            name: "Custom".into(),
+            url_template: "custom-client://clone?owner={owner}&repo={repo}".into(),
+            providers: Vec::new(),
+        };
+        let gitlab_remote = GitRemote {
+            host: Arc::new(Gitlab::public_instance()),
+            owner: "zed-industries".into(),
+            repo: "zed&co".into(),
+        };
+        assert_eq!(
+            custom.build_url(&gitlab_remote).unwrap().as_str(),
+            "custom-client://clone?owner=zed-industries&repo=zed%26co"
+        );
+
+        assert!(github_desktop.supports(&github_remote));
+        assert!(!github_desktop.supports(&gitlab_remote));
+        assert!(custom.supports(&github_remote));
+        assert!(custom.supports(&gitlab_remote));
+    }
+
+    #[test]
+    fn test_validate_git_hosting_provider_config() {
+        let config = |base_url: &str| {
+            test_config(GitHostingProviderKind::Github, "BigCorp GitHub", base_url)
+        };
+
+        assert_eq!(
+            config("https://code.corp.big.com")
+                .validate()
+                .unwrap()
+                .as_str(),
+            "https://code.corp.big.com/owner/repo/blob/0000000000000000000000000000000000000000/src/main.rs#L10"
+        );
+        assert!(config("code.corp.big.com").validate().is_err());
+        assert!(config("ftp://code.corp.big.com").validate().is_err());
+
+        let invalid_api_base_url = GitHostingProviderConfig {
+            api_base_url: Some("not a url".into()),
+            ..config("https://code.corp.big.com")
+        };
+        assert_eq!(
+            invalid_api_base_url
+                .build_provider()
+                .unwrap_err()
+                .to_string(),
+            "invalid API base URL \"not a url\""
+        );
+    }
+
+    #[test]
+    fn test_provider_path_prefix() {
+        let config = |base_url: &str, api_path_prefix: &str| GitHostingProviderConfig {
+            api_path_prefix: Some(api_path_prefix.into()),
+            ..test_config(GitHostingProviderKind::Gitlab, "Corp GitLab", base_url)
+        };
+
+        for (base_url, api_path_prefix) in [
+            ("https://devtools.corp", "/gitlab"),
+            ("https://devtools.corp", "gitlab/"),
+            ("https://devtools.corp/gitlab/", "/gitlab"),
+        ] {
+            let provider = config(base_url, api_path_prefix).build_provider().unwrap();
+            assert_eq!(
+                provider.base_url().as_str(),
+                "https://devtools.corp/gitlab/"
+            );
+        }
+        assert_eq!(
+            config("https://devtools.corp", "/gitlab")
+                .validate()
+                .unwrap()
+                .as_str(),
+            "https://devtools.corp/gitlab/owner/repo/-/blob/0000000000000000000000000000000000000000/src/main.rs#L10"
+        );
+
+        assert!(
+            config("https://devtools.corp/forge", "/gitlab")
+                .build_provider()
+                .is_err()
+        );
+        assert!(
+            config("https://devtools.corp", "/")
+                .build_provider()
+                .is_err()
+        );
+        assert!(
+            config("https://devtools.corp", "a//b")
+                .build_provider()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_provider_public_url() {
+        let config = |provider: GitHostingProviderKind| GitHostingProviderConfig {
+            public_url: Some("https://code.big-corp.com/forge".into()),
+            ..test_config(provider, "Corp Forge", "https://git.corp.internal")
+        };
+
+        for (kind, expected_permalink) in [
+            (
+                GitHostingProviderKind::Github,
+                "https://code.big-corp.com/forge/owner/repo/blob/0000000000000000000000000000000000000000/src/main.rs#L10",
+            ),
+            (
+                GitHostingProviderKind::Gitlab,
+                "https://code.big-corp.com/forge/owner/repo/-/blob/0000000000000000000000000000000000000000/src/main.rs#L10",
+            ),
+            (
+                GitHostingProviderKind::Bitbucket,
+                "https://code.big-corp.com/forge/owner/repo/src/0000000000000000000000000000000000000000/src/main.rs#lines-10",
+            ),
+            (
+                GitHostingProviderKind::BitbucketServer,
+                "https://code.big-corp.com/forge/projects/owner/repos/repo/browse/src/main.rs?at=0000000000000000000000000000000000000000#10",
+            ),
+            (
+                GitHostingProviderKind::Forgejo,
+                "https://code.big-corp.com/forge/owner/repo/src/commit/0000000000000000000000000000000000000000/src/main.rs#L10",
+            ),
+        ] {
+            let config = config(kind);
+            assert_eq!(config.validate().unwrap().as_str(), expected_permalink);
+
+            // Remotes are recognized by the host of the base URL, not of the public URL.
+            let provider = config.build_provider().unwrap();
+            assert_eq!(
+                provider.base_url().as_str(),
+                "https://code.big-corp.com/forge/"
+            );
+            assert!(
+                provider
+                    .parse_remote_url("git@git.corp.internal:owner/repo.git")
+                    .is_some()
+            );
+            assert!(
+                provider
+                    .parse_remote_url("git@code.big-corp.com:owner/repo.git")
+                    .is_none()
+            );
+        }
+
+        let invalid = GitHostingProviderConfig {
+            public_url: Some("not a url".into()),
+            ..config(GitHostingProviderKind::Github)
+        };
+        assert!(invalid.build_provider().is_err());
+
+        // The reachability check requests the URL that links point to.
+        let requested_url = Arc::new(Mutex::new(None));
+        let http_client = FakeHttpClient::create({
+            let requested_url = requested_url.clone();
+            move |request| {
+                *requested_url.lock().unwrap() = Some(request.uri().to_string());
+                async move {
+                    Ok(Response::builder()
+                        .status(200)
+                        .body(Default::default())
+                        .unwrap())
+                }
+            }
+        });
+        futures::executor::block_on(
+            config(GitHostingProviderKind::Github).check_reachability(http_client),
+        )
+        .unwrap();
+        assert_eq!(
+            requested_url.lock().unwrap().as_deref(),
+            Some("https://code.big-corp.com/forge/")
+        );
+    }
+
+    #[test]
+    fn test_provider_remote_host_aliases() {
+        let config: GitHostingProviderConfig = serde_json::from_str(
+            r#"{
+                "provider": "github",
+                "base_url": "https://code.corp.big.com",
+                "name": "BigCorp GitHub",
+                "remote_host_aliases": ["gh-work", "code-ssh.corp.big.com"]
+            }"#,
+        )
+        .unwrap();
+        let (providers, errors) = build_git_hosting_providers([config], &HashMap::default());
+        assert!(errors.is_empty());
+        let registry = Arc::new(GitHostingProviderRegistry::new());
+        registry.set_setting_providers(providers);
+
+        for remote_url in [
+            "git@code.corp.big.com:owner/repo.git",
+            "git@gh-work:owner/repo.git",
+            "ssh://git@code-ssh.corp.big.com/owner/repo.git",
+        ] {
+            let (provider, remote) =
+                git::parse_git_remote_url(registry.clone(), remote_url).unwrap();
+            assert_eq!(provider.name(), "BigCorp GitHub", "{remote_url}");
+
+            let permalink = provider.build_permalink(
+                remote,
+                BuildPermalinkParams {
+                    sha: "0000000000000000000000000000000000000000",
+                    path: "src/main.rs",
+                    selection: Some(9..9),
+                },
+            );
+            assert_eq!(
+                permalink.as_str(),
+                "https://code.corp.big.com/owner/repo/blob/0000000000000000000000000000000000000000/src/main.rs#L10"
+            );
+        }
+
+        assert!(git::parse_git_remote_url(registry, "git@gh-personal:owner/repo.git").is_none());
+    }
+
+    #[test]
+    fn test_provider_certificate_options() {
+        let config: GitHostingProviderConfig = serde_json::from_str(
+            r#"{
+                "provider": "github",
+                "base_url": "https://code.corp.big.com",
+                "name": "BigCorp GitHub"
+            }"#,
+        )
+        .unwrap();
+        assert!(!config.accept_invalid_certs);
+        assert_eq!(config.ca_certificate_path, None);
+        assert!(config.build_http_client().is_none());
+
+        // The CA bundle isn't read until a request is sent.
+        let config = GitHostingProviderConfig {
+            ca_certificate_path: Some("/nonexistent/corp-ca.pem".into()),
+            ..config
+        };
+        assert!(config.build_provider().is_ok());
+        let http_client = config.build_http_client().unwrap();
+        let error = futures::executor::block_on(http_client.get(
+            "https://code.corp.big.com",
+            AsyncBody::default(),
+            false,
+        ))
+        .unwrap_err();
+        assert!(
+            error.to_string().contains("/nonexistent/corp-ca.pem"),
+            "unexpected error: {error:?}"
+        );
+    }
+
+    #[test]
+    fn test_credential_key_requires_github() {
+        let config: GitHostingProviderConfig = serde_json::from_str(
+            r#"{
+                "provider": "gitlab",
+                "base_url": "https://gitlab.corp.big.com",
+                "name": "BigCorp GitLab",
+                "credential_key": "https://gitlab.corp.big.com"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.build_provider().unwrap_err().to_string(),
+            "credential_key is only supported by github providers"
+        );
+    }
+
+    #[test]
+    fn test_email_to_username() {
+        let email_to_username = EmailToUsername::new(&[
+            EmailToUsernameRule {
+                email: "jane\\.doe@corp\\.big\\.com".into(),
+                username: "jdoe".into(),
+            },
+            EmailToUsernameRule {
+                email: "(.+)@corp\\.big\\.com".into(),
+                username: "$1".into(),
+            },
+        ])
+        .unwrap();
+
+        let username_for = |email| email_to_username.username_for_email(email);
+        assert_eq!(
+            username_for("jane.doe@corp.big.com").as_deref(),
+            Some("jdoe")
+        );
+        assert_eq!(username_for("john@corp.big.com").as_deref(), Some("john"));
+        assert_eq!(username_for("john@corp.big.com.evil.com"), None);
+        assert_eq!(username_for("john@example.com"), None);
+
+        let invalid = EmailToUsernameRule {
+            email: "(".into(),
+            username: "$1".into(),
+        };
+        assert!(EmailToUsername::new(&[invalid]).is_err());
+    }
+
+    #[test]
+    fn test_author_url_from_email() {
+        let email_to_username = EmailToUsername::new(&[EmailToUsernameRule {
+            email: "(.+)@corp\\.big\\.com".into(),
+            username: "$1".into(),
+        }])
+        .unwrap();
+        let github = Github::new(
+            "BigCorp GitHub",
+            "https://code.corp.big.com".parse().unwrap(),
+        )
+        .with_email_to_username(email_to_username);
+        let remote = GitRemote {
+            host: Arc::new(github),
+            owner: "zed-industries".into(),
+            repo: "zed".into(),
+        };
+
+        assert!(remote.host_supports_avatars());
+        assert_eq!(
+            remote.author_url("jane@corp.big.com").unwrap().as_str(),
+            "https://code.corp.big.com/jane"
+        );
+        assert_eq!(remote.author_url("jane@example.com"), None);
+    }
+
+    struct FakeCredentialsProvider(HashMap<String, Vec<u8>>);
+
+    impl CredentialsProvider for FakeCredentialsProvider {
+        fn read_credentials<'a>(
+            &'a self,
+            url: &'a str,
+            _: &'a AsyncApp,
+        ) -> Pin<Box<dyn Future<Output = Result<Option<(String, Vec<u8>)>>> + 'a>> {
+            let credentials = self
+                .0
+                .get(url)
+                .map(|token| ("token".to_string(), token.clone()));
+            async move { Ok(credentials) }.boxed_local()
+        }
+
+        fn write_credentials<'a>(
+            &'a self,
+            _: &'a str,
+            _: &'a str,
+            _: &'a [u8],
+            _: &'a AsyncApp,
+        ) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>> {
+            async move { Err(anyhow::anyhow!("the fake credential store is read-only")) }
+                .boxed_local()
+        }
+
+        fn delete_credentials<'a>(
+            &'a self,
+            _: &'a str,
+            _: &'a AsyncApp,
+        ) -> Pin<Box<dyn Future<Output = Result<()>> + 'a>> {
+            async move { Err(anyhow::anyhow!("the fake credential store is read-only")) }
+                .boxed_local()
+        }
+    }
+
+    #[gpui::test]
+    async fn test_provider_access_token_from_credential_store(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            GitHostingProviderRegistry::default_global(cx);
+            GitHostingProviderSettings::register(cx);
+
+            let credentials_provider = FakeCredentialsProvider(HashMap::from_iter([(
+                "https://api.code.corp.big.com".to_string(),
+                b"secret-token".to_vec(),
+            )]));
+            init_git_hosting_provider_settings(Arc::new(credentials_provider), cx);
+            SettingsStore::update_global(cx, |store, cx| {
+                store
+                    .set_user_settings(
+                        r#"{
+                            "git_hosting_providers": [
+                                {
+                                    "provider": "github",
+                                    "base_url": "https://code.corp.big.com",
+                                    "name": "BigCorp GitHub",
+                                    "credential_key": "https://api.code.corp.big.com"
+                                }
+                            ]
+                        }"#,
+                        cx,
+                    )
+                    .unwrap();
+            });
+        });
+        cx.run_until_parked();
+
+        let provider = cx.update(|cx| {
+            GitHostingProviderRegistry::global(cx)
+                .list_hosting_providers()
+                .into_iter()
+                .find(|provider| provider.name() == "BigCorp GitHub")
+                .unwrap()
+        });
+        let authorization = Arc::new(Mutex::new(None));
+        let http_client = FakeHttpClient::create({
+            let authorization = authorization.clone();
+            move |request| {
+                *authorization.lock().unwrap() = request
+                    .headers()
+                    .get("Authorization")
+                    .and_then(|value| value.to_str().ok())
+                    .map(ToString::to_string);
+                async move {
+                    Ok(Response::builder()
+                        .status(404)
+                        .body(Default::default())
+                        .unwrap())
+                }
+            }
+        });
+        provider
+            .commit_author_avatar_url("owner", "repo", "f00b4r".into(), http_client)
+            .await
+            .ok();
+        assert_eq!(
+            authorization.lock().unwrap().as_deref(),
+            Some("Bearer secret-token")
+        );
+    }
+}
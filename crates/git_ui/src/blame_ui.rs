@@ -12,6 +12,7 @@ use gpui::{
     prelude::*,
 };
 use markdown::{Markdown, MarkdownElement};
+use notifications::status_toast::{StatusToast, ToastIcon};
 use project::{git_store::Repository, project_settings::ProjectSettings};
 use settings::Settings as _;
 use theme::ThemeSettings;
@@ -69,10 +70,14 @@ impl BlameRenderer for GitBlameRenderer {
                 .on_mouse_down(MouseButton::Right, {
                     let blame_entry = blame_entry.clone();
                     let details = details.clone();
+                    let repository = repository.clone();
+                    let workspace = workspace.clone();
                     move |event, window, cx| {
                         deploy_blame_entry_context_menu(
                             &blame_entry,
                             details.as_ref(),
+                            repository.clone(),
+                            workspace.clone(),
                             editor.clone(),
                             event.position,
                             window,
@@ -387,17 +392,48 @@ impl BlameRenderer for GitBlameRenderer {
 fn deploy_blame_entry_context_menu(
     blame_entry: &BlameEntry,
     details: Option<&ParsedCommitMessage>,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
     editor: Entity<Editor>,
     position: gpui::Point<Pixels>,
     window: &mut Window,
     cx: &mut App,
 ) {
+    // Link to the file as it appears in the pull request that introduced the commit.
+    let pull_request_file_url = details.and_then(|details| {
+        let pull_request = details.pull_request.as_ref()?;
+        let url = details
+            .remote
+            .as_ref()?
+            .pull_request_file_review_url(pull_request.number, &blame_entry.filename)?;
+        Some((pull_request.number, url))
+    });
+    // Link to the blame of the entry's lines as they were at the entry's commit.
+    let blame_url = details.and_then(|details| {
+        let start_line = blame_entry.original_line_number.saturating_sub(1);
+        let end_line = start_line + blame_entry.range.len().saturating_sub(1) as u32;
+        details.remote.as_ref()?.blame_url_at(
+            &blame_entry.sha.to_string(),
+            &blame_entry.filename,
+            Some(start_line..end_line),
+        )
+    });
     let context_menu = ContextMenu::build(window, cx, move |menu, _, _| {
         let sha = format!("{}", blame_entry.sha);
+        let blame_entry = blame_entry.clone();
         menu.on_blur_subscription(Subscription::new(|| {}))
             .entry("Copy commit SHA", None, move |_, cx| {
                 cx.write_to_clipboard(ClipboardItem::new_string(sha.clone()));
             })
+            .entry("Copy permalink at this commit", None, move |window, cx| {
+                copy_permalink_at_commit(
+                    &blame_entry,
+                    repository.clone(),
+                    workspace.clone(),
+                    window,
+                    cx,
+                );
+            })
             .when_some(
                 details.and_then(|details| details.permalink.clone()),
                 |this, url| {
@@ -406,6 +442,20 @@ fn deploy_blame_entry_context_menu(
                     })
                 },
             )
+            .when_some(blame_url, |this, url| {
+                this.entry("Open blame at this commit", None, move |_, cx| {
+                    cx.open_url(url.as_str())
+                })
+            })
+            .when_some(pull_request_file_url, |this, (number, url)| {
+                this.entry(
+                    format!("Copy link to file in pull request #{number}"),
+                    None,
+                    move |_, cx| {
+                        cx.write_to_clipboard(ClipboardItem::new_string(url.to_string()));
+                    },
+                )
+            })
     });
 
     editor.update(cx, move |editor, cx| {
@@ -414,6 +464,54 @@ fn deploy_blame_entry_context_menu(
     });
 }
 
+/// Copies a permalink to the lines of the blame entry, pinned to the entry's commit
+/// rather than HEAD, and warns if the commit has not been pushed to any remote.
+fn copy_permalink_at_commit(
+    blame_entry: &BlameEntry,
+    repository: Entity<Repository>,
+    workspace: WeakEntity<Workspace>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let sha = blame_entry.sha.to_string();
+    let short_sha = blame_entry.sha.display_short();
+    let path = blame_entry.filename.clone();
+    let start_line = blame_entry.original_line_number.saturating_sub(1);
+    let end_line = start_line + blame_entry.range.len().saturating_sub(1) as u32;
+
+    let remote = repository.update(cx, |repository, cx| repository.hosting_remote(cx));
+    let remote_branches = repository.update(cx, |repository, _| {
+        repository.remote_branches_containing(sha.clone())
+    });
+    window
+        .spawn(cx, async move |cx| {
+            let remote = remote.await??;
+            let permalink =
+                remote.build_permalink_at_commit(&sha, &path, Some(start_line..end_line));
+            cx.update(|_, cx| {
+                cx.write_to_clipboard(ClipboardItem::new_string(permalink.to_string()))
+            })?;
+
+            if remote_branches
+                .await?
+                .is_ok_and(|branches| branches.is_empty())
+            {
+                workspace.update(cx, |workspace, cx| {
+                    let toast = StatusToast::new(
+                        format!("Commit {short_sha} is not on any remote branch"),
+                        cx,
+                        |this, _| {
+                            this.icon(ToastIcon::new(IconName::Warning).color(Color::Warning))
+                        },
+                    );
+                    workspace.toggle_status_toast(toast, cx);
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+}
+
 fn blame_entry_relative_timestamp(blame_entry: &BlameEntry) -> String {
     match blame_entry.author_offset_date_time() {
         Ok(timestamp) => {
@@ -46,7 +46,11 @@ impl<'a> CommitAvatar<'a> {
             .and_then(|details| details.remote.clone())
             .filter(|remote| remote.host_supports_avatars())?;
 
-        let avatar_url = CommitAvatarAsset::new(remote, self.commit.sha.clone());
+        let avatar_url = CommitAvatarAsset::new(
+            remote,
+            self.commit.sha.clone(),
+            self.commit.author_email.clone(),
+        );
 
         let element = match window.use_asset::<CommitAvatarAsset>(&avatar_url, cx) {
             // Loading or no avatar found
@@ -64,6 +68,7 @@ impl<'a> CommitAvatar<'a> {
 #[derive(Clone, Debug)]
 struct CommitAvatarAsset {
     sha: SharedString,
+    author_email: SharedString,
     remote: GitRemote,
 }
 
@@ -75,8 +80,12 @@ impl Hash for CommitAvatarAsset {
 }
 
 impl CommitAvatarAsset {
-    fn new(remote: GitRemote, sha: SharedString) -> Self {
-        Self { remote, sha }
+    fn new(remote: GitRemote, sha: SharedString, author_email: SharedString) -> Self {
+        Self {
+            remote,
+            sha,
+            author_email,
+        }
     }
 }
 
@@ -93,7 +102,7 @@ impl Asset for CommitAvatarAsset {
         async move {
             source
                 .remote
-                .avatar_url(source.sha, client)
+                .avatar_url(source.sha, &source.author_email, client)
                 .await
                 .map(|url| SharedString::from(url.to_string()))
         }
@@ -172,6 +181,12 @@ impl Render for CommitTooltip {
         let avatar = CommitAvatar::new(&self.commit).render(window, cx);
 
         let author = self.commit.author_name.clone();
+        let author_url = self
+            .commit
+            .message
+            .as_ref()
+            .and_then(|details| details.remote.as_ref())
+            .and_then(|remote| remote.author_url(&self.commit.author_email));
 
         let author_email = self.commit.author_email.clone();
 
@@ -247,7 +262,16 @@ impl Render for CommitTooltip {
                                 .overflow_x_hidden()
                                 .flex_wrap()
                                 .children(avatar)
-                                .child(author)
+                                .child(match author_url {
+                                    Some(author_url) => Button::new("commit-author-button", author)
+                                        .style(ButtonStyle::Subtle)
+                                        .on_click(move |_, _, cx| {
+                                            cx.stop_propagation();
+                                            cx.open_url(author_url.as_str())
+                                        })
+                                        .into_any_element(),
+                                    None => author.into_any_element(),
+                                })
                                 .when(!author_email.is_empty(), |this| {
                                     this.child(
                                         div()
@@ -0,0 +1,132 @@
+use editor::{Editor, EditorEvent, actions::SelectAll};
+use gpui::{DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Subscription};
+use regex::Regex;
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::project_diff::ProjectDiff;
+
+/// Prompts for a pattern that the project diff's hunks are filtered by, updating the diff as
+/// the pattern is typed.
+pub(crate) struct DiffFilterModal {
+    pattern_editor: Entity<Editor>,
+    project_diff: Entity<ProjectDiff>,
+    error: Option<SharedString>,
+    _subscription: Subscription,
+}
+
+impl EventEmitter<DismissEvent> for DiffFilterModal {}
+impl ModalView for DiffFilterModal {}
+impl Focusable for DiffFilterModal {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.pattern_editor.focus_handle(cx)
+    }
+}
+
+impl DiffFilterModal {
+    pub fn new(
+        project_diff: Entity<ProjectDiff>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let current_pattern = project_diff
+            .read(cx)
+            .hunk_filter()
+            .map(|filter| filter.as_str().to_string());
+        let pattern_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Filter changed lines by regex…", cx);
+            if let Some(pattern) = current_pattern {
+                editor.set_text(pattern, window, cx);
+                editor.select_all(&SelectAll, window, cx);
+            }
+            editor
+        });
+        let subscription = cx.subscribe_in(&pattern_editor, window, Self::on_editor_event);
+
+        Self {
+            pattern_editor,
+            project_diff,
+            error: None,
+            _subscription: subscription,
+        }
+    }
+
+    fn on_editor_event(
+        &mut self,
+        _: &Entity<Editor>,
+        event: &EditorEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            EditorEvent::Blurred => cx.emit(DismissEvent),
+            EditorEvent::BufferEdited { .. } => self.update_filter(cx),
+            _ => {}
+        }
+    }
+
+    fn update_filter(&mut self, cx: &mut Context<Self>) {
+        let pattern = self.pattern_editor.read(cx).text(cx);
+        let filter = if pattern.is_empty() {
+            None
+        } else {
+            match Regex::new(&pattern) {
+                Ok(filter) => Some(filter),
+                Err(error) => {
+                    // Keep showing the last valid filter while the pattern is being typed.
+                    let message = error.to_string();
+                    let message = message
+                        .lines()
+                        .last()
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string();
+                    self.error = Some(message.into());
+                    cx.notify();
+                    return;
+                }
+            }
+        };
+
+        self.error = None;
+        self.project_diff.update(cx, |project_diff, cx| {
+            project_diff.set_hunk_filter(filter, cx);
+        });
+        cx.notify();
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        self.project_diff.focus_handle(cx).focus(window);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for DiffFilterModal {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let help = match &self.error {
+            Some(error) => Label::new(error.clone()).color(Color::Error),
+            None => Label::new("Leave empty to show all changes").color(Color::Muted),
+        };
+
+        v_flex()
+            .w(rems(34.))
+            .elevation_2(cx)
+            .key_context("DiffFilterModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.pattern_editor.clone()),
+            )
+            .child(h_flex().px_2().py_1().gap_1().child(help))
+    }
+}
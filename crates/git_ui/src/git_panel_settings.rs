@@ -36,6 +36,20 @@ pub enum StatusStyle {
     LabelColor,
 }
 
+/// How hunks that only change whitespace are shown in the project diff.
+#[derive(Default, Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WhitespaceHunks {
+    /// Show them like any other hunk.
+    #[default]
+    Show,
+    /// Leave them out of the diff.
+    Hide,
+    /// Leave them out of the diff, with a toolbar button that counts them and shows them
+    /// again when clicked.
+    Collapse,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
 pub struct GitPanelSettingsContent {
     /// Whether to show the panel button in the status bar.
@@ -70,6 +84,18 @@ pub struct GitPanelSettingsContent {
     ///
     /// Default: false
     pub sort_by_path: Option<bool>,
+
+    /// Whether the project diff opens locked, so that its contents
+    /// can't be edited until `git::ToggleDiffEditable` is run.
+    /// Hunks can still be staged and unstaged while locked.
+    ///
+    /// Default: false
+    pub diff_read_only: Option<bool>,
+
+    /// How hunks that only change whitespace are shown in the project diff.
+    ///
+    /// Default: show
+    pub whitespace_hunks: Option<WhitespaceHunks>,
 }
 
 #[derive(Deserialize, Debug, Clone, PartialEq)]
@@ -81,6 +107,8 @@ pub struct GitPanelSettings {
     pub scrollbar: ScrollbarSettings,
     pub fallback_branch_name: String,
     pub sort_by_path: bool,
+    pub diff_read_only: bool,
+    pub whitespace_hunks: WhitespaceHunks,
 }
 
 impl Settings for GitPanelSettings {
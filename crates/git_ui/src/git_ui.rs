@@ -1,20 +1,35 @@
-use std::any::Any;
+use std::{any::Any, pin::pin, time::Duration};
 
 use ::settings::Settings;
 use command_palette_hooks::CommandPaletteFilter;
 use commit_modal::CommitModal;
 use editor::Editor;
+use futures::{FutureExt as _, select_biased};
 mod blame_ui;
 use git::{
+    GitRemote,
     repository::{Branch, Upstream, UpstreamTracking, UpstreamTrackingStatus},
     status::{FileStatus, StatusCode, UnmergedStatus, UnmergedStatusCode},
 };
+use git_hosting_providers::{
+    GitHostingProviderConfig, GitHostingProviderConfigError, GitHostingProviderSettings,
+    GitHostingProviderSettingsErrors,
+};
 use git_panel_settings::GitPanelSettings;
-use gpui::{Action, App, Context, FocusHandle, Window, actions};
+use gpui::{Action, App, Context, DismissEvent, FocusHandle, PromptLevel, Window, actions};
+use notifications::status_toast::{StatusToast, ToastIcon};
 use onboarding::GitOnboardingModal;
+use project::ProjectItem as _;
 use project_diff::ProjectDiff;
 use ui::prelude::*;
-use workspace::Workspace;
+use url::Url;
+use workspace::{
+    Workspace,
+    notifications::{
+        NotificationId, dismiss_app_notification, show_app_notification,
+        simple_message_notification::MessageNotification,
+    },
+};
 
 mod askpass_modal;
 pub mod branch_picker;
@@ -22,6 +37,7 @@ mod commit_modal;
 pub mod commit_tooltip;
 mod commit_view;
 mod conflict_view;
+mod diff_filter_modal;
 pub mod diff_view;
 pub mod git_panel;
 mod git_panel_settings;
@@ -33,6 +49,8 @@ pub mod repository_selector;
 
 actions!(git, [ResetOnboarding]);
 
+const REACHABILITY_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub fn init(cx: &mut App) {
     GitPanelSettings::register(cx);
 
@@ -43,6 +61,21 @@ pub fn init(cx: &mut App) {
     })
     .detach();
 
+    let mut shown_provider_errors = Vec::new();
+    let mut update_provider_errors = move |cx: &mut App| {
+        let Some(errors) = cx.try_global::<GitHostingProviderSettingsErrors>() else {
+            return;
+        };
+        // The errors are recomputed whenever any setting changes, so only notify about new ones.
+        if errors.0 != shown_provider_errors {
+            shown_provider_errors = errors.0.clone();
+            show_git_hosting_provider_errors(&shown_provider_errors, cx);
+        }
+    };
+    update_provider_errors(cx);
+    cx.observe_global::<GitHostingProviderSettingsErrors>(update_provider_errors)
+        .detach();
+
     cx.observe_new(|workspace: &mut Workspace, _, cx| {
         ProjectDiff::register(workspace, cx);
         CommitModal::register(workspace);
@@ -146,6 +179,44 @@ pub fn init(cx: &mut App) {
         workspace.register_action(|workspace, _: &git::OpenModifiedFiles, window, cx| {
             open_modified_files(workspace, window, cx);
         });
+        workspace.register_action(|workspace, _: &git::OpenInExternalApp, window, cx| {
+            open_in_external_app(workspace, window, cx);
+        });
+        workspace.register_action(
+            |workspace, _: &git::OpenSecurityAdvisoriesOnRemote, window, cx| {
+                open_remote_page(
+                    workspace,
+                    window,
+                    cx,
+                    |remote| remote.security_url(),
+                    "security advisories",
+                );
+            },
+        );
+        workspace.register_action(|workspace, _: &git::OpenDiscussionsOnRemote, window, cx| {
+            open_remote_page(
+                workspace,
+                window,
+                cx,
+                |remote| remote.discussions_url(),
+                "discussions",
+            );
+        });
+        workspace.register_action(|workspace, _: &git::OpenCiOverviewOnRemote, window, cx| {
+            open_remote_page(
+                workspace,
+                window,
+                cx,
+                |remote| remote.ci_overview_url(),
+                "CI overview",
+            );
+        });
+        workspace.register_action(|workspace, _: &git::CreateIssueFromComment, window, cx| {
+            create_issue_from_comment(workspace, window, cx);
+        });
+        workspace.register_action(|_, action: &git::ValidateGitHostingProviders, window, cx| {
+            validate_git_hosting_providers(action, window, cx);
+        });
     })
     .detach();
 }
@@ -178,6 +249,254 @@ fn open_modified_files(
     }
 }
 
+fn open_in_external_app(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(repo) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+    let apps = GitHostingProviderSettings::get_global(cx)
+        .git_external_apps
+        .clone();
+    if apps.is_empty() {
+        return;
+    }
+
+    let remote = repo.update(cx, |repo, cx| repo.hosting_remote(cx));
+    let workspace = workspace.weak_handle();
+    window
+        .spawn(cx, async move |cx| {
+            let remote = remote.await??;
+            let apps = apps
+                .into_iter()
+                .filter(|app| app.supports(&remote))
+                .collect::<Vec<_>>();
+            anyhow::ensure!(
+                !apps.is_empty(),
+                "no external app can open repositories on {}",
+                remote.host.name()
+            );
+            let options = apps.iter().map(|app| app.name.clone().into()).collect();
+            let selection = cx
+                .update(|window, cx| {
+                    picker_prompt::prompt("Open in External App", options, workspace, window, cx)
+                })?
+                .await;
+            let Some(app) = selection.and_then(|ix| apps.get(ix)) else {
+                return Ok(());
+            };
+            let url = app.build_url(&remote)?;
+            cx.update(|_, cx| cx.open_url(url.as_str()))?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+}
+
+/// Opens a page of the active repository on its hosting provider, or shows a toast when the
+/// provider has no such page.
+fn open_remote_page(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+    page_url: impl FnOnce(&GitRemote) -> Option<Url> + 'static,
+    page_name: &'static str,
+) {
+    let Some(repo) = workspace.project().read(cx).active_repository(cx) else {
+        return;
+    };
+
+    let remote = repo.update(cx, |repo, cx| repo.hosting_remote(cx));
+    let workspace = workspace.weak_handle();
+    window
+        .spawn(cx, async move |cx| {
+            let remote = remote.await??;
+            if let Some(url) = page_url(&remote) {
+                cx.update(|_, cx| cx.open_url(url.as_str()))?;
+                return anyhow::Ok(());
+            }
+
+            workspace.update(cx, |workspace, cx| {
+                let toast = StatusToast::new(
+                    format!("{} has no {page_name} page", remote.host.name()),
+                    cx,
+                    |this, _| this.icon(ToastIcon::new(IconName::Warning).color(Color::Warning)),
+                );
+                workspace.toggle_status_toast(toast, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+}
+
+/// Opens the hosting provider's new issue form, prefilled with the comment at the cursor of
+/// the active editor and a permalink to it.
+fn create_issue_from_comment(
+    workspace: &mut Workspace,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let cursor = editor.read(cx).selections.newest_anchor().head();
+    let project_path = editor
+        .read(cx)
+        .buffer()
+        .read(cx)
+        .point_to_buffer_offset(cursor, cx)
+        .and_then(|(buffer, _)| buffer.read(cx).project_path(cx));
+    let Some((repo, _)) = project_path.and_then(|project_path| {
+        workspace
+            .project()
+            .read(cx)
+            .git_store()
+            .read(cx)
+            .repository_and_path_for_project_path(&project_path, cx)
+    }) else {
+        return;
+    };
+    let Some(comment) = editor.read(cx).comment_at_cursor(cx) else {
+        let toast = StatusToast::new("No comment at the cursor", cx, |this, _| {
+            this.icon(ToastIcon::new(IconName::Warning).color(Color::Warning))
+        });
+        workspace.toggle_status_toast(toast, cx);
+        return;
+    };
+
+    let permalink = editor.update(cx, |editor, cx| editor.get_permalink_to_line(cx));
+    let remote = repo.update(cx, |repo, cx| repo.hosting_remote(cx));
+    let workspace = workspace.weak_handle();
+    window
+        .spawn(cx, async move |cx| {
+            let remote = remote.await??;
+            let permalink = permalink.await?;
+            let title = comment.lines().next().unwrap_or_default();
+            let body = format!("{comment}\n\n{permalink}");
+            if let Some(url) = remote.new_issue_url(title, &body) {
+                cx.update(|_, cx| cx.open_url(url.as_str()))?;
+                return anyhow::Ok(());
+            }
+
+            workspace.update(cx, |workspace, cx| {
+                let toast = StatusToast::new(
+                    format!("{} has no issues", remote.host.name()),
+                    cx,
+                    |this, _| this.icon(ToastIcon::new(IconName::Warning).color(Color::Warning)),
+                );
+                workspace.toggle_status_toast(toast, cx);
+            })?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+}
+
+fn validate_git_hosting_providers(
+    action: &git::ValidateGitHostingProviders,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let local_configs = cx
+        .global::<::settings::SettingsStore>()
+        .get_all_locals::<GitHostingProviderSettings>()
+        .into_iter()
+        .flat_map(|(_, _, settings)| settings.git_hosting_providers.clone());
+    let configs: Vec<GitHostingProviderConfig> = GitHostingProviderSettings::get_global(cx)
+        .git_hosting_providers
+        .iter()
+        .cloned()
+        .chain(local_configs)
+        .collect();
+    let check_reachability = action.check_reachability;
+    let http_client = cx.http_client();
+    let executor = cx.background_executor().clone();
+
+    window
+        .spawn(cx, async move |cx| {
+            let mut broken_count = 0;
+            let mut report = Vec::new();
+            for config in &configs {
+                let mut result = config.validate();
+                if check_reachability && result.is_ok() {
+                    let mut timeout = executor.timer(REACHABILITY_CHECK_TIMEOUT).fuse();
+                    let mut reachable = pin!(config.check_reachability(http_client.clone()).fuse());
+                    select_biased! {
+                        reachable = reachable => {
+                            if let Err(error) = reachable {
+                                result = Err(error);
+                            }
+                        },
+                        _ = timeout => {
+                            result = Err(anyhow::anyhow!("timed out reaching {}", config.base_url));
+                        },
+                    }
+                }
+
+                report.push(match result {
+                    Ok(permalink) => format!("✓ {}: {permalink}", config.name),
+                    Err(error) => {
+                        broken_count += 1;
+                        format!("✗ {}: {error:#}", config.name)
+                    }
+                });
+            }
+
+            let message = if configs.is_empty() {
+                "No Git hosting providers are configured".to_string()
+            } else if broken_count == 0 {
+                "All Git hosting providers are valid".to_string()
+            } else {
+                format!(
+                    "{broken_count} of {} Git hosting providers would produce broken links",
+                    configs.len()
+                )
+            };
+            let level = if broken_count == 0 {
+                PromptLevel::Info
+            } else {
+                PromptLevel::Warning
+            };
+            let detail = report.join("\n");
+            let prompt = cx.update(|window, cx| {
+                window.prompt(level, &message, Some(detail.as_str()), &["Ok"], cx)
+            })?;
+            prompt.await.ok();
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+}
+
+/// Notifies about the configured Git hosting providers that were not registered, or
+/// dismisses the notification once there are none.
+fn show_git_hosting_provider_errors(errors: &[GitHostingProviderConfigError], cx: &mut App) {
+    struct GitHostingProviderErrorsNotification;
+    let id = NotificationId::unique::<GitHostingProviderErrorsNotification>();
+    if errors.is_empty() {
+        dismiss_app_notification(&id, cx);
+        return;
+    }
+
+    let details = errors
+        .iter()
+        .map(|error| format!("• {error}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let message = format!("Some Git hosting providers in your settings were ignored:\n{details}");
+    show_app_notification(id, cx, move |cx| {
+        let message = message.clone();
+        cx.new(|cx| {
+            MessageNotification::new(message, cx)
+                .primary_message("Open Settings File")
+                .primary_icon(IconName::Settings)
+                .primary_on_click(|window, cx| {
+                    window.dispatch_action(zed_actions::OpenSettings.boxed_clone(), cx);
+                    cx.emit(DismissEvent);
+                })
+        })
+    });
+}
+
 pub fn git_status_icon(status: FileStatus) -> impl IntoElement {
     GitStatusIcon::new(status)
 }
@@ -1,12 +1,13 @@
 use crate::{
     conflict_view::ConflictAddon,
+    diff_filter_modal::DiffFilterModal,
     git_panel::{GitPanel, GitPanelAddon, GitStatusEntry},
-    git_panel_settings::GitPanelSettings,
+    git_panel_settings::{GitPanelSettings, WhitespaceHunks},
     remote_button::{render_publish_button, render_push_button},
 };
 use anyhow::Result;
-use buffer_diff::{BufferDiff, DiffHunkSecondaryStatus};
-use collections::HashSet;
+use buffer_diff::{BufferDiff, DiffHunk, DiffHunkSecondaryStatus};
+use collections::{HashMap, HashSet};
 use editor::{
     Editor, EditorEvent,
     actions::{GoToHunk, GoToPreviousHunk},
@@ -22,17 +23,18 @@ use gpui::{
     Action, AnyElement, AnyView, App, AppContext as _, AsyncWindowContext, Entity, EventEmitter,
     FocusHandle, Focusable, Render, Subscription, Task, WeakEntity, actions,
 };
-use language::{Anchor, Buffer, Capability, OffsetRangeExt};
+use language::{Anchor, Buffer, BufferSnapshot, Capability, OffsetRangeExt};
 use multi_buffer::{MultiBuffer, PathKey};
 use project::{
     Project, ProjectPath,
     git_store::{GitStore, GitStoreEvent, RepositoryEvent},
 };
+use regex::Regex;
 use settings::{Settings, SettingsStore};
 use std::any::{Any, TypeId};
 use std::ops::Range;
 use theme::ActiveTheme;
-use ui::{KeyBinding, Tooltip, prelude::*, vertical_divider};
+use ui::{ContextMenu, KeyBinding, PopoverMenu, Tooltip, prelude::*, vertical_divider};
 use util::ResultExt as _;
 use workspace::{
     CloseActiveItem, ItemNavHistory, SerializableItem, ToolbarItemEvent, ToolbarItemLocation,
@@ -41,7 +43,7 @@ use workspace::{
     searchable::SearchableItemHandle,
 };
 
-actions!(git, [Diff, Add]);
+actions!(git, [Diff, Add, ToggleDiffEditable, FilterDiffByPattern]);
 
 pub struct ProjectDiff {
     project: Entity<Project>,
@@ -52,6 +54,12 @@ pub struct ProjectDiff {
     focus_handle: FocusHandle,
     update_needed: postage::watch::Sender<()>,
     pending_scroll: Option<PathKey>,
+    hunk_filter: Option<Regex>,
+    context_lines: u32,
+    /// The number of whitespace-only hunks left out of each path's excerpts.
+    hidden_whitespace_hunks: HashMap<PathKey, usize>,
+    /// Whether collapsed whitespace-only hunks have been shown again in this view.
+    whitespace_hunks_expanded: bool,
     _task: Task<Result<()>>,
     _subscription: Subscription,
 }
@@ -64,6 +72,9 @@ struct DiffBuffer {
     file_status: FileStatus,
 }
 
+/// The numbers of unchanged lines around each hunk that can be picked from the toolbar.
+const CONTEXT_LINE_PRESETS: [u32; 4] = [0, editor::DEFAULT_MULTIBUFFER_CONTEXT, 3, 8];
+
 const CONFLICT_NAMESPACE: u32 = 1;
 const TRACKED_NAMESPACE: u32 = 2;
 const NEW_NAMESPACE: u32 = 3;
@@ -74,6 +85,14 @@ impl ProjectDiff {
         workspace.register_action(|workspace, _: &Add, window, cx| {
             Self::deploy(workspace, &Diff, window, cx);
         });
+        workspace.register_action(|workspace, _: &FilterDiffByPattern, window, cx| {
+            let Some(project_diff) = workspace.active_item_as::<Self>(cx) else {
+                return;
+            };
+            workspace.toggle_modal(window, cx, |window, cx| {
+                DiffFilterModal::new(project_diff, window, cx)
+            });
+        });
         workspace::register_serializable_item::<ProjectDiff>(cx);
     }
 
@@ -143,6 +162,8 @@ impl ProjectDiff {
                 Editor::for_multibuffer(multibuffer.clone(), Some(project.clone()), window, cx);
             diff_display_editor.disable_inline_diagnostics();
             diff_display_editor.set_expand_all_diff_hunks(cx);
+            diff_display_editor.set_read_only(GitPanelSettings::get_global(cx).diff_read_only);
+            diff_display_editor.set_allow_staging_while_read_only(true);
             diff_display_editor.register_addon(GitPanelAddon {
                 workspace: workspace.downgrade(),
             });
@@ -177,12 +198,17 @@ impl ProjectDiff {
         );
 
         let mut was_sort_by_path = GitPanelSettings::get_global(cx).sort_by_path;
+        let mut previous_whitespace_hunks = GitPanelSettings::get_global(cx).whitespace_hunks;
         cx.observe_global::<SettingsStore>(move |this, cx| {
-            let is_sort_by_path = GitPanelSettings::get_global(cx).sort_by_path;
-            if is_sort_by_path != was_sort_by_path {
+            let settings = GitPanelSettings::get_global(cx);
+            let is_sort_by_path = settings.sort_by_path;
+            if is_sort_by_path != was_sort_by_path
+                || settings.whitespace_hunks != previous_whitespace_hunks
+            {
                 *this.update_needed.borrow_mut() = ();
             }
-            was_sort_by_path = is_sort_by_path
+            was_sort_by_path = is_sort_by_path;
+            previous_whitespace_hunks = settings.whitespace_hunks;
         })
         .detach();
 
@@ -202,6 +228,10 @@ impl ProjectDiff {
             editor,
             multibuffer,
             pending_scroll: None,
+            hunk_filter: None,
+            context_lines: editor::DEFAULT_MULTIBUFFER_CONTEXT,
+            hidden_whitespace_hunks: HashMap::default(),
+            whitespace_hunks_expanded: false,
             update_needed: send,
             _task: worker,
             _subscription: git_store_subscription,
@@ -232,6 +262,67 @@ impl ProjectDiff {
         self.move_to_path(path_key, window, cx)
     }
 
+    /// Returns the pattern that hunks must have a changed line matching to be shown.
+    pub fn hunk_filter(&self) -> Option<&Regex> {
+        self.hunk_filter.as_ref()
+    }
+
+    pub fn set_hunk_filter(&mut self, filter: Option<Regex>, cx: &mut Context<Self>) {
+        self.hunk_filter = filter;
+        *self.update_needed.borrow_mut() = ();
+        cx.notify();
+    }
+
+    /// Returns the number of unchanged lines shown around each hunk.
+    pub fn context_lines(&self) -> u32 {
+        self.context_lines
+    }
+
+    /// Sets the number of unchanged lines shown around each hunk. Hunks whose context would
+    /// overlap are shown in a single excerpt.
+    pub fn set_context_lines(&mut self, context_lines: u32, cx: &mut Context<Self>) {
+        self.context_lines = context_lines;
+        *self.update_needed.borrow_mut() = ();
+        cx.notify();
+    }
+
+    /// Returns the number of whitespace-only hunks that are collapsed, which is zero unless
+    /// the `whitespace_hunks` setting is `collapse`.
+    pub fn collapsed_whitespace_hunk_count(&self, cx: &App) -> usize {
+        if GitPanelSettings::get_global(cx).whitespace_hunks != WhitespaceHunks::Collapse {
+            return 0;
+        }
+        self.hidden_whitespace_hunks.values().sum()
+    }
+
+    pub fn whitespace_hunks_expanded(&self) -> bool {
+        self.whitespace_hunks_expanded
+    }
+
+    /// Shows the collapsed whitespace-only hunks, or collapses them again.
+    pub fn toggle_whitespace_hunks(&mut self, cx: &mut Context<Self>) {
+        self.whitespace_hunks_expanded = !self.whitespace_hunks_expanded;
+        *self.update_needed.borrow_mut() = ();
+        cx.notify();
+    }
+
+    pub fn is_locked(&self, cx: &App) -> bool {
+        self.editor.read(cx).read_only(cx)
+    }
+
+    fn toggle_diff_editable(
+        &mut self,
+        _: &ToggleDiffEditable,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.set_read_only(!editor.read_only(cx));
+            cx.notify();
+        });
+        cx.notify();
+    }
+
     pub fn active_path(&self, cx: &App) -> Option<ProjectPath> {
         let editor = self.editor.read(cx);
         let position = editor.selections.newest_anchor().head();
@@ -355,6 +446,7 @@ impl ProjectDiff {
             self.multibuffer.update(cx, |multibuffer, cx| {
                 multibuffer.clear(cx);
             });
+            self.hidden_whitespace_hunks.clear();
             return vec![];
         };
 
@@ -403,6 +495,9 @@ impl ProjectDiff {
                 }));
             }
         });
+        for path in &previous_paths {
+            self.hidden_whitespace_hunks.remove(path);
+        }
         self.multibuffer.update(cx, |multibuffer, cx| {
             for path in previous_paths {
                 multibuffer.remove_excerpts_for_path(path, cx);
@@ -429,11 +524,30 @@ impl ProjectDiff {
 
         let snapshot = buffer.read(cx).snapshot();
         let diff = diff.read(cx);
+        let base_text = diff.base_text();
+        let hide_whitespace_hunks = match GitPanelSettings::get_global(cx).whitespace_hunks {
+            WhitespaceHunks::Show => false,
+            WhitespaceHunks::Hide => true,
+            WhitespaceHunks::Collapse => !self.whitespace_hunks_expanded,
+        };
+        let mut hidden_whitespace_hunks = 0;
         let diff_hunk_ranges = diff
             .hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &snapshot, cx)
+            .filter(|diff_hunk| {
+                self.hunk_filter.as_ref().is_none_or(|filter| {
+                    hunk_matches_filter(diff_hunk, filter, &snapshot, base_text)
+                })
+            })
+            .filter(|diff_hunk| {
+                let hidden = hide_whitespace_hunks
+                    && is_whitespace_only_hunk(diff_hunk, &snapshot, base_text);
+                hidden_whitespace_hunks += hidden as usize;
+                !hidden
+            })
             .map(|diff_hunk| diff_hunk.buffer_range.clone());
         let conflicts = conflict_addon
             .conflict_set(snapshot.remote_id())
+            .filter(|_| self.hunk_filter.is_none())
             .map(|conflict_set| conflict_set.read(cx).snapshot().conflicts.clone())
             .unwrap_or_default();
         let conflicts = conflicts.iter().map(|conflict| conflict.range.clone());
@@ -441,6 +555,16 @@ impl ProjectDiff {
         let excerpt_ranges = merge_anchor_ranges(diff_hunk_ranges, conflicts, &snapshot)
             .map(|range| range.to_point(&snapshot))
             .collect::<Vec<_>>();
+        self.hidden_whitespace_hunks
+            .insert(path_key.clone(), hidden_whitespace_hunks);
+
+        if excerpt_ranges.is_empty() && (self.hunk_filter.is_some() || hidden_whitespace_hunks > 0)
+        {
+            self.multibuffer.update(cx, |multibuffer, cx| {
+                multibuffer.remove_excerpts_for_path(path_key, cx);
+            });
+            return;
+        }
 
         let (was_empty, is_excerpt_newly_added) = self.multibuffer.update(cx, |multibuffer, cx| {
             let was_empty = multibuffer.is_empty();
@@ -448,7 +572,7 @@ impl ProjectDiff {
                 path_key.clone(),
                 buffer,
                 excerpt_ranges,
-                editor::DEFAULT_MULTIBUFFER_CONTEXT,
+                self.context_lines,
                 cx,
             );
             (was_empty, is_newly_added)
@@ -678,6 +802,10 @@ impl Item for ProjectDiff {
         ToolbarItemLocation::PrimaryLeft
     }
 
+    fn has_breadcrumbs(&self, _: &App) -> bool {
+        true
+    }
+
     fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
         self.editor.breadcrumbs(theme, cx)
     }
@@ -701,6 +829,7 @@ impl Render for ProjectDiff {
         div()
             .track_focus(&self.focus_handle)
             .key_context(if is_empty { "EmptyPane" } else { "GitDiff" })
+            .on_action(cx.listener(Self::toggle_diff_editable))
             .bg(cx.theme().colors().editor_background)
             .flex()
             .items_center()
@@ -720,11 +849,13 @@ impl Render for ProjectDiff {
                 el.child(
                     v_flex()
                         .gap_1()
-                        .child(
-                            h_flex()
-                                .justify_around()
-                                .child(Label::new("No uncommitted changes")),
-                        )
+                        .child(h_flex().justify_around().child(Label::new(
+                            if self.hunk_filter.is_some() {
+                                "No changes match the filter"
+                            } else {
+                                "No uncommitted changes"
+                            },
+                        )))
                         .map(|el| match remote_button {
                             Some(button) => el.child(h_flex().justify_around().child(button)),
                             None => el.child(
@@ -901,6 +1032,16 @@ impl Render for ProjectDiffToolbar {
         };
         let focus_handle = project_diff.focus_handle(cx);
         let button_states = project_diff.read(cx).button_states(cx);
+        let is_locked = project_diff.read(cx).is_locked(cx);
+        let hunk_filter = project_diff
+            .read(cx)
+            .hunk_filter()
+            .map(|filter| filter.as_str().to_string());
+        let context_lines = project_diff.read(cx).context_lines();
+        let collapsed_whitespace_hunks = project_diff.read(cx).collapsed_whitespace_hunk_count(cx);
+        let whitespace_hunks_expanded = project_diff.read(cx).whitespace_hunks_expanded()
+            && GitPanelSettings::get_global(cx).whitespace_hunks == WhitespaceHunks::Collapse;
+        let weak_project_diff = project_diff.downgrade();
 
         h_group_xl()
             .my_neg_1()
@@ -908,6 +1049,115 @@ impl Render for ProjectDiffToolbar {
             .items_center()
             .flex_wrap()
             .justify_between()
+            .child(
+                IconButton::new("toggle-diff-editable", IconName::LockOutlined)
+                    .shape(ui::IconButtonShape::Square)
+                    .toggle_state(is_locked)
+                    .selected_icon_color(Color::Accent)
+                    .tooltip(Tooltip::for_action_title_in(
+                        if is_locked {
+                            "Unlock diff for editing"
+                        } else {
+                            "Lock diff to prevent edits"
+                        },
+                        &ToggleDiffEditable,
+                        &focus_handle,
+                    ))
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.dispatch_action(&ToggleDiffEditable, window, cx)
+                    })),
+            )
+            .child(
+                PopoverMenu::new("diff-context-lines")
+                    .trigger_with_tooltip(
+                        Button::new(
+                            "diff-context-lines-trigger",
+                            context_lines_label(context_lines),
+                        )
+                        .label_size(LabelSize::Small)
+                        .icon(IconName::ChevronDownSmall)
+                        .icon_position(IconPosition::End)
+                        .icon_size(IconSize::XSmall),
+                        Tooltip::text("Unchanged lines shown around each change"),
+                    )
+                    .menu(move |window, cx| {
+                        let project_diff = weak_project_diff.clone();
+                        Some(ContextMenu::build(window, cx, |mut menu, _, _| {
+                            for preset in CONTEXT_LINE_PRESETS {
+                                let project_diff = project_diff.clone();
+                                menu = menu.toggleable_entry(
+                                    context_lines_label(preset),
+                                    preset == context_lines,
+                                    IconPosition::Start,
+                                    None,
+                                    move |_, cx| {
+                                        project_diff
+                                            .update(cx, |project_diff, cx| {
+                                                project_diff.set_context_lines(preset, cx)
+                                            })
+                                            .ok();
+                                    },
+                                );
+                            }
+                            menu
+                        }))
+                    }),
+            )
+            .when_some(hunk_filter, |el, hunk_filter| {
+                el.child(
+                    Button::new("clear-diff-filter", "Clear Filter")
+                        .icon(IconName::Close)
+                        .icon_position(IconPosition::Start)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text(format!(
+                            "Showing changes matching {hunk_filter}"
+                        )))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            if let Some(project_diff) = this.project_diff(cx) {
+                                project_diff.update(cx, |project_diff, cx| {
+                                    project_diff.set_hunk_filter(None, cx)
+                                });
+                            }
+                        })),
+                )
+            })
+            .when(collapsed_whitespace_hunks > 0, |el| {
+                let label = if collapsed_whitespace_hunks == 1 {
+                    "1 whitespace-only hunk hidden".to_string()
+                } else {
+                    format!("{collapsed_whitespace_hunks} whitespace-only hunks hidden")
+                };
+                el.child(
+                    Button::new("show-whitespace-hunks", label)
+                        .label_size(LabelSize::Small)
+                        .color(Color::Muted)
+                        .tooltip(Tooltip::text("Click to show them"))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            if let Some(project_diff) = this.project_diff(cx) {
+                                project_diff.update(cx, |project_diff, cx| {
+                                    project_diff.toggle_whitespace_hunks(cx)
+                                });
+                            }
+                        })),
+                )
+            })
+            .when(whitespace_hunks_expanded, |el| {
+                el.child(
+                    Button::new(
+                        "collapse-whitespace-hunks",
+                        "Collapse Whitespace-Only Hunks",
+                    )
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        if let Some(project_diff) = this.project_diff(cx) {
+                            project_diff.update(cx, |project_diff, cx| {
+                                project_diff.toggle_whitespace_hunks(cx)
+                            });
+                        }
+                    })),
+                )
+            })
+            .child(vertical_divider())
             .child(
                 h_group_sm()
                     .when(button_states.selection, |el| {
@@ -1282,6 +1532,50 @@ mod preview {
     }
 }
 
+fn context_lines_label(context_lines: u32) -> String {
+    match context_lines {
+        1 => "1 Line".to_string(),
+        _ => format!("{context_lines} Lines"),
+    }
+}
+
+/// Returns whether any line added or removed by the hunk matches the filter.
+fn hunk_matches_filter(
+    hunk: &DiffHunk,
+    filter: &Regex,
+    buffer: &BufferSnapshot,
+    base_text: &BufferSnapshot,
+) -> bool {
+    let added_text = buffer
+        .text_for_range(hunk.buffer_range.clone())
+        .collect::<String>();
+    let removed_text = base_text
+        .text_for_range(hunk.diff_base_byte_range.clone())
+        .collect::<String>();
+    added_text
+        .lines()
+        .chain(removed_text.lines())
+        .any(|line| filter.is_match(line))
+}
+
+/// Returns whether the hunk's removed and added text only differ in whitespace, such as a
+/// re-indented block or added blank lines.
+fn is_whitespace_only_hunk(
+    hunk: &DiffHunk,
+    buffer: &BufferSnapshot,
+    base_text: &BufferSnapshot,
+) -> bool {
+    let added_chars = buffer
+        .text_for_range(hunk.buffer_range.clone())
+        .flat_map(str::chars)
+        .filter(|c| !c.is_whitespace());
+    let removed_chars = base_text
+        .text_for_range(hunk.diff_base_byte_range.clone())
+        .flat_map(str::chars)
+        .filter(|c| !c.is_whitespace());
+    added_chars.eq(removed_chars)
+}
+
 fn merge_anchor_ranges<'a>(
     left: impl 'a + Iterator<Item = Range<Anchor>>,
     right: impl 'a + Iterator<Item = Range<Anchor>>,
@@ -1335,7 +1629,7 @@ mod tests {
     use db::indoc;
     use editor::test::editor_test_context::{EditorTestContext, assert_state_with_diff};
     use git::status::{UnmergedStatus, UnmergedStatusCode};
-    use gpui::TestAppContext;
+    use gpui::{TestAppContext, VisualTestContext};
     use project::FakeFs;
     use serde_json::json;
     use settings::SettingsStore;
@@ -1417,6 +1711,119 @@ mod tests {
         assert_eq!(text, "foo\n");
     }
 
+    #[gpui::test]
+    async fn test_context_lines(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let original = (1..=12)
+            .map(|row| format!("line {row}\n"))
+            .collect::<String>();
+        let modified = original
+            .replace("line 2\n", "LINE 2\n")
+            .replace("line 9\n", "LINE 9\n");
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "foo.txt": modified,
+            }),
+        )
+        .await;
+        fs.set_head_and_index_for_repo(
+            path!("/project/.git").as_ref(),
+            &[("foo.txt".into(), original)],
+        );
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let diff = cx.new_window_entity(|window, cx| {
+            ProjectDiff::new(project.clone(), workspace, window, cx)
+        });
+        cx.run_until_parked();
+
+        let excerpt_count = |cx: &mut VisualTestContext| {
+            diff.read_with(cx, |diff, cx| diff.multibuffer.read(cx).excerpt_ids().len())
+        };
+        assert_eq!(excerpt_count(cx), 2);
+
+        diff.update(cx, |diff, cx| diff.set_context_lines(0, cx));
+        cx.run_until_parked();
+        assert_eq!(excerpt_count(cx), 2);
+
+        // The context of the two hunks overlaps, so they are shown in a single excerpt.
+        diff.update(cx, |diff, cx| diff.set_context_lines(3, cx));
+        cx.run_until_parked();
+        assert_eq!(excerpt_count(cx), 1);
+    }
+
+    #[gpui::test]
+    async fn test_whitespace_hunks(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let original = (1..=12)
+            .map(|row| format!("line {row}\n"))
+            .collect::<String>();
+        let modified = original
+            .replace("line 2\n", "    line  2\n\n")
+            .replace("line 9\n", "LINE 9\n");
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                ".git": {},
+                "foo.txt": modified,
+            }),
+        )
+        .await;
+        fs.set_head_and_index_for_repo(
+            path!("/project/.git").as_ref(),
+            &[("foo.txt".into(), original)],
+        );
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let diff = cx.new_window_entity(|window, cx| {
+            ProjectDiff::new(project.clone(), workspace, window, cx)
+        });
+        cx.run_until_parked();
+
+        let set_whitespace_hunks = |whitespace_hunks, cx: &mut VisualTestContext| {
+            cx.update(|_, cx| {
+                SettingsStore::update_global(cx, |store, cx| {
+                    store.update_user_settings::<GitPanelSettings>(cx, |settings| {
+                        settings.whitespace_hunks = Some(whitespace_hunks);
+                    });
+                });
+            });
+            cx.run_until_parked();
+        };
+        let state = |cx: &mut VisualTestContext| {
+            diff.read_with(cx, |diff, cx| {
+                (
+                    diff.multibuffer.read(cx).excerpt_ids().len(),
+                    diff.collapsed_whitespace_hunk_count(cx),
+                )
+            })
+        };
+        assert_eq!(state(cx), (2, 0));
+
+        // The re-indented line followed by a blank line only changes whitespace.
+        set_whitespace_hunks(WhitespaceHunks::Hide, cx);
+        assert_eq!(state(cx), (1, 0));
+
+        set_whitespace_hunks(WhitespaceHunks::Collapse, cx);
+        assert_eq!(state(cx), (1, 1));
+
+        diff.update(cx, |diff, cx| diff.toggle_whitespace_hunks(cx));
+        cx.run_until_parked();
+        assert_eq!(state(cx), (2, 0));
+
+        diff.update(cx, |diff, cx| diff.toggle_whitespace_hunks(cx));
+        cx.run_until_parked();
+        assert_eq!(state(cx), (1, 1));
+    }
+
     #[gpui::test]
     async fn test_scroll_to_beginning_with_deletion(cx: &mut TestAppContext) {
         init_test(cx);
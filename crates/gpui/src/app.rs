@@ -821,6 +821,11 @@ impl App {
         self.platform.should_auto_hide_scrollbars()
     }
 
+    /// Returns whether the user has asked the platform to reduce motion in animations.
+    pub fn should_reduce_motion(&self) -> bool {
+        self.platform.should_reduce_motion()
+    }
+
     /// Restarts the application.
     pub fn restart(&self, binary_path: Option<PathBuf>) {
         self.platform.restart(binary_path)
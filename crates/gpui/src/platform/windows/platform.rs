@@ -593,6 +593,10 @@ impl Platform for WindowsPlatform {
         should_auto_hide_scrollbars().log_err().unwrap_or(false)
     }
 
+    fn should_reduce_motion(&self) -> bool {
+        should_reduce_motion().log_err().unwrap_or(false)
+    }
+
     fn write_to_clipboard(&self, item: ClipboardItem) {
         write_to_clipboard(item);
     }
@@ -853,6 +857,12 @@ fn should_auto_hide_scrollbars() -> Result<bool> {
     Ok(ui_settings.AutoHideScrollBars()?)
 }
 
+#[inline]
+fn should_reduce_motion() -> Result<bool> {
+    let ui_settings = UISettings::new()?;
+    Ok(!ui_settings.AnimationsEnabled()?)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{ClipboardItem, read_from_clipboard, write_to_clipboard};
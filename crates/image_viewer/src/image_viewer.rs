@@ -167,15 +167,27 @@ impl Item for ImageView {
         }
     }
 
+    fn has_breadcrumbs(&self, _: &App) -> bool {
+        true
+    }
+
     fn breadcrumbs(&self, _theme: &Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
         let text = breadcrumbs_text_for_image(self.project.read(cx), self.image_item.read(cx), cx);
         Some(vec![BreadcrumbText {
             text,
             highlights: None,
             font: None,
+            type_hint: None,
+            source_range: None,
         }])
     }
 
+    fn breadcrumb_metadata(&self, cx: &App) -> Option<SharedString> {
+        let metadata = self.image_item.read(cx).image_metadata?;
+        let size = format_image_size(metadata.file_size, ImageViewerSettings::get_global(cx).unit);
+        Some(format!("{}x{}, {}", metadata.width, metadata.height, size).into())
+    }
+
     fn clone_on_split(
         &self,
         _workspace_id: Option<WorkspaceId>,
@@ -3477,18 +3477,78 @@ impl BufferSnapshot {
         theme: Option<&SyntaxTheme>,
     ) -> Option<Vec<OutlineItem<Anchor>>> {
         let position = position.to_offset(self);
-        let mut items = self.outline_items_containing(
+        let items = self.outline_items_containing(
             position.saturating_sub(1)..self.len().min(position + 1),
             false,
             theme,
         )?;
-        let mut prev_depth = None;
-        items.retain(|item| {
-            let result = prev_depth.map_or(true, |prev_depth| item.depth > prev_depth);
-            prev_depth = Some(item.depth);
-            result
+        Some(retain_nested_items(items))
+    }
+
+    /// Like [`Self::symbols_containing`], but returns only the `max_depth` outermost symbols.
+    /// The deeper symbols are left out before their text is computed, which bounds the work
+    /// done in deeply nested code.
+    pub fn symbols_containing_to_depth<T: ToOffset>(
+        &self,
+        position: T,
+        max_depth: usize,
+        theme: Option<&SyntaxTheme>,
+    ) -> Option<Vec<OutlineItem<Anchor>>> {
+        let position = position.to_offset(self);
+        let range = position.saturating_sub(1)..self.len().min(position + 1);
+        let outermost_item_ranges = self.outermost_outline_item_ranges(&range, max_depth);
+        let items = self.outline_items_containing_internal(range, false, theme, &|item_range| {
+            outermost_item_ranges.contains(&item_range)
+        })?;
+        Some(retain_nested_items(items))
+    }
+
+    /// Returns the byte ranges of the `max_depth` outermost outline items that intersect the
+    /// given range and are each nested in the previous one, without computing their text.
+    fn outermost_outline_item_ranges(
+        &self,
+        range: &Range<usize>,
+        max_depth: usize,
+    ) -> Vec<Range<usize>> {
+        let mut matches = self.syntax.matches(range.clone(), &self.text, |grammar| {
+            grammar.outline_config.as_ref().map(|c| &c.query)
         });
-        Some(items)
+        let configs = matches
+            .grammars()
+            .iter()
+            .map(|g| g.outline_config.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut item_ranges = Vec::new();
+        while let Some(mat) = matches.peek() {
+            let config = &configs[mat.grammar_index];
+            let item_range = mat
+                .captures
+                .iter()
+                .find(|capture| capture.index == config.item_capture_ix)
+                .map(|capture| capture.node.byte_range())
+                .filter(|item_range| {
+                    item_range.end >= range.start && item_range.start <= range.end
+                });
+            item_ranges.extend(item_range);
+            matches.advance();
+        }
+        item_ranges.sort_by_key(|item_range| (item_range.start, Reverse(item_range.end)));
+        item_ranges.dedup();
+
+        let mut nested_ranges = Vec::<Range<usize>>::new();
+        for item_range in item_ranges {
+            if nested_ranges.len() == max_depth {
+                break;
+            }
+            if nested_ranges
+                .last()
+                .is_none_or(|outer_range| item_range.end <= outer_range.end)
+            {
+                nested_ranges.push(item_range);
+            }
+        }
+        nested_ranges
     }
 
     pub fn outline_range_containing<T: ToOffset>(&self, range: Range<T>) -> Option<Range<Point>> {
@@ -3539,7 +3599,23 @@ impl BufferSnapshot {
         include_extra_context: bool,
         theme: Option<&SyntaxTheme>,
     ) -> Option<Vec<OutlineItem<Anchor>>> {
-        let range = range.to_offset(self);
+        self.outline_items_containing_internal(
+            range.to_offset(self),
+            include_extra_context,
+            theme,
+            &|_| true,
+        )
+    }
+
+    /// Returns the outline items intersecting the given range, skipping those whose byte range
+    /// `include_item` rejects before computing their text.
+    fn outline_items_containing_internal(
+        &self,
+        range: Range<usize>,
+        include_extra_context: bool,
+        theme: Option<&SyntaxTheme>,
+        include_item: &dyn Fn(Range<usize>) -> bool,
+    ) -> Option<Vec<OutlineItem<Anchor>>> {
         let mut matches = self.syntax.matches(range.clone(), &self.text, |grammar| {
             grammar.outline_config.as_ref().map(|c| &c.query)
         });
@@ -3553,9 +3629,14 @@ impl BufferSnapshot {
         let mut annotation_row_ranges: Vec<Range<u32>> = Vec::new();
         while let Some(mat) = matches.peek() {
             let config = &configs[mat.grammar_index];
-            if let Some(item) =
-                self.next_outline_item(config, &mat, &range, include_extra_context, theme)
-            {
+            if let Some(item) = self.next_outline_item(
+                config,
+                &mat,
+                &range,
+                include_extra_context,
+                theme,
+                include_item,
+            ) {
                 items.push(item);
             } else if let Some(capture) = mat
                 .captures
@@ -3617,6 +3698,9 @@ impl BufferSnapshot {
                 text: item.text,
                 highlight_ranges: item.highlight_ranges,
                 name_ranges: item.name_ranges,
+                name_range: item.name_range.map(|name_range| {
+                    self.anchor_after(name_range.start)..self.anchor_before(name_range.end)
+                }),
                 body_range: item.body_range.map(|body_range| {
                     self.anchor_after(body_range.start)..self.anchor_before(body_range.end)
                 }),
@@ -3641,6 +3725,7 @@ impl BufferSnapshot {
         range: &Range<usize>,
         include_extra_context: bool,
         theme: Option<&SyntaxTheme>,
+        include_item: &dyn Fn(Range<usize>) -> bool,
     ) -> Option<OutlineItem<Point>> {
         let item_node = mat.captures.iter().find_map(|cap| {
             if cap.index == config.item_capture_ix {
@@ -3651,7 +3736,10 @@ impl BufferSnapshot {
         })?;
 
         let item_byte_range = item_node.byte_range();
-        if item_byte_range.end < range.start || item_byte_range.start > range.end {
+        if item_byte_range.end < range.start
+            || item_byte_range.start > range.end
+            || !include_item(item_byte_range)
+        {
             return None;
         }
         let item_point_range = Point::from_ts_point(item_node.start_position())
@@ -3694,6 +3782,7 @@ impl BufferSnapshot {
         let mut text = String::new();
         let mut highlight_ranges = Vec::new();
         let mut name_ranges = Vec::new();
+        let mut name_range = None;
         let mut chunks = self.chunks(
             buffer_ranges.first().unwrap().0.start..buffer_ranges.last().unwrap().0.end,
             true,
@@ -3730,6 +3819,12 @@ impl BufferSnapshot {
                 }
             }
             if is_name {
+                if name_range.is_none() {
+                    name_range = Some(
+                        self.offset_to_point(buffer_range.start)
+                            ..self.offset_to_point(buffer_range.end),
+                    );
+                }
                 let after_append_len = text.len();
                 let start = if space_added && !name_ranges.is_empty() {
                     before_append_len - 1
@@ -3747,6 +3842,7 @@ impl BufferSnapshot {
             text,
             highlight_ranges,
             name_ranges,
+            name_range,
             body_range: open_point.zip(close_point).map(|(start, end)| start..end),
             annotation_range: None,
         })
@@ -4358,6 +4454,18 @@ pub struct WordsQuery<'a> {
     pub range: Range<usize>,
 }
 
+/// Keeps the outline items that are each nested in the previous one, which are the symbols
+/// containing a position.
+fn retain_nested_items(mut items: Vec<OutlineItem<Anchor>>) -> Vec<OutlineItem<Anchor>> {
+    let mut prev_depth = None;
+    items.retain(|item| {
+        let result = prev_depth.map_or(true, |prev_depth| item.depth > prev_depth);
+        prev_depth = Some(item.depth);
+        result
+    });
+    items
+}
+
 fn indent_size_for_line(text: &text::BufferSnapshot, row: u32) -> IndentSize {
     indent_size_for_text(text.chars_at(Point::new(row, 0)))
 }
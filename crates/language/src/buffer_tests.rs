@@ -991,6 +991,55 @@ async fn test_symbols_containing(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_symbols_containing_to_depth(cx: &mut gpui::TestAppContext) {
+    let text = r#"
+        impl Person {
+            fn one() {
+                1
+            }
+
+            fn two() {
+                2
+            }fn three() {
+                3
+            }
+        }
+    "#
+    .unindent();
+
+    let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx));
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+
+    let symbols_containing_to_depth = |position: Point, max_depth: usize| {
+        snapshot
+            .symbols_containing_to_depth(position, max_depth, None)
+            .unwrap()
+            .into_iter()
+            .map(|item| item.text)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        symbols_containing_to_depth(Point::new(2, 8), usize::MAX),
+        ["impl Person", "fn one"]
+    );
+    assert_eq!(
+        symbols_containing_to_depth(Point::new(2, 8), 1),
+        ["impl Person"]
+    );
+    assert_eq!(
+        symbols_containing_to_depth(Point::new(2, 8), 0),
+        Vec::<String>::new()
+    );
+
+    // Of two adjacent items, only the first is nested in the one containing them.
+    assert_eq!(
+        symbols_containing_to_depth(Point::new(7, 5), 2),
+        ["impl Person", "fn two"]
+    );
+}
+
 #[gpui::test]
 fn test_text_objects(cx: &mut App) {
     let (text, ranges) = marked_text_ranges(
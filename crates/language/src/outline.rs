@@ -19,6 +19,9 @@ pub struct OutlineItem<T> {
     pub text: String,
     pub highlight_ranges: Vec<(Range<usize>, HighlightStyle)>,
     pub name_ranges: Vec<Range<usize>>,
+    /// The range of the item's first name in the buffer, whose text is at the first of the
+    /// `name_ranges` in `text`.
+    pub name_range: Option<Range<T>>,
     pub body_range: Option<Range<T>>,
     pub annotation_range: Option<Range<T>>,
 }
@@ -35,6 +38,10 @@ impl<T: ToPoint> OutlineItem<T> {
             text: self.text.clone(),
             highlight_ranges: self.highlight_ranges.clone(),
             name_ranges: self.name_ranges.clone(),
+            name_range: self
+                .name_range
+                .as_ref()
+                .map(|r| r.start.to_point(buffer)..r.end.to_point(buffer)),
             body_range: self
                 .body_range
                 .as_ref()
@@ -208,6 +215,7 @@ mod tests {
                 text: "class Foo".to_string(),
                 highlight_ranges: vec![],
                 name_ranges: vec![6..9],
+                name_range: None,
                 body_range: None,
                 annotation_range: None,
             },
@@ -217,6 +225,7 @@ mod tests {
                 text: "private".to_string(),
                 highlight_ranges: vec![],
                 name_ranges: vec![],
+                name_range: None,
                 body_range: None,
                 annotation_range: None,
             },
@@ -241,6 +250,7 @@ mod tests {
                 text: "fn process".to_string(),
                 highlight_ranges: vec![],
                 name_ranges: vec![3..10],
+                name_range: None,
                 body_range: None,
                 annotation_range: None,
             },
@@ -250,6 +260,7 @@ mod tests {
                 text: "struct DataProcessor".to_string(),
                 highlight_ranges: vec![],
                 name_ranges: vec![7..20],
+                name_range: None,
                 body_range: None,
                 annotation_range: None,
             },
@@ -788,6 +788,17 @@ impl MultiBuffer {
         self.read(cx).symbols_containing(offset, theme)
     }
 
+    pub fn symbols_containing_to_depth<T: ToOffset>(
+        &self,
+        offset: T,
+        max_depth: usize,
+        theme: Option<&SyntaxTheme>,
+        cx: &App,
+    ) -> Option<(BufferId, Vec<OutlineItem<Anchor>>)> {
+        self.read(cx)
+            .symbols_containing_to_depth(offset, max_depth, theme)
+    }
+
     pub fn edit<I, S, T>(
         &self,
         edits: I,
@@ -4294,6 +4305,16 @@ impl MultiBufferSnapshot {
         self.excerpts.summary().widest_line_number + 1
     }
 
+    /// Returns the widest line number of the diff bases of the buffers, as
+    /// [`Self::widest_line_number`] does for the buffers themselves.
+    pub fn widest_diff_base_line_number(&self) -> u32 {
+        self.diffs
+            .values()
+            .map(|diff| diff.base_text().max_point().row + 1)
+            .max()
+            .unwrap_or(1)
+    }
+
     pub fn bytes_in_range<T: ToOffset>(&self, range: Range<T>) -> MultiBufferBytes<'_> {
         let range = range.start.to_offset(self)..range.end.to_offset(self);
         let mut excerpts = self.cursor::<usize>();
@@ -6115,6 +6136,12 @@ impl MultiBufferSnapshot {
                         text: item.text,
                         highlight_ranges: item.highlight_ranges,
                         name_ranges: item.name_ranges,
+                        name_range: item.name_range.and_then(|name_range| {
+                            Some(
+                                self.anchor_in_excerpt(*excerpt_id, name_range.start)?
+                                    ..self.anchor_in_excerpt(*excerpt_id, name_range.end)?,
+                            )
+                        }),
                         body_range: item.body_range.and_then(|body_range| {
                             Some(
                                 self.anchor_in_excerpt(*excerpt_id, body_range.start)?
@@ -6137,15 +6164,41 @@ impl MultiBufferSnapshot {
         &self,
         offset: T,
         theme: Option<&SyntaxTheme>,
+    ) -> Option<(BufferId, Vec<OutlineItem<Anchor>>)> {
+        self.symbols_containing_internal(offset, None, theme)
+    }
+
+    /// Like [`Self::symbols_containing`], but returns only the `max_depth` outermost symbols,
+    /// without computing the deeper ones.
+    pub fn symbols_containing_to_depth<T: ToOffset>(
+        &self,
+        offset: T,
+        max_depth: usize,
+        theme: Option<&SyntaxTheme>,
+    ) -> Option<(BufferId, Vec<OutlineItem<Anchor>>)> {
+        self.symbols_containing_internal(offset, Some(max_depth), theme)
+    }
+
+    fn symbols_containing_internal<T: ToOffset>(
+        &self,
+        offset: T,
+        max_depth: Option<usize>,
+        theme: Option<&SyntaxTheme>,
     ) -> Option<(BufferId, Vec<OutlineItem<Anchor>>)> {
         let anchor = self.anchor_before(offset);
         let excerpt_id = anchor.excerpt_id;
         let excerpt = self.excerpt(excerpt_id)?;
+        let symbols = match max_depth {
+            Some(max_depth) => {
+                excerpt
+                    .buffer
+                    .symbols_containing_to_depth(anchor.text_anchor, max_depth, theme)
+            }
+            None => excerpt.buffer.symbols_containing(anchor.text_anchor, theme),
+        };
         Some((
             excerpt.buffer_id,
-            excerpt
-                .buffer
-                .symbols_containing(anchor.text_anchor, theme)
+            symbols
                 .into_iter()
                 .flatten()
                 .flat_map(|item| {
@@ -6156,6 +6209,12 @@ impl MultiBufferSnapshot {
                         text: item.text,
                         highlight_ranges: item.highlight_ranges,
                         name_ranges: item.name_ranges,
+                        name_range: item.name_range.and_then(|name_range| {
+                            Some(
+                                self.anchor_in_excerpt(excerpt_id, name_range.start)?
+                                    ..self.anchor_in_excerpt(excerpt_id, name_range.end)?,
+                            )
+                        }),
                         body_range: item.body_range.and_then(|body_range| {
                             Some(
                                 self.anchor_in_excerpt(excerpt_id, body_range.start)?
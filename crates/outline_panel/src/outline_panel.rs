@@ -2405,6 +2405,7 @@ impl OutlinePanel {
                     .cloned()
                     .unwrap_or_default(),
                 name_ranges: search_data.search_match_indices.clone(),
+                name_range: None,
                 body_range: Some(search_data.context_range.clone()),
             },
             match_ranges.iter().cloned(),
@@ -19,18 +19,22 @@ use futures::{
     future::{self, Shared},
 };
 use git::{
-    BuildPermalinkParams, GitHostingProviderRegistry, WORK_DIRECTORY_REPO_PATH,
+    BuildPermalinkParams, GitHostingProvider, GitHostingProviderRegistry, GitRemote,
+    ParsedGitRemote, SHORT_SHA_LENGTH, WORK_DIRECTORY_REPO_PATH,
     blame::Blame,
+    codeowners::{CODEOWNERS_PATHS, CodeOwners},
     parse_git_remote_url,
     repository::{
         Branch, CommitDetails, CommitDiff, CommitFile, CommitOptions, DiffType, FetchOptions,
         GitRepository, GitRepositoryCheckpoint, PushOptions, Remote, RemoteCommandOutput, RepoPath,
-        ResetMode, UpstreamTrackingStatus,
+        ResetMode, Upstream, UpstreamTrackingStatus,
     },
+    resolve_git_remote_url,
     status::{
         FileStatus, GitSummary, StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode,
     },
 };
+use git_hosting_providers::GitHostingProviderSettings;
 use gpui::{
     App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Subscription, Task,
     WeakEntity,
@@ -46,6 +50,7 @@ use rpc::{
     proto::{self, FromProto, SSH_PROJECT_ID, ToProto, git_reset, split_repository_update},
 };
 use serde::Deserialize;
+use settings::{Settings as _, SettingsLocation};
 use std::{
     cmp::Ordering,
     collections::{BTreeSet, VecDeque},
@@ -268,6 +273,17 @@ pub struct Repository {
     job_id: JobId,
     askpass_delegates: Arc<Mutex<HashMap<u64, AskPassDelegate>>>,
     latest_askpass_id: u64,
+    // The merge-base of HEAD and the upstream of the current branch, along with the HEAD
+    // commit and upstream it was computed for.
+    merge_base: Option<(MergeBaseKey, String)>,
+    // The URLs of the repository's remotes as of the last scan.
+    remote_urls: Option<HashMap<String, String>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct MergeBaseKey {
+    head_sha: Option<SharedString>,
+    upstream: Option<Upstream>,
 }
 
 impl std::ops::Deref for Repository {
@@ -665,6 +681,50 @@ impl GitStore {
         cx.background_spawn(async move { task.await.map_err(|e| anyhow!("{e}")) })
     }
 
+    /// Opens a diff of the buffer against the merge-base of HEAD and the upstream of the current
+    /// branch. Unlike the unstaged and uncommitted diffs, this diff isn't kept up to date as the
+    /// buffer or repository changes, so it must be reopened to be refreshed.
+    pub fn open_merge_base_diff(
+        &mut self,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<BufferDiff>>> {
+        let Some((repo, repo_path)) =
+            self.repository_and_path_for_buffer_id(buffer.read(cx).remote_id(), cx)
+        else {
+            return Task::ready(Err(anyhow!("failed to find git repository for buffer")));
+        };
+
+        let base_text = repo.update(cx, |repo, cx| repo.load_merge_base_text(repo_path, cx));
+        cx.spawn(async move |_, cx| {
+            let base_text = base_text.await?;
+            let (text_snapshot, language, language_registry) =
+                buffer.read_with(cx, |buffer, _| {
+                    (
+                        buffer.text_snapshot(),
+                        buffer.language().cloned(),
+                        buffer.language_registry(),
+                    )
+                })?;
+            let diff = cx.new(|cx| BufferDiff::new(&text_snapshot, cx))?;
+            let snapshot = BufferDiff::update_diff(
+                diff.clone(),
+                text_snapshot.clone(),
+                base_text.map(Arc::new),
+                true,
+                false,
+                language,
+                language_registry,
+                cx,
+            )
+            .await?;
+            diff.update(cx, |diff, cx| {
+                diff.set_snapshot(snapshot, &text_snapshot, cx)
+            })?;
+            Ok(diff)
+        })
+    }
+
     async fn open_diff_internal(
         this: WeakEntity<Self>,
         kind: DiffKind,
@@ -979,43 +1039,93 @@ impl GitStore {
         };
 
         let buffer_id = buffer.read(cx).remote_id();
-        let branch = repo.read(cx).branch.clone();
-        let remote = branch
-            .as_ref()
-            .and_then(|b| b.upstream.as_ref())
-            .and_then(|b| b.remote_name())
-            .unwrap_or("origin")
-            .to_string();
+        let selection = permalink_selection(
+            &buffer.read(cx).snapshot(),
+            selection,
+            GitHostingProviderSettings::get_global(cx).permalink_include_trailing_line,
+        );
+        let settings_location = SettingsLocation {
+            worktree_id: file.worktree.read(cx).id(),
+            path: &file.path,
+        };
+        let remote_names = repo
+            .read(cx)
+            .hosting_remote_names(Some(settings_location), cx);
+        let work_directory_abs_path = repo.read(cx).work_directory_abs_path.clone();
+        let shorten_permalinks = GitHostingProviderSettings::get_global(cx).shorten_permalinks;
 
         let rx = repo.update(cx, |repo, _| {
             repo.send_job(None, move |state, cx| async move {
                 match state {
                     RepositoryState::Local { backend, .. } => {
-                        let origin_url = backend
-                            .remote_url(&remote)
-                            .with_context(|| format!("remote \"{remote}\" not found"))?;
-
                         let sha = backend.head_sha().await.context("reading HEAD SHA")?;
 
                         let provider_registry =
                             cx.update(GitHostingProviderRegistry::default_global)?;
 
-                        let (provider, remote) =
-                            parse_git_remote_url(provider_registry, &origin_url)
-                                .context("parsing Git remote URL")?;
+                        let (provider, remote) = resolve_hosting_remote(
+                            backend.as_ref(),
+                            provider_registry,
+                            &work_directory_abs_path,
+                            &remote_names,
+                        )?;
+
+                        let old_paths = backend
+                            .paths_at_commit(sha.clone(), repo_path.clone())
+                            .await
+                            .context("resolving the file's path at HEAD")?;
+                        let short_sha = &sha[..sha.len().min(SHORT_SHA_LENGTH)];
+                        let repo_path = match old_paths.as_slice() {
+                            [old_path] => old_path.clone(),
+                            [] => bail!(
+                                "{repo_path} does not exist at commit {short_sha}, \
+                                so a permalink to it would not resolve"
+                            ),
+                            _ => bail!(
+                                "{repo_path} was renamed from several files since commit \
+                                {short_sha}: {}",
+                                old_paths
+                                    .iter()
+                                    .map(ToString::to_string)
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                        };
 
                         let path = repo_path.to_str().with_context(|| {
                             format!("converting repo path {repo_path:?} to string")
                         })?;
 
-                        Ok(provider.build_permalink(
+                        // LFS-tracked files are only pointers locally, so their lines don't
+                        // correspond to anything the provider renders; link to the file itself.
+                        let selection = if backend.is_lfs_tracked(&repo_path) {
+                            None
+                        } else {
+                            Some(selection)
+                        };
+                        let permalink = provider.build_permalink(
                             remote,
                             BuildPermalinkParams {
                                 sha: &sha,
                                 path,
-                                selection: Some(selection),
+                                selection,
                             },
-                        ))
+                        );
+
+                        if shorten_permalinks {
+                            let http_client = cx.update(|cx| cx.http_client())?;
+                            if let Some(short_permalink) = provider
+                                .shorten_url(&permalink, http_client)
+                                .await
+                                .context("shortening permalink")
+                                .log_err()
+                                .flatten()
+                            {
+                                return Ok(short_permalink);
+                            }
+                        }
+
+                        Ok(permalink)
                     }
                     RepositoryState::Remote { project_id, client } => {
                         let response = client
@@ -1037,6 +1147,107 @@ impl GitStore {
         cx.spawn(|_: &mut AsyncApp| async move { rx.await? })
     }
 
+    /// Returns a link to the blame of the given line of the buffer's file on the repository's
+    /// hosting provider, as of the repository's HEAD.
+    pub fn get_blame_url_to_line(
+        &self,
+        buffer: &Entity<Buffer>,
+        line: u32,
+        cx: &mut App,
+    ) -> Task<Result<url::Url>> {
+        let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
+            return Task::ready(Err(anyhow!("buffer has no file")));
+        };
+        let Some((repo, repo_path)) = self.repository_and_path_for_project_path(
+            &(file.worktree.read(cx).id(), file.path.clone()).into(),
+            cx,
+        ) else {
+            return Task::ready(Err(anyhow!("buffer is not in a Git repository")));
+        };
+
+        let hosting_remote = repo.update(cx, |repo, cx| repo.hosting_remote(cx));
+        let head_path = repo.update(cx, |repo, _| {
+            repo.send_job(None, move |state, _| async move {
+                let RepositoryState::Local { backend, .. } = state else {
+                    bail!("blame links are not supported for remote projects");
+                };
+                let sha = backend.head_sha().await.context("reading HEAD SHA")?;
+                let old_paths = backend
+                    .paths_at_commit(sha.clone(), repo_path.clone())
+                    .await
+                    .context("resolving the file's path at HEAD")?;
+                let [old_path] = old_paths.as_slice() else {
+                    bail!("{repo_path} has no single path at HEAD to link the blame of");
+                };
+                Ok((sha, old_path.clone()))
+            })
+        });
+        cx.spawn(async move |_| {
+            let remote = hosting_remote.await??;
+            let (sha, repo_path) = head_path.await??;
+            let path = repo_path
+                .to_str()
+                .with_context(|| format!("converting repo path {repo_path:?} to string"))?;
+            remote
+                .blame_url_at(&sha, path, Some(line..line))
+                .with_context(|| format!("{} has no blame view", remote.host.name()))
+        })
+    }
+
+    /// Returns the owners of the buffer's file according to its repository's CODEOWNERS
+    /// file, each with a link to the owner on the repository's hosting provider, if any.
+    ///
+    /// Returns no owners when the repository has no CODEOWNERS file.
+    pub fn code_owners_for_buffer(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Task<Result<Vec<(String, Option<url::Url>)>>> {
+        let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
+            return Task::ready(Err(anyhow!("buffer has no file")));
+        };
+        let Some((repo, repo_path)) = self.repository_and_path_for_project_path(
+            &(file.worktree.read(cx).id(), file.path.clone()).into(),
+            cx,
+        ) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let GitStoreState::Local { fs, .. } = &self.state else {
+            return Task::ready(Err(anyhow!(
+                "code owners are not supported for remote projects"
+            )));
+        };
+
+        let fs = fs.clone();
+        let work_directory_abs_path = repo.read(cx).work_directory_abs_path.clone();
+        let hosting_remote = repo.update(cx, |repo, cx| repo.hosting_remote(cx));
+        cx.spawn(async move |_| {
+            let mut code_owners = None;
+            for path in CODEOWNERS_PATHS {
+                if let Ok(text) = fs.load(&work_directory_abs_path.join(path)).await {
+                    code_owners = Some(CodeOwners::parse(&text));
+                    break;
+                }
+            }
+            let Some(code_owners) = code_owners else {
+                return Ok(Vec::new());
+            };
+
+            // Owners are still listed when the remote has no known hosting provider.
+            let hosting_remote = hosting_remote.await.ok().and_then(Result::ok);
+            Ok(code_owners
+                .owners_for(&repo_path)
+                .iter()
+                .map(|owner| {
+                    let url = hosting_remote
+                        .as_ref()
+                        .and_then(|remote| remote.code_owner_url(owner));
+                    (owner.clone(), url)
+                })
+                .collect())
+        })
+    }
+
     fn downstream_client(&self) -> Option<(AnyProtoClient, ProjectId)> {
         match &self.state {
             GitStoreState::Local {
@@ -2788,6 +2999,8 @@ impl Repository {
             ),
             job_id: 0,
             active_jobs: Default::default(),
+            merge_base: None,
+            remote_urls: None,
         }
     }
 
@@ -2811,6 +3024,8 @@ impl Repository {
             latest_askpass_id: 0,
             active_jobs: Default::default(),
             job_id: 0,
+            merge_base: None,
+            remote_urls: None,
         }
     }
 
@@ -3796,6 +4011,99 @@ impl Repository {
         })
     }
 
+    /// Returns the name of the remote used to resolve the Git hosting provider.
+    ///
+    /// The `permalink_remote` setting wins, with worktree-local settings taking
+    /// precedence over the global ones. Otherwise, this is the remote of the
+    /// current branch's upstream, falling back to `origin`.
+    pub(crate) fn hosting_remote_name(
+        &self,
+        settings_location: Option<SettingsLocation>,
+        cx: &App,
+    ) -> String {
+        GitHostingProviderSettings::get(settings_location, cx)
+            .permalink_remote
+            .clone()
+            .or_else(|| {
+                self.branch
+                    .as_ref()
+                    .and_then(|branch| branch.upstream.as_ref())
+                    .and_then(|upstream| upstream.remote_name())
+                    .map(|remote_name| remote_name.to_string())
+            })
+            .unwrap_or_else(|| "origin".to_string())
+    }
+
+    /// Returns the names of the remotes to try, in order, when resolving the Git hosting
+    /// provider.
+    ///
+    /// Unless the `permalink_remote` setting names one, the remotes listed in
+    /// `remote_priority` come first, and the first of them on a known provider is used.
+    /// The remote from [`Self::hosting_remote_name`] is tried last.
+    pub(crate) fn hosting_remote_names(
+        &self,
+        settings_location: Option<SettingsLocation>,
+        cx: &App,
+    ) -> Vec<String> {
+        let settings = GitHostingProviderSettings::get(settings_location, cx);
+        let fallback_name = self.hosting_remote_name(settings_location, cx);
+        if settings.permalink_remote.is_some() {
+            return vec![fallback_name];
+        }
+
+        let mut remote_names = Vec::new();
+        for remote_name in settings.remote_priority.iter().chain([&fallback_name]) {
+            if !remote_names.contains(remote_name) {
+                remote_names.push(remote_name.clone());
+            }
+        }
+        remote_names
+    }
+
+    /// Resolves the Git hosting provider and the owner/repo for this repository's remote.
+    pub fn hosting_remote(
+        &mut self,
+        cx: &mut Context<Self>,
+    ) -> oneshot::Receiver<Result<GitRemote>> {
+        let worktree = self.git_store.upgrade().and_then(|git_store| {
+            git_store
+                .read(cx)
+                .worktree_store
+                .read(cx)
+                .find_worktree(&self.work_directory_abs_path, cx)
+        });
+        let settings_location = worktree.as_ref().map(|(worktree, path)| SettingsLocation {
+            worktree_id: worktree.read(cx).id(),
+            path,
+        });
+        let remote_names = self.hosting_remote_names(settings_location, cx);
+        let work_directory_abs_path = self.work_directory_abs_path.clone();
+        self.send_job(None, move |repo, cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => {
+                    let provider_registry =
+                        cx.update(GitHostingProviderRegistry::default_global)?;
+
+                    let (host, remote) = resolve_hosting_remote(
+                        backend.as_ref(),
+                        provider_registry,
+                        &work_directory_abs_path,
+                        &remote_names,
+                    )?;
+
+                    Ok(GitRemote {
+                        host,
+                        owner: remote.owner.to_string(),
+                        repo: remote.repo.to_string(),
+                    })
+                }
+                RepositoryState::Remote { .. } => {
+                    bail!("resolving the Git hosting provider is not supported for remote projects")
+                }
+            }
+        })
+    }
+
     pub fn branches(&mut self) -> oneshot::Receiver<Result<Vec<Branch>>> {
         let id = self.id;
         self.send_job(None, move |repo, _| async move {
@@ -3919,6 +4227,22 @@ impl Repository {
         })
     }
 
+    pub fn remote_branches_containing(
+        &mut self,
+        sha: String,
+    ) -> oneshot::Receiver<Result<Vec<SharedString>>> {
+        self.send_job(None, move |repo, _cx| async move {
+            match repo {
+                RepositoryState::Local { backend, .. } => {
+                    backend.remote_branches_containing(sha).await
+                }
+                RepositoryState::Remote { .. } => {
+                    bail!("listing remote branches is not supported for remote projects")
+                }
+            }
+        })
+    }
+
     pub fn checkpoint(&mut self) -> oneshot::Receiver<Result<GitRepositoryCheckpoint>> {
         self.send_job(None, |repo, _cx| async move {
             match repo {
@@ -4046,8 +4370,17 @@ impl Repository {
                         )
                     })?
                     .await?;
+                let remote_urls = backend.remote_urls();
                 this.update(&mut cx, |this, cx| {
                     this.snapshot = snapshot.clone();
+                    if this.remote_urls.as_ref() != Some(&remote_urls) {
+                        this.remote_urls = Some(remote_urls);
+                        if let Some(provider_registry) = GitHostingProviderRegistry::try_global(cx)
+                        {
+                            provider_registry
+                                .invalidate_resolved_remotes(&this.work_directory_abs_path);
+                        }
+                    }
                     for event in events {
                         cx.emit(event);
                     }
@@ -4197,6 +4530,51 @@ impl Repository {
         cx.spawn(|_: &mut AsyncApp| async move { rx.await? })
     }
 
+    /// Loads the contents of the given path at the merge-base of HEAD and the upstream of the
+    /// current branch. The merge-base is reused until HEAD or the upstream changes.
+    pub fn load_merge_base_text(
+        &mut self,
+        repo_path: RepoPath,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Option<String>>> {
+        let key = MergeBaseKey {
+            head_sha: self.head_commit.as_ref().map(|commit| commit.sha.clone()),
+            upstream: self
+                .branch
+                .as_ref()
+                .and_then(|branch| branch.upstream.clone()),
+        };
+        let cached_merge_base = self
+            .merge_base
+            .as_ref()
+            .filter(|(cached_key, _)| *cached_key == key)
+            .map(|(_, merge_base)| merge_base.clone());
+        let rx = self.send_job(None, move |state, _| async move {
+            match state {
+                RepositoryState::Local { backend, .. } => {
+                    let merge_base = match cached_merge_base {
+                        Some(merge_base) => Some(merge_base),
+                        None => backend.merge_base_with_upstream().await?,
+                    };
+                    let text = match &merge_base {
+                        Some(sha) => backend.load_text_at_commit(sha.clone(), repo_path).await,
+                        None => None,
+                    };
+                    anyhow::Ok((merge_base, text))
+                }
+                RepositoryState::Remote { .. } => {
+                    bail!("diffing against the merge-base is not supported for remote projects")
+                }
+            }
+        });
+        cx.spawn(async move |this, cx| {
+            let (merge_base, text) = rx.await??;
+            let merge_base = merge_base.context("the current branch has no upstream")?;
+            this.update(cx, |this, _| this.merge_base = Some((key, merge_base)))?;
+            Ok(text)
+        })
+    }
+
     fn load_committed_text(
         &mut self,
         buffer_id: BufferId,
@@ -4331,6 +4709,56 @@ impl Repository {
     }
 }
 
+/// Adjusts a selection of zero-based rows so that it refers to lines as Git
+/// hosting providers count them.
+///
+/// Buffers normalize CRLF line endings to `\n`, so their rows already match the
+/// provider's line numbers. The empty row after a final newline is not a line
+/// on the provider, though, so unless `include_trailing_line` is set, a
+/// selection touching it is clamped to the last line.
+pub(crate) fn permalink_selection(
+    snapshot: &text::BufferSnapshot,
+    selection: Range<u32>,
+    include_trailing_line: bool,
+) -> Range<u32> {
+    let max_point = snapshot.max_point();
+    let last_row = if !include_trailing_line && max_point.row > 0 && max_point.column == 0 {
+        max_point.row - 1
+    } else {
+        max_point.row
+    };
+    selection.start.min(last_row)..selection.end.min(last_row)
+}
+
+/// Resolves the Git hosting provider of the first of the given remotes whose URL a known
+/// provider recognizes. When none of them resolves, the last remote's error is returned.
+fn resolve_hosting_remote(
+    backend: &dyn GitRepository,
+    provider_registry: Arc<GitHostingProviderRegistry>,
+    work_directory_abs_path: &Path,
+    remote_names: &[String],
+) -> Result<(Arc<dyn GitHostingProvider + Send + Sync>, ParsedGitRemote)> {
+    let mut last_error = anyhow!("no remote to resolve the Git hosting provider from");
+    for remote_name in remote_names {
+        let resolved = backend
+            .remote_url(remote_name)
+            .with_context(|| format!("remote \"{remote_name}\" not found"))
+            .and_then(|remote_url| {
+                resolve_git_remote_url(
+                    provider_registry.clone(),
+                    work_directory_abs_path,
+                    &remote_url,
+                )
+                .context("parsing Git remote URL")
+            });
+        match resolved {
+            Ok(resolved) => return Ok(resolved),
+            Err(error) => last_error = error,
+        }
+    }
+    Err(last_error)
+}
+
 fn get_permalink_in_rust_registry_src(
     provider_registry: Arc<GitHostingProviderRegistry>,
     path: PathBuf,
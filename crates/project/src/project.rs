@@ -2527,6 +2527,19 @@ impl Project {
         })
     }
 
+    pub fn open_merge_base_diff(
+        &mut self,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Entity<BufferDiff>>> {
+        if self.is_disconnected(cx) {
+            return Task::ready(Err(anyhow!(ErrorCode::Disconnected)));
+        }
+        self.git_store.update(cx, |git_store, cx| {
+            git_store.open_merge_base_diff(buffer, cx)
+        })
+    }
+
     pub fn open_buffer_by_id(
         &mut self,
         id: BufferId,
@@ -4363,6 +4376,27 @@ impl Project {
         })
     }
 
+    pub fn get_blame_url_to_line(
+        &self,
+        buffer: &Entity<Buffer>,
+        line: u32,
+        cx: &mut App,
+    ) -> Task<Result<url::Url>> {
+        self.git_store.update(cx, |git_store, cx| {
+            git_store.get_blame_url_to_line(buffer, line, cx)
+        })
+    }
+
+    pub fn code_owners_for_buffer(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Task<Result<Vec<(String, Option<url::Url>)>>> {
+        self.git_store.update(cx, |git_store, cx| {
+            git_store.code_owners_for_buffer(buffer, cx)
+        })
+    }
+
     // RPC message handlers
 
     async fn handle_unshare_project(
@@ -294,6 +294,11 @@ pub struct GitSettings {
     ///
     /// Default: staged_hollow
     pub hunk_style: Option<GitHunkStyleSetting>,
+    /// Whether and how to show the sequential number of a hunk in its
+    /// controls, so hunks can be referred to by number during reviews.
+    ///
+    /// Default: off
+    pub number_hunks: Option<GitHunkNumbering>,
 }
 
 impl GitSettings {
@@ -336,6 +341,20 @@ pub enum GitHunkStyleSetting {
     UnstagedHollow,
 }
 
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GitHunkNumbering {
+    /// Don't number hunks.
+    #[default]
+    Off,
+    /// Number the hunks shown in the editor, in order across all of its files.
+    Visible,
+    /// Number each hunk by its position among all the hunks of its file,
+    /// including those that aren't shown, so that it keeps its number however
+    /// much of the file the editor shows.
+    All,
+}
+
 #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum GitGutterSetting {
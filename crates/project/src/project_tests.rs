@@ -216,6 +216,145 @@ async fn test_editorconfig_support(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_permalink_remote_worktree_setting(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        GitHostingProviderRegistry::default_global(cx);
+        git_hosting_providers::init(cx);
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            ".zed": {
+                "settings.json": r#"{ "permalink_remote": "fork" }"#
+            },
+            "a.txt": "a",
+        }),
+    )
+    .await;
+
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<git_hosting_providers::GitHostingProviderSettings>(
+                cx,
+                |settings| settings.permalink_remote = Some("upstream".into()),
+            );
+        })
+    });
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    cx.executor().run_until_parked();
+
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let repository = project.read_with(cx, |project, cx| {
+        project.repositories(cx).values().next().unwrap().clone()
+    });
+
+    repository.read_with(cx, |repository, cx| {
+        let location = SettingsLocation {
+            worktree_id,
+            path: Path::new("a.txt"),
+        };
+        assert_eq!(repository.hosting_remote_name(Some(location), cx), "fork");
+        assert_eq!(repository.hosting_remote_name(None, cx), "upstream");
+    });
+}
+
+#[gpui::test]
+async fn test_permalink_remote_priority(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        GitHostingProviderRegistry::default_global(cx);
+        git_hosting_providers::init(cx);
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            ".git": {},
+            "a.txt": "a",
+        }),
+    )
+    .await;
+    fs.set_remote_urls_for_repo(
+        Path::new(path!("/dir/.git")),
+        &[
+            ("origin", "git@gitlab.com:zed-industries/zed.git"),
+            ("upstream", "https://github.com/zed-industries/zed.git"),
+        ],
+    );
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/a.txt"), cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    let set_remote_priority = |remote_priority: &[&str], cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |settings, cx| {
+                settings.update_user_settings::<git_hosting_providers::GitHostingProviderSettings>(
+                    cx,
+                    |settings| {
+                        settings.remote_priority =
+                            remote_priority.iter().map(ToString::to_string).collect()
+                    },
+                );
+            })
+        });
+    };
+    let permalink_host = async |cx: &mut gpui::TestAppContext| {
+        let permalink = project
+            .update(cx, |project, cx| {
+                project.get_permalink_to_line(&buffer, 0..0, cx)
+            })
+            .await
+            .unwrap();
+        permalink.host_str().unwrap().to_string()
+    };
+
+    // The default priority prefers `origin`.
+    assert_eq!(permalink_host(cx).await, "gitlab.com");
+
+    set_remote_priority(&["upstream", "origin"], cx);
+    assert_eq!(permalink_host(cx).await, "github.com");
+
+    // Remotes that don't exist are skipped, falling back to `origin`.
+    set_remote_priority(&["fork"], cx);
+    assert_eq!(permalink_host(cx).await, "gitlab.com");
+}
+
+#[gpui::test]
+fn test_permalink_selection_line_endings(cx: &mut gpui::App) {
+    use crate::git_store::permalink_selection;
+
+    let crlf_with_final_newline = cx.new(|cx| Buffer::local("one\r\ntwo\r\nthree\r\n", cx));
+    let crlf_without_final_newline = cx.new(|cx| Buffer::local("one\r\ntwo\r\nthree", cx));
+    let mixed_with_final_newline = cx.new(|cx| Buffer::local("one\ntwo\r\nthree\n", cx));
+
+    for buffer in [&crlf_with_final_newline, &mixed_with_final_newline] {
+        let snapshot = buffer.read(cx).snapshot();
+        assert_eq!(permalink_selection(&snapshot, 1..1, false), 1..1);
+        assert_eq!(permalink_selection(&snapshot, 1..3, false), 1..2);
+        assert_eq!(permalink_selection(&snapshot, 3..3, false), 2..2);
+        assert_eq!(permalink_selection(&snapshot, 1..3, true), 1..3);
+    }
+
+    let snapshot = crlf_without_final_newline.read(cx).snapshot();
+    assert_eq!(permalink_selection(&snapshot, 0..2, false), 0..2);
+    assert_eq!(permalink_selection(&snapshot, 0..2, true), 0..2);
+}
+
 #[gpui::test]
 async fn test_git_provider_project_setting(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -6889,6 +7028,94 @@ async fn test_uncommitted_diff_for_buffer(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_merge_base_diff_for_buffer(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let merge_base_contents = r#"
+        fn main() {
+            println!("hello world");
+        }
+    "#
+    .unindent();
+    let committed_contents = r#"
+        fn main() {
+            println!("goodbye world");
+        }
+    "#
+    .unindent();
+    let file_contents = r#"
+        // print goodbye
+        fn main() {
+            println!("goodbye world");
+        }
+    "#
+    .unindent();
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "src": {
+                "main.rs": file_contents,
+            }
+        }),
+    )
+    .await;
+    fs.set_head_for_repo(
+        Path::new("/dir/.git"),
+        &[("src/main.rs".into(), committed_contents)],
+        "deadbeef",
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/src/main.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    // Without an upstream, there is no merge-base to diff against.
+    let diff = project
+        .update(cx, |project, cx| {
+            project.open_merge_base_diff(buffer.clone(), cx)
+        })
+        .await;
+    assert!(diff.is_err());
+
+    fs.set_merge_base_for_repo(
+        Path::new("/dir/.git"),
+        &[("src/main.rs".into(), merge_base_contents)],
+        "cafebabe",
+    );
+    let diff = project
+        .update(cx, |project, cx| {
+            project.open_merge_base_diff(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    diff.update(cx, |diff, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        assert_hunks(
+            diff.hunks(&snapshot, cx),
+            &snapshot,
+            &diff.base_text_string().unwrap(),
+            &[
+                (0..1, "", "// print goodbye\n", DiffHunkStatus::added_none()),
+                (
+                    2..3,
+                    "    println!(\"hello world\");\n",
+                    "    println!(\"goodbye world\");\n",
+                    DiffHunkStatus::modified_none(),
+                ),
+            ],
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_staging_hunks(cx: &mut gpui::TestAppContext) {
     use DiffHunkSecondaryStatus::*;
@@ -44,6 +44,25 @@ impl ReqwestClient {
         Ok(client.into())
     }
 
+    /// Builds a client that verifies server certificates according to `options` rather than
+    /// only against the platform's roots. It does not use the app's proxy.
+    pub fn user_agent_and_certificates(
+        agent: &str,
+        options: &CertificateOptions,
+    ) -> anyhow::Result<Self> {
+        let mut map = HeaderMap::new();
+        map.insert(http::header::USER_AGENT, HeaderValue::from_str(agent)?);
+        let mut client = Self::builder()
+            .default_headers(map)
+            .danger_accept_invalid_certs(options.accept_invalid_certs);
+        if let Some(ca_bundle) = options.ca_bundle.as_deref() {
+            for certificate in reqwest::Certificate::from_pem_bundle(ca_bundle)? {
+                client = client.add_root_certificate(certificate);
+            }
+        }
+        Ok(client.build()?.into())
+    }
+
     pub fn proxy_and_user_agent(proxy: Option<Url>, agent: &str) -> anyhow::Result<Self> {
         let mut map = HeaderMap::new();
         map.insert(http::header::USER_AGENT, HeaderValue::from_str(agent)?);
@@ -76,6 +95,18 @@ impl ReqwestClient {
     }
 }
 
+/// Changes to how a client verifies the certificates of the servers it connects to.
+#[derive(Debug, Default, Clone)]
+pub struct CertificateOptions {
+    /// Whether to accept any certificate, including self-signed and expired ones.
+    ///
+    /// This lets anyone able to intercept a connection impersonate the server, so it should
+    /// only be enabled for hosts on trusted networks.
+    pub accept_invalid_certs: bool,
+    /// PEM-encoded certificate authorities to trust in addition to the platform's roots.
+    pub ca_bundle: Option<Vec<u8>>,
+}
+
 impl From<reqwest::Client> for ReqwestClient {
     fn from(client: reqwest::Client) -> Self {
         let handle = tokio::runtime::Handle::try_current().unwrap_or_else(|_| {
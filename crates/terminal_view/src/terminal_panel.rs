@@ -998,7 +998,7 @@ pub fn new_terminal_pane(
         let buffer_search_bar = cx.new(|cx| {
             search::BufferSearchBar::new(Some(project.read(cx).languages().clone()), window, cx)
         });
-        let breadcrumbs = cx.new(|_| Breadcrumbs::new());
+        let breadcrumbs = cx.new(Breadcrumbs::new);
         pane.toolbar().update(cx, |toolbar, cx| {
             toolbar.add_item(buffer_search_bar, window, cx);
             toolbar.add_item(breadcrumbs, window, cx);
@@ -1709,11 +1709,17 @@ impl Item for TerminalView {
         }
     }
 
+    fn has_breadcrumbs(&self, _: &App) -> bool {
+        true
+    }
+
     fn breadcrumbs(&self, _: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
         Some(vec![BreadcrumbText {
             text: self.terminal().read(cx).breadcrumb_text.clone(),
             highlights: None,
             font: None,
+            type_hint: None,
+            source_range: None,
         }])
     }
 
@@ -16,26 +16,27 @@ use crate::application_menu::{
 };
 
 use auto_update::AutoUpdateStatus;
+use breadcrumbs::BreadcrumbsSettings;
 use call::ActiveCall;
 use client::{Client, UserStore};
 use gpui::{
     Action, AnyElement, App, Context, Corner, Element, Entity, InteractiveElement, IntoElement,
-    MouseButton, ParentElement, Render, StatefulInteractiveElement, Styled, Subscription,
+    MouseButton, ParentElement, Render, StatefulInteractiveElement, Styled, Subscription, Task,
     WeakEntity, Window, actions, div,
 };
 use onboarding_banner::OnboardingBanner;
 use project::Project;
 use rpc::proto;
-use settings::Settings as _;
-use std::sync::Arc;
+use settings::{Settings as _, SettingsStore};
+use std::{sync::Arc, time::Duration};
 use theme::ActiveTheme;
-use title_bar_settings::TitleBarSettings;
+use title_bar_settings::{TitleBarSettings, WindowTitleBreadcrumb};
 use ui::{
     Avatar, Button, ButtonLike, ButtonStyle, ContextMenu, Icon, IconName, IconSize,
     IconWithIndicator, Indicator, PopoverMenu, Tooltip, h_flex, prelude::*,
 };
 use util::ResultExt;
-use workspace::{Workspace, notifications::NotifyResultExt};
+use workspace::{Workspace, item::ItemEvent, notifications::NotifyResultExt};
 use zed_actions::{OpenRecent, OpenRemote};
 
 pub use onboarding_banner::restore_banner;
@@ -46,6 +47,7 @@ pub use stories::*;
 const MAX_PROJECT_NAME_LENGTH: usize = 40;
 const MAX_BRANCH_NAME_LENGTH: usize = 40;
 const MAX_SHORT_SHA_LENGTH: usize = 8;
+const WINDOW_TITLE_BREADCRUMB_DEBOUNCE: Duration = Duration::from_millis(250);
 
 actions!(collab, [ToggleUserMenu, ToggleProjectMenu, SwitchBranch]);
 
@@ -116,6 +118,8 @@ pub struct TitleBar {
     workspace: WeakEntity<Workspace>,
     application_menu: Option<Entity<ApplicationMenu>>,
     _subscriptions: Vec<Subscription>,
+    active_item_subscription: Option<Subscription>,
+    update_window_title_breadcrumb_task: Option<Task<()>>,
     banner: Entity<OnboardingBanner>,
 }
 
@@ -224,6 +228,20 @@ impl TitleBar {
         subscriptions.push(cx.observe(&active_call, |this, _, cx| this.active_call_changed(cx)));
         subscriptions.push(cx.observe_window_activation(window, Self::window_activation_changed));
         subscriptions.push(cx.observe(&user_store, |_, _, cx| cx.notify()));
+        subscriptions.push(cx.subscribe_in(
+            &workspace.weak_handle().upgrade().unwrap(),
+            window,
+            |this, _, event, window, cx| {
+                if let workspace::Event::ActiveItemChanged = event {
+                    this.active_item_changed(window, cx);
+                }
+            },
+        ));
+        subscriptions.push(
+            cx.observe_global_in::<SettingsStore>(window, |this, window, cx| {
+                this.update_window_title_breadcrumb(window, cx)
+            }),
+        );
 
         let banner = cx.new(|cx| {
             OnboardingBanner::new(
@@ -246,10 +264,91 @@ impl TitleBar {
             user_store,
             client,
             _subscriptions: subscriptions,
+            active_item_subscription: None,
+            update_window_title_breadcrumb_task: None,
             banner,
         }
     }
 
+    fn active_item_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let active_item = self
+            .workspace
+            .upgrade()
+            .and_then(|workspace| workspace.read(cx).active_item(cx));
+        let this = cx.entity().downgrade();
+        self.active_item_subscription = active_item.map(|item| {
+            item.subscribe_to_item_events(
+                window,
+                cx,
+                Box::new(move |event, window, cx| {
+                    if let ItemEvent::UpdateBreadcrumbs = event {
+                        this.update(cx, |this, cx| {
+                            this.schedule_window_title_breadcrumb_update(window, cx)
+                        })
+                        .ok();
+                    }
+                }),
+            )
+        });
+        self.update_window_title_breadcrumb_task = None;
+        self.update_window_title_breadcrumb(window, cx);
+    }
+
+    /// Debounces breadcrumb changes, as they happen whenever the cursor moves.
+    fn schedule_window_title_breadcrumb_update(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if TitleBarSettings::get_global(cx).include_breadcrumb == WindowTitleBreadcrumb::Off {
+            return;
+        }
+
+        self.update_window_title_breadcrumb_task =
+            Some(cx.spawn_in(window, async move |this, cx| {
+                cx.background_executor()
+                    .timer(WINDOW_TITLE_BREADCRUMB_DEBOUNCE)
+                    .await;
+                this.update_in(cx, |this, window, cx| {
+                    this.update_window_title_breadcrumb(window, cx)
+                })
+                .ok();
+            }));
+    }
+
+    fn update_window_title_breadcrumb(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        let include_breadcrumb = TitleBarSettings::get_global(cx).include_breadcrumb;
+        let breadcrumb = workspace
+            .read(cx)
+            .active_item(cx)
+            .filter(|_| include_breadcrumb != WindowTitleBreadcrumb::Off)
+            .and_then(|item| item.breadcrumbs(cx.theme(), cx))
+            .and_then(|segments| {
+                // The first segment is the item's path, and the rest are its symbols.
+                let symbols = segments
+                    .into_iter()
+                    .skip(1)
+                    .map(|segment| segment.text.replace('\n', " "))
+                    .collect::<Vec<_>>();
+                let separator = &BreadcrumbsSettings::get_global(cx).separator;
+                let breadcrumb = match include_breadcrumb {
+                    WindowTitleBreadcrumb::Off => None,
+                    WindowTitleBreadcrumb::InnermostSymbol => symbols.last().cloned(),
+                    WindowTitleBreadcrumb::Symbols => {
+                        (!symbols.is_empty()).then(|| symbols.join(&format!(" {separator} ")))
+                    }
+                }?;
+                Some(format!("{separator} {breadcrumb}"))
+            });
+        workspace.update(cx, |workspace, cx| {
+            workspace.set_window_title_breadcrumb(breadcrumb, window, cx)
+        });
+    }
+
     fn render_ssh_project_host(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
         let options = self.project.read(cx).ssh_connection_options(cx)?;
         let host: SharedString = options.connection_string().into();
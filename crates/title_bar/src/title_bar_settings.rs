@@ -11,6 +11,20 @@ pub struct TitleBarSettings {
     pub show_branch_name: bool,
     pub show_project_items: bool,
     pub show_sign_in: bool,
+    pub include_breadcrumb: WindowTitleBreadcrumb,
+}
+
+/// Which breadcrumb symbols are included in the window title.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WindowTitleBreadcrumb {
+    /// Don't include any symbols.
+    #[default]
+    Off,
+    /// Include the innermost symbol containing the cursor.
+    InnermostSymbol,
+    /// Include every symbol containing the cursor.
+    Symbols,
 }
 
 #[derive(Copy, Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
@@ -39,6 +53,10 @@ pub struct TitleBarSettingsContent {
     ///
     /// Default: true
     pub show_sign_in: Option<bool>,
+    /// Which breadcrumb symbols to include after the file name in the window title.
+    ///
+    /// Default: off
+    pub include_breadcrumb: Option<WindowTitleBreadcrumb>,
 }
 
 impl Settings for TitleBarSettings {
@@ -219,6 +219,11 @@ pub struct BreadcrumbText {
     pub text: String,
     pub highlights: Option<Vec<(Range<usize>, HighlightStyle)>>,
     pub font: Option<Font>,
+    /// The type of the symbol, such as a function's return type or a field's type, if known.
+    pub type_hint: Option<String>,
+    /// The range of the item's buffer that the segment was derived from, such as the range
+    /// of a symbol, if it has one.
+    pub source_range: Option<Range<language::Anchor>>,
 }
 
 #[derive(Clone, Copy, Default, Debug)]
@@ -387,10 +392,22 @@ pub trait Item: Focusable + EventEmitter<Self::Event> + Render + Sized {
         ToolbarItemLocation::Hidden
     }
 
+    /// Returns whether the item has breadcrumbs, which is cheaper to tell than computing
+    /// them with [`Item::breadcrumbs`].
+    fn has_breadcrumbs(&self, _cx: &App) -> bool {
+        false
+    }
+
     fn breadcrumbs(&self, _theme: &Theme, _cx: &App) -> Option<Vec<BreadcrumbText>> {
         None
     }
 
+    /// A short, type-specific description of the item's contents (e.g. image dimensions),
+    /// shown after the breadcrumbs of items that have no symbols to display.
+    fn breadcrumb_metadata(&self, _cx: &App) -> Option<SharedString> {
+        None
+    }
+
     fn added_to_workspace(
         &mut self,
         _workspace: &mut Workspace,
@@ -571,7 +588,9 @@ pub trait ItemHandle: 'static + Send {
     ) -> gpui::Subscription;
     fn to_searchable_item_handle(&self, cx: &App) -> Option<Box<dyn SearchableItemHandle>>;
     fn breadcrumb_location(&self, cx: &App) -> ToolbarItemLocation;
+    fn has_breadcrumbs(&self, cx: &App) -> bool;
     fn breadcrumbs(&self, theme: &Theme, cx: &App) -> Option<Vec<BreadcrumbText>>;
+    fn breadcrumb_metadata(&self, cx: &App) -> Option<SharedString>;
     fn show_toolbar(&self, cx: &App) -> bool;
     fn pixel_position_of_cursor(&self, cx: &App) -> Option<Point<Pixels>>;
     fn downgrade_item(&self) -> Box<dyn WeakItemHandle>;
@@ -1057,10 +1076,18 @@ impl<T: Item> ItemHandle for Entity<T> {
         self.read(cx).breadcrumb_location(cx)
     }
 
+    fn has_breadcrumbs(&self, cx: &App) -> bool {
+        self.read(cx).has_breadcrumbs(cx)
+    }
+
     fn breadcrumbs(&self, theme: &Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
         self.read(cx).breadcrumbs(theme, cx)
     }
 
+    fn breadcrumb_metadata(&self, cx: &App) -> Option<SharedString> {
+        self.read(cx).breadcrumb_metadata(cx)
+    }
+
     fn show_toolbar(&self, cx: &App) -> bool {
         self.read(cx).show_toolbar()
     }
@@ -271,6 +271,9 @@ action_as!(project_symbols, ToggleProjectSymbols as Toggle);
 pub struct ToggleFileFinder {
     #[serde(default)]
     pub separate_history: bool,
+    /// The text to prefill the file finder's query with.
+    #[serde(default)]
+    pub query: Option<String>,
 }
 
 impl_action_as!(file_finder, ToggleFileFinder as Toggle);
@@ -946,6 +949,7 @@ pub struct Workspace {
     follower_states: HashMap<CollaboratorId, FollowerState>,
     last_leaders_by_pane: HashMap<WeakEntity<Pane>, CollaboratorId>,
     window_edited: bool,
+    window_title_breadcrumb: Option<String>,
     dirty_items: HashMap<EntityId, Subscription>,
     active_call: Option<(Entity<ActiveCall>, Vec<Subscription>)>,
     leader_updates_tx: mpsc::UnboundedSender<(PeerId, proto::UpdateFollowers)>,
@@ -1277,6 +1281,7 @@ impl Workspace {
             last_leaders_by_pane: Default::default(),
             dispatching_keystrokes: Default::default(),
             window_edited: false,
+            window_title_breadcrumb: None,
             dirty_items: Default::default(),
             active_call,
             database_id: workspace_id,
@@ -4195,6 +4200,10 @@ impl Workspace {
             if let Some(filename) = filename {
                 title.push_str(" — ");
                 title.push_str(filename.as_ref());
+                if let Some(breadcrumb) = &self.window_title_breadcrumb {
+                    title.push(' ');
+                    title.push_str(breadcrumb);
+                }
             }
         }
 
@@ -4207,6 +4216,20 @@ impl Workspace {
         window.set_window_title(&title);
     }
 
+    /// Sets the breadcrumb shown after the active item's file name in the window title,
+    /// which starts with the separator that precedes it.
+    pub fn set_window_title_breadcrumb(
+        &mut self,
+        breadcrumb: Option<String>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        if self.window_title_breadcrumb != breadcrumb {
+            self.window_title_breadcrumb = breadcrumb;
+            self.update_window_title(window, cx);
+        }
+    }
+
     fn update_window_edited(&mut self, window: &mut Window, cx: &mut App) {
         let is_edited = !self.project.read(cx).is_disconnected(cx) && !self.dirty_items.is_empty();
         if is_edited != self.window_edited {
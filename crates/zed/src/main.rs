@@ -563,6 +563,7 @@ pub fn main() {
         ui_prompt::init(cx);
 
         go_to_line::init(cx);
+        breadcrumbs::init(cx);
         file_finder::init(cx);
         tab_switcher::init(cx);
         outline::init(cx);
@@ -479,6 +479,8 @@ fn initialize_panels(
             workspace_handle.clone(),
             cx.clone(),
         );
+        let breadcrumbs_panel =
+            breadcrumbs::BreadcrumbsPanel::load(workspace_handle.clone(), cx.clone());
         let debug_panel = DebugPanel::load(workspace_handle.clone(), cx);
 
         let (
@@ -489,6 +491,7 @@ fn initialize_panels(
             channels_panel,
             chat_panel,
             notification_panel,
+            breadcrumbs_panel,
             debug_panel,
         ) = futures::try_join!(
             project_panel,
@@ -498,6 +501,7 @@ fn initialize_panels(
             channels_panel,
             chat_panel,
             notification_panel,
+            breadcrumbs_panel,
             debug_panel,
         )?;
 
@@ -509,6 +513,7 @@ fn initialize_panels(
             workspace.add_panel(channels_panel, window, cx);
             workspace.add_panel(chat_panel, window, cx);
             workspace.add_panel(notification_panel, window, cx);
+            workspace.add_panel(breadcrumbs_panel, window, cx);
             workspace.add_panel(debug_panel, window, cx);
         })?;
 
@@ -893,7 +898,7 @@ fn initialize_pane(
         pane.toolbar().update(cx, |toolbar, cx| {
             let multibuffer_hint = cx.new(|_| MultibufferHint::new());
             toolbar.add_item(multibuffer_hint, window, cx);
-            let breadcrumbs = cx.new(|_| Breadcrumbs::new());
+            let breadcrumbs = cx.new(Breadcrumbs::new);
             toolbar.add_item(breadcrumbs, window, cx);
             let buffer_search_bar = cx.new(|cx| {
                 search::BufferSearchBar::new(
@@ -4287,6 +4292,7 @@ mod tests {
             command_palette::init(cx);
             language::init(cx);
             editor::init(cx);
+            breadcrumbs::init(cx);
             collab_ui::init(&app_state, cx);
             git_ui::init(cx);
             project_panel::init(cx);